@@ -1,3 +1,9 @@
+import { createHash } from 'node:crypto';
+import { mkdtempSync, writeFileSync } from 'node:fs';
+import { createServer } from 'node:http';
+import { tmpdir } from 'node:os';
+import { join } from 'node:path';
+
 import test from 'ava'
 
 import { SpellChecker } from '../index.js';
@@ -52,3 +58,714 @@ test('can handle utf-8 characters just fine', (t) => {
     t.fail(`SpellChecker failed with error: ${error.message}`);
   }
 })
+
+test('can get dictionary info', (t) => {
+  const spellchecker = new SpellChecker();
+  const info = spellchecker.dictionaryInfo();
+
+  t.true(['system', 'session', 'overlay'].includes(info.storage));
+  t.is(typeof info.persistent, 'boolean');
+
+  // Sandboxed mode never writes to the system dictionary
+  const sandboxed = new SpellChecker({ sandboxed: true });
+  t.not(sandboxed.dictionaryInfo().storage, 'system');
+})
+
+test('forked spell checker has its own session', (t) => {
+  const spellchecker = new SpellChecker({ sandboxed: true });
+  const forked = spellchecker.fork();
+
+  forked.addWord('snetences');
+  t.deepEqual(forked.checkAndSuggest('This is a invalid snetences.'), []);
+  const results = spellchecker.checkAndSuggest('This is a invalid snetences.');
+  t.is(results.length, 1);
+  t.is(results[0].word, 'snetences');
+  t.is(results[0].start, 18);
+})
+
+test('native handle requires opt-in', (t) => {
+  const spellchecker = new SpellChecker();
+  t.throws(() => spellchecker.unsafeNativeHandle());
+
+  const allowed = new SpellChecker({ allowNativeHandle: true });
+  t.truthy(allowed.unsafeNativeHandle());
+})
+
+test('results can be serialized to JSON', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const results = spellchecker.checkAndSuggest('This is a invalid snetences.');
+  t.deepEqual(JSON.parse(JSON.stringify(results)), results);
+  t.deepEqual(Object.keys(results[0]), ['start', 'end', 'word', 'suggestions']);
+})
+
+test('can add and remove words in the background', async (t) => {
+  const spellchecker = new SpellChecker({ sandboxed: true });
+
+  const progress = [];
+  await spellchecker.addWordsAsync(['snetences', 'ejaan'], (done, total) => {
+    progress.push([done, total]);
+  });
+  t.deepEqual(spellchecker.checkAndSuggest('This is a invalid snetences.'), []);
+
+  await spellchecker.removeWordsAsync(['snetences']);
+  t.is(spellchecker.checkAndSuggest('This is a invalid snetences.').length, 1);
+
+  // Progress callbacks are asynchronous, wait for them to be delivered
+  await new Promise((resolve) => setImmediate(resolve));
+  t.deepEqual(progress, [[2, 2]]);
+})
+
+test('can check in the background', async (t) => {
+  const spellchecker = new SpellChecker();
+
+  const results = await spellchecker.checkAndSuggestAsync('This is a invalid snetences.');
+  t.deepEqual(results, spellchecker.checkAndSuggest('This is a invalid snetences.'));
+})
+
+test('can check UTF-16 strings directly', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const sentences = '“As I said, this should work properly!” with a mistake snetences.';
+  t.deepEqual(spellchecker.checkAndSuggestUtf16(sentences), spellchecker.checkAndSuggest(sentences));
+
+  // Offsets are in UTF-16 code units, the same as JavaScript strings
+  const results = spellchecker.checkAndSuggestUtf16('😀 snetences');
+  t.is(results.length, 1);
+  t.is(results[0].start, 3);
+  t.is(results[0].end, 11);
+
+  // The emoji shortcodes and placeholders are skipped the same way
+  const masked = 'Hello :smiel: {naem} snetences wrod';
+  const options = { skipPlaceholders: true, maxResults: 1 };
+  t.deepEqual(spellchecker.checkAndSuggestUtf16(masked, options), spellchecker.checkAndSuggest(masked, options));
+})
+
+test('common words fast path gives the same results', (t) => {
+  const spellchecker = new SpellChecker({ commonWords: true });
+
+  t.true(spellchecker.checkWord('the'));
+  t.deepEqual(spellchecker.checkAndSuggest('This is the way.'), []);
+  t.is(spellchecker.checkAndSuggest('This is a invalid snetences.').length, 1);
+  // The mixed casings are still checked by the native spell checker
+  t.false(spellchecker.checkWord('THe'));
+  t.is(spellchecker.checkAndSuggest('THe way').length, 1);
+})
+
+test('known words are forgotten when removing words', (t) => {
+  const spellchecker = new SpellChecker({ sandboxed: true, knownWords: true });
+
+  spellchecker.addWord('snetences');
+  t.deepEqual(spellchecker.checkAndSuggest('This is a invalid snetences.'), []);
+  // Checked again from the known words
+  t.deepEqual(spellchecker.checkAndSuggest('This is a invalid snetences.'), []);
+
+  spellchecker.removeWord('snetences');
+  t.is(spellchecker.checkAndSuggest('This is a invalid snetences.').length, 1);
+})
+
+test('can stop checking after max results', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const sentences = 'Tihs sentnce has mnay mistkaes.';
+  t.true(spellchecker.checkAndSuggest(sentences).length > 2);
+
+  const results = spellchecker.checkAndSuggest(sentences, { maxResults: 2 });
+  t.is(results.length, 2);
+  t.is(results[0].word, 'Tihs');
+})
+
+test('can check for diagnostics', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const diagnostics = spellchecker.check('This is a invalid snetences.');
+  t.is(diagnostics.length, 1);
+  t.is(diagnostics[0].category, 'spelling');
+  t.is(diagnostics[0].severity, 'warning');
+  t.is(typeof diagnostics[0].language, 'string');
+  t.deepEqual(diagnostics[0].rejectedBy, [diagnostics[0].language]);
+
+  // The older shape is still available
+  const { category, severity, language, rejectedBy, ...suggestion } = diagnostics[0];
+  t.deepEqual(spellchecker.checkAndSuggest('This is a invalid snetences.'), [suggestion]);
+})
+
+test('can check pre-tokenized input', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const diagnostics = spellchecker.checkTokens([
+    { word: 'valid', start: 10 },
+    { word: 'snetences', start: 18 },
+  ]);
+  t.is(diagnostics.length, 1);
+  t.is(diagnostics[0].word, 'snetences');
+  t.is(diagnostics[0].start, 18);
+  t.is(diagnostics[0].end, 26);
+
+  // A token is reported as a whole, even when the native spell checker would split it
+  const identifiers = spellchecker.checkTokens([{ word: 'snetences_wrod', start: 4 }]);
+  t.is(identifiers.length, 1);
+  t.is(identifiers[0].word, 'snetences_wrod');
+  t.is(identifiers[0].start, 4);
+  t.is(identifiers[0].end, 17);
+})
+
+test('can tokenize text', (t) => {
+  const spellchecker = new SpellChecker();
+
+  t.deepEqual(spellchecker.tokenize("😀 Don't snetences."), [
+    { start: 3, end: 7, word: "Don't" },
+    { start: 9, end: 17, word: 'snetences' },
+  ]);
+})
+
+test('can customize the word characters', (t) => {
+  const spellchecker = new SpellChecker({ wordBreak: { joinCharacters: "_.'" } });
+
+  t.deepEqual(spellchecker.tokenize('call my_module.run_fast now'), [
+    { start: 0, end: 3, word: 'call' },
+    { start: 5, end: 22, word: 'my_module.run_fast' },
+    { start: 24, end: 26, word: 'now' },
+  ]);
+
+  const results = spellchecker.checkAndSuggest('This is a invalid snetences.');
+  t.is(results.length, 1);
+  t.is(results[0].start, 18);
+  t.is(results[0].end, 26);
+
+  // Forked spell checkers keep the same word breaking
+  t.is(spellchecker.fork().tokenize('my_module').length, 1);
+})
+
+test('can check JSON and YAML documents', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const json = '{"snetences": "This is a invalid snetences.", "count": 12}';
+  const results = spellchecker.checkAndSuggest(json, { format: 'json' });
+  t.is(results.length, 1);
+  t.is(results[0].start, json.lastIndexOf('snetences'));
+  t.is(spellchecker.checkAndSuggest(json, { format: 'json', checkKeys: true }).length, 2);
+
+  const yaml = '# snetences\ntitle: This is a invalid snetences.\ncount: 12\n';
+  const diagnostics = spellchecker.check(yaml, { format: 'yaml' });
+  t.is(diagnostics.length, 1);
+  t.is(diagnostics[0].start, yaml.lastIndexOf('snetences'));
+
+  t.throws(() => spellchecker.check(json, { format: 'toml' }));
+})
+
+test('can check gettext catalogs', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const catalog = [
+    '# snetences',
+    'msgid "Invalid snetences %s"',
+    'msgstr "This is a invalid snetences %s"',
+    '',
+  ].join('\n');
+  const results = spellchecker.checkAndSuggest(catalog, { format: 'gettext' });
+  t.is(results.length, 1);
+  t.is(results[0].start, catalog.lastIndexOf('snetences'));
+})
+
+test('can check subtitles', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const subtitles = '1\n00:00:01,000 --> 00:00:02,500\nThis is a <i>snetences</i>\n';
+  const results = spellchecker.checkAndSuggest(subtitles, { format: 'srt' });
+  t.is(results.length, 1);
+  t.is(results[0].start, subtitles.indexOf('snetences'));
+})
+
+test('can check ASS subtitles without the override tags', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const subtitles = [
+    '[Events]',
+    'Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text',
+    'Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\an8}This is a {\\k20}sne{\\k30}tences',
+    '',
+  ].join('\n');
+  const results = spellchecker.checkAndSuggest(subtitles, { format: 'ass' });
+  t.is(results.length, 1);
+  t.is(results[0].word, 'snetences');
+  t.is(results[0].start, subtitles.indexOf('sne{'));
+  t.is(results[0].end, subtitles.lastIndexOf('s'));
+})
+
+test('can check chat messages', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const message = 'Hi <@123> <:pepega:456> run `cargo tset` on [spoiler]snetences[/spoiler]';
+  const results = spellchecker.checkAndSuggest(message, { format: 'chat' });
+  t.is(results.length, 1);
+  t.is(results[0].word, 'snetences');
+  t.is(results[0].start, message.indexOf('snetences'));
+})
+
+test('skips emoji shortcodes', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const sentences = 'Nice :thumbsup: :partyparrot: 👨‍👩‍👧 snetences';
+  const results = spellchecker.checkAndSuggest(sentences);
+  t.is(results.length, 1);
+  t.is(results[0].word, 'snetences');
+  t.is(results[0].start, sentences.indexOf('snetences'));
+
+  t.true(spellchecker.checkAndSuggest(sentences, { skipEmoji: false }).length > 1);
+})
+
+test('can skip template placeholders', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const template = 'Dear {{fisrtName}}, you have %(cnt)d new snetences in [[foldr]]';
+  t.true(spellchecker.checkAndSuggest(template).length > 1);
+
+  const results = spellchecker.checkAndSuggest(template, {
+    skipPlaceholders: true,
+    placeholders: [{ open: '[[', close: ']]' }],
+  });
+  t.is(results.length, 1);
+  t.is(results[0].start, template.indexOf('snetences'));
+
+  t.throws(() => spellchecker.check(template, { placeholders: [{ open: '', close: '' }] }));
+})
+
+test('can check commit messages', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const message = 'fix(tokenizr): Handle snetences\n\nFixes #12, see `chek_word`.\n\nSigned-off-by: Persn <persn@example.com>\n';
+  const results = spellchecker.checkAndSuggest(message, { format: 'commit' });
+  t.is(results.length, 1);
+  t.is(results[0].start, message.indexOf('snetences'));
+})
+
+test('can check editor blocks', (t) => {
+  const spellchecker = new SpellChecker();
+
+  // A heading at 0, and a paragraph at 8 with an inline image after "A "
+  const blocks = [
+    { text: 'Title', pos: 1 },
+    { text: 'A snetences', pos: 9, segments: [{ offset: 2, pos: 12 }] },
+  ];
+  const results = spellchecker.checkBlocks(blocks);
+  t.is(results.length, 1);
+  t.is(results[0].block, 1);
+  t.is(results[0].word, 'snetences');
+  t.is(results[0].from, 12);
+  t.is(results[0].to, 21);
+})
+
+test('can analyze unknown words', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const results = spellchecker.analyzeUnknownWords([
+    'The ejaan crate uses ejaan internally.',
+    'Install ejaan, then check the snetences.',
+  ]);
+  t.is(results[0].word, 'ejaan');
+  t.is(results[0].count, 3);
+  t.is(results[0].documents, 2);
+  t.true(results[0].contexts.length <= 3);
+  t.true(results.some((result) => result.word === 'snetences' && result.count === 1));
+})
+
+test('proposes frequently flagged words', (t) => {
+  const spellchecker = new SpellChecker({ proposeAdditions: 2 });
+
+  const text = 'The napi binding for ejaan.\n\nBuild napi with cargo, then test napi.';
+  // Re-checking the same text does not count the same occurrences again
+  for (let index = 0; index < 3; index++) {
+    spellchecker.checkAndSuggest(text);
+  }
+  const proposals = spellchecker.proposedAdditions();
+  t.true(proposals.some((proposal) => proposal.word === 'napi' && proposal.count === 3));
+  t.false(proposals.some((proposal) => proposal.word === 'ejaan'));
+
+  spellchecker.dismissProposal('napi');
+  t.false(spellchecker.proposedAdditions().some((proposal) => proposal.word === 'napi'));
+})
+
+test('can correct the text one word at a time', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const session = spellchecker.correctionSession('A tset of snetences and tset again');
+  t.is(session.remaining(), 3);
+  t.is(session.current().word, 'tset');
+
+  session.accept('test');
+  t.is(session.current().word, 'snetences');
+  session.accept('sentences');
+  session.ignoreAll();
+  t.is(session.current(), null);
+  t.is(session.text(), 'A test of sentences and tset again');
+})
+
+test('caches the unchanged paragraphs', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const text = 'A first snetences.\n\nA second paragraph.';
+  t.is(spellchecker.checkParagraphs(text).length, 1);
+  const edited = 'A first snetences.\n\nA second paragrpah.';
+  const results = spellchecker.checkParagraphs(edited);
+  t.is(results.length, 2);
+  t.is(results[1].start, edited.indexOf('paragrpah'));
+
+  const stats = spellchecker.cacheStats();
+  t.is(stats.hits, 1);
+  t.is(stats.misses, 3);
+
+  spellchecker.clearCache();
+  t.is(spellchecker.cacheStats().entries, 0);
+})
+
+test('can check files', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const path = join(mkdtempSync(join(tmpdir(), 'ejaan-')), 'messages.json');
+  writeFileSync(path, '{ "snetences": "A tset" }');
+  const results = spellchecker.checkFile(path);
+  t.is(results.length, 1);
+  t.is(results[0].word, 'tset');
+})
+
+test('can check files with a persistent cache', async (t) => {
+  const spellchecker = new SpellChecker();
+
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const first = join(directory, 'first.txt');
+  const second = join(directory, 'second.txt');
+  writeFileSync(first, 'A tset');
+  writeFileSync(second, 'All good');
+  const cacheLocation = join(directory, '.ejaancache');
+
+  const report = await spellchecker.checkFiles([first, second, join(directory, 'missing.txt')], { cacheLocation });
+  t.is(report.errorCount, 1);
+  t.is(report.files[0].diagnostics[0].word, 'tset');
+  t.false(report.files[0].cached);
+  t.truthy(report.files[2].error);
+
+  writeFileSync(second, 'Now a snetences');
+  const rerun = await spellchecker.checkFiles([first, second], { cacheLocation });
+  t.true(rerun.files[0].cached);
+  t.false(rerun.files[1].cached);
+  t.is(rerun.errorCount, 2);
+
+  // The cache is keyed by the added words too
+  spellchecker.addWord('tset');
+  const added = await spellchecker.checkFiles([first, second], { cacheLocation });
+  spellchecker.removeWord('tset');
+  t.false(added.files[0].cached);
+  t.is(added.files[0].diagnostics.length, 0);
+})
+
+test('can check files in parallel', async (t) => {
+  const spellchecker = new SpellChecker();
+
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const paths = ['A tset', 'All good', 'Two snetences, tset', 'Fine'].map((content, index) => {
+    const path = join(directory, `${index}.txt`);
+    writeFileSync(path, content);
+    return path;
+  });
+
+  const report = await spellchecker.checkFiles(paths, { concurrency: 3 });
+  t.deepEqual(
+    report.files.map((file) => file.path),
+    paths,
+  );
+  t.deepEqual(
+    report.files.map((file) => file.diagnostics.length),
+    [1, 0, 2, 0],
+  );
+  t.is(report.errorCount, 3);
+})
+
+test('can stream the findings as NDJSON records', async (t) => {
+  const spellchecker = new SpellChecker();
+
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'first.txt');
+  writeFileSync(path, 'A tset and snetences');
+
+  const records = [];
+  const report = await spellchecker.checkFiles([path, join(directory, 'missing.txt')], {}, (record) => {
+    records.push(record);
+  });
+  // The records may still be queued when the promise resolves
+  await new Promise((resolve) => setImmediate(resolve));
+
+  t.is(report.errorCount, 2);
+  t.is(report.files[0].diagnostics.length, 0);
+  const parsed = records.map((record) => JSON.parse(record));
+  t.deepEqual(
+    parsed.filter((record) => record.word).map((record) => record.word),
+    ['tset', 'snetences'],
+  );
+  t.truthy(parsed.find((record) => record.error));
+})
+
+test('can stop checking files after too many errors', async (t) => {
+  const spellchecker = new SpellChecker();
+
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const paths = ['A tset', 'Two snetences, tset', 'Another tset'].map((content, index) => {
+    const path = join(directory, `${index}.txt`);
+    writeFileSync(path, content);
+    return path;
+  });
+
+  const report = await spellchecker.checkFiles(paths, { maxErrors: 1 });
+  t.true(report.thresholdExceeded);
+  t.is(report.files.length, 2);
+  t.is(report.errorCount, 3);
+
+  const passing = await spellchecker.checkFiles(paths, { maxErrors: 10 });
+  t.false(passing.thresholdExceeded);
+  t.is(passing.files.length, 3);
+
+  // Only the issues which were not in the previous run are counted
+  const cacheLocation = join(directory, '.ejaancache');
+  await spellchecker.checkFiles(paths, { cacheLocation });
+  writeFileSync(paths[0], 'A tset, and a nwe one');
+  const rerun = await spellchecker.checkFiles(paths, { cacheLocation, maxErrors: 0, failOn: 'new' });
+  t.true(rerun.thresholdExceeded);
+  t.is(rerun.files.length, 1);
+
+  const unchanged = await spellchecker.checkFiles(paths, { cacheLocation, maxErrors: 0, failOn: 'new' });
+  t.false(unchanged.thresholdExceeded);
+})
+
+test('can accept the words of custom dictionaries', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'CUSTOM.DIC');
+  writeFileSync(path, 'tset\r\nsnetences\r\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [path] });
+  t.deepEqual(spellchecker.checkAndSuggest('A tset of snetences'), []);
+  t.true(spellchecker.checkWord('Tset'));
+
+  t.throws(() => new SpellChecker({ dictionaries: [join(directory, 'missing.dic')] }));
+})
+
+test('can fall back to the custom dictionaries', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'CUSTOM.DIC');
+  writeFileSync(path, 'tset\r\nsnetences\r\n');
+
+  // The native spell checker is used when available, the dictionaries are accepted either way
+  const spellchecker = new SpellChecker({ dictionaries: [path], dictionaryFallback: true });
+  t.deepEqual(spellchecker.checkAndSuggest('tset snetences'), []);
+  t.true(spellchecker.checkAndSuggest('tset qzxwvkj').some((suggestion) => suggestion.word === 'qzxwvkj'));
+})
+
+test('can expand the words of Hunspell dictionaries', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  writeFileSync(join(directory, 'custom.aff'), 'SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n');
+  writeFileSync(join(directory, 'custom.dic'), '1\ntset/S\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [join(directory, 'custom.dic')] });
+  t.true(spellchecker.checkWord('tset'));
+  t.true(spellchecker.checkWord('tsets'));
+
+  const extension = join(directory, 'custom.oxt');
+  writeFileSync(extension, 'not a zip archive');
+  t.throws(() => new SpellChecker({ dictionaries: [extension] }));
+})
+
+test('can run a self-test of the native spell checker', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'words.dic');
+  writeFileSync(path, 'qzxwvkj\n');
+
+  // The custom dictionaries are skipped
+  const spellchecker = new SpellChecker({ dictionaries: [path] });
+  const report = spellchecker.selfTest();
+  t.true(report.passed, JSON.stringify(report.probes));
+  t.is(typeof report.backend, 'string');
+  t.true(report.probes.some((probe) => probe.input === 'qzxwvkj' && !probe.expected));
+  t.true(report.probes.every((probe) => probe.durationMs >= 0));
+})
+
+test('can record the timings of a check', async (t) => {
+  const spellchecker = new SpellChecker();
+  t.true(Array.isArray(spellchecker.check('Hello wrold')));
+
+  const { results, timings } = spellchecker.check('Hello wrold', { debug: true });
+  t.true(Array.isArray(results));
+  t.true(timings.totalMs >= 0);
+  t.true(timings.totalMs >= timings.tokenizationMs + timings.suggestionsMs + timings.marshallingMs);
+
+  const suggested = await spellchecker.checkAndSuggestAsync('Hello wrold', { debug: true });
+  t.true(Array.isArray(suggested.results));
+  t.true(suggested.timings.totalMs >= 0);
+
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'timed.txt');
+  writeFileSync(path, 'Hello wrold');
+  const report = await spellchecker.checkFiles([path], { check: { debug: true } });
+  t.true(report.timings.totalMs >= report.timings.tokenizationMs);
+  t.is((await spellchecker.checkFiles([path])).timings, undefined);
+})
+
+test('can toggle the custom dictionaries', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'medical.dic');
+  writeFileSync(path, 'tset\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [path] });
+  const other = spellchecker.fork();
+  t.deepEqual(spellchecker.listDictionaries(), [
+    { id: path, format: 'wordlist', languages: [], words: 1, enabled: true },
+  ]);
+
+  spellchecker.setDictionaryEnabled(path, false);
+  t.false(spellchecker.listDictionaries()[0].enabled);
+  t.is(spellchecker.checkAndSuggest('A tset').length, 1);
+  t.deepEqual(other.checkAndSuggest('A tset'), []);
+  t.throws(() => spellchecker.setDictionaryEnabled('missing.dic', true));
+})
+
+test('can look up similar dictionary words', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'project.dic');
+  writeFileSync(path, 'ejaan\nejekan\nnapi\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [path] });
+  t.deepEqual(spellchecker.lookupSimilar('Ejana'), [{ word: 'ejaan', distance: 1 }]);
+  t.deepEqual(
+    spellchecker.lookupSimilar('ejaan', { maxDistance: 2 }).map((similar) => similar.word),
+    ['ejaan', 'ejekan'],
+  );
+  t.is(spellchecker.lookupSimilar('ejaan', { limit: 1 }).length, 1);
+  t.deepEqual(spellchecker.lookupSimilar('ejaan', { maxDistance: 0 }), [{ word: 'ejaan', distance: 0 }]);
+})
+
+test('can stem words', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  writeFileSync(join(directory, 'id_ID.aff'), 'PFX M Y 1\nPFX M 0 mem .\n');
+  writeFileSync(join(directory, 'id_ID.dic'), '1\nbaca/M\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [join(directory, 'id_ID.dic')] });
+  t.deepEqual(spellchecker.stem('membaca', 'id-ID'), ['baca']);
+  t.deepEqual(spellchecker.stem('running', 'en-US'), ['run']);
+  t.deepEqual(spellchecker.stem('berlari', 'id-ID'), []);
+})
+
+test('lowercases the words with the case rules of the language', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'words.dic');
+  writeFileSync(path, 'ısparta\nstraße\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [path] });
+  t.deepEqual(spellchecker.stem('STRASSE', 'de-DE'), ['straße']);
+  t.deepEqual(spellchecker.stem('Isparta', 'tr-TR'), ['ısparta']);
+})
+
+test('can accept compounds of valid words', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'de.dic');
+  writeFileSync(path, 'Ejaanprüfung\nBericht\n');
+
+  const spellchecker = new SpellChecker({ dictionaries: [path], compounds: true });
+  if (!spellchecker.availableLanguages().some((language) => language.startsWith('de'))) {
+    t.pass('German is not installed');
+    return;
+  }
+  spellchecker.setLanguage(spellchecker.availableLanguages().find((language) => language.startsWith('de')));
+  t.true(spellchecker.checkWord('Ejaanprüfungsbericht'));
+  t.false(new SpellChecker({ dictionaries: [path] }).checkWord('Ejaanprüfungsbericht'));
+})
+
+test('can hyphenate words', (t) => {
+  const directory = mkdtempSync(join(tmpdir(), 'ejaan-'));
+  const path = join(directory, 'hyph_en_US.dic');
+  writeFileSync(path, 'UTF-8\nLEFTHYPHENMIN 2\nRIGHTHYPHENMIN 3\n.hy3ph\nhe2n\nhena4\nhen5at\n1na\nn2at\n1tio\n2io\no2n\n');
+
+  const spellchecker = new SpellChecker({ hyphenation: [path] });
+  t.deepEqual(spellchecker.hyphenate('hyphenation', 'en-GB'), [2, 6]);
+  t.deepEqual(spellchecker.fork().hyphenate('Hyphenation', 'en-US'), [2, 6]);
+  t.throws(() => spellchecker.hyphenate('Silbentrennung', 'de-DE'));
+})
+
+test('can use the preferred languages of the system', (t) => {
+  const spellchecker = SpellChecker.fromSystemPreferences();
+  const language = spellchecker.language();
+  t.true(language === null || spellchecker.availableLanguages().includes(language));
+})
+
+test('can fall back to other languages', (t) => {
+  const available = new SpellChecker().availableLanguages();
+  const english = available.find((language) => language.startsWith('en'));
+  const indonesian = available.find((language) => language.startsWith('id'));
+  if (!english || !indonesian) {
+    t.pass('English or Indonesian is not installed');
+    return;
+  }
+
+  const spellchecker = new SpellChecker({ languages: ['en-ZZ', 'id-ID'] });
+  t.is(spellchecker.language(), english);
+  t.true(spellchecker.checkWord('walking'));
+  t.true(spellchecker.checkWord('berjalan'));
+  t.deepEqual(spellchecker.check('I am berjalan'), []);
+})
+
+test('can get the build info', (t) => {
+  const info = SpellChecker.buildInfo();
+  t.regex(info.version, /^\d+\.\d+\.\d+/);
+  t.true(Array.isArray(info.features));
+  t.true(info.backendAvailable);
+  t.is(info.error, undefined);
+  t.true(info.components.length > 0);
+})
+
+test('can download a dictionary', async (t) => {
+  const content = '2\ntset\nsnetences\n';
+  const server = createServer((_request, response) => response.end(content));
+  await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+  const url = `http://127.0.0.1:${server.address().port}/words.dic`;
+  const sha256 = createHash('sha256').update(content).digest('hex');
+  const cacheLocation = mkdtempSync(join(tmpdir(), 'ejaan-'));
+
+  const spellchecker = new SpellChecker();
+  try {
+    await t.throwsAsync(spellchecker.downloadDictionary(url, { sha256: '0'.repeat(64), cacheLocation }));
+    const path = await spellchecker.downloadDictionary(url, { sha256, cacheLocation });
+    t.is(path, join(cacheLocation, `${sha256}.dic`));
+  } finally {
+    server.close();
+  }
+  t.deepEqual(spellchecker.checkAndSuggest('A tset of snetences'), []);
+})
+
+test('keeps the language of each instance separate', (t) => {
+  const first = new SpellChecker({ sandboxed: true });
+  const second = new SpellChecker({ sandboxed: true });
+  const languages = first.availableLanguages();
+  if (languages.length < 2) {
+    t.pass('Only a single language is installed');
+    return;
+  }
+
+  first.setLanguage(languages[0]);
+  second.setLanguage(languages[1]);
+  t.is(first.language(), languages[0]);
+  t.is(second.language(), languages[1]);
+
+  first.addWord('tset');
+  t.true(first.checkWord('tset'));
+  t.false(second.checkWord('tset'));
+})
+
+test('can get the capabilities of the native spell checker', (t) => {
+  const spellchecker = new SpellChecker();
+
+  const capabilities = spellchecker.capabilities();
+  t.is(typeof capabilities.removeWords, 'boolean');
+  t.is(typeof capabilities.languageDetection, 'boolean');
+  if (capabilities.removeWords) {
+    t.notThrows(() => spellchecker.removeWord('tset'));
+  } else {
+    t.throws(() => spellchecker.removeWord('tset'));
+  }
+})
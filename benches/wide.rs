@@ -0,0 +1,101 @@
+//! Benchmarks of the wide string buffers of the Windows hot path, see `src/wide.rs`.
+//!
+//! Run with `cargo bench --bench wide`, the native spell checker is only measured on Windows.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+#[path = "../src/wide.rs"]
+#[allow(dead_code, unused_imports)]
+mod wide;
+
+use wide::WideBuffer;
+
+const WORDS: [&str; 5] = ["the", "snetences", "“Test...”", "dictionary", "yang"];
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    group.bench_function("fresh allocation", |b| {
+        b.iter(|| {
+            for word in WORDS {
+                let wide: Vec<u16> = word.encode_utf16().chain(std::iter::once(0)).collect();
+                black_box(wide.as_ptr());
+            }
+        })
+    });
+    group.bench_function("reused buffer", |b| {
+        let mut buffer = WideBuffer::new();
+        b.iter(|| {
+            for word in WORDS {
+                buffer.set_str(word);
+                black_box(buffer.as_ptr());
+            }
+        })
+    });
+    group.finish();
+}
+
+/// Check the text after every keystroke with the native spell checker.
+#[cfg(target_os = "windows")]
+fn as_you_type(c: &mut Criterion) {
+    use windows::{
+        Win32::{
+            Foundation::S_OK,
+            Globalization::{ISpellChecker, ISpellCheckerFactory, SpellCheckerFactory},
+            System::Com::{CLSCTX_ALL, COINIT_MULTITHREADED, CoCreateInstance, CoInitializeEx},
+        },
+        core::{PCWSTR, w},
+    };
+
+    let sentence = "This is a tset sentence.";
+    let checker: ISpellChecker = unsafe {
+        CoInitializeEx(None, COINIT_MULTITHREADED)
+            .ok()
+            .expect("Failed to initialize COM");
+        let factory: ISpellCheckerFactory =
+            CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_ALL)
+                .expect("Failed to create the spell checker factory");
+        factory
+            .CreateSpellChecker(w!("en-US"))
+            .expect("Failed to create the spell checker")
+    };
+    let check = |text: *const u16| unsafe {
+        let errors = checker
+            .Check(PCWSTR::from_raw(text))
+            .expect("Failed to check the text");
+        let mut error = None;
+        while errors.Next(&mut error) == S_OK {
+            black_box(error.take());
+        }
+    };
+
+    let mut group = c.benchmark_group("as you type");
+    group.bench_function("fresh allocation", |b| {
+        b.iter(|| {
+            for end in 1..=sentence.len() {
+                let wide: Vec<u16> = sentence[..end]
+                    .encode_utf16()
+                    .chain(std::iter::once(0))
+                    .collect();
+                check(wide.as_ptr());
+            }
+        })
+    });
+    group.bench_function("reused buffer", |b| {
+        let mut buffer = WideBuffer::new();
+        b.iter(|| {
+            for end in 1..=sentence.len() {
+                buffer.set_str(&sentence[..end]);
+                check(buffer.as_ptr());
+            }
+        })
+    });
+    group.finish();
+}
+
+#[cfg(not(target_os = "windows"))]
+fn as_you_type(_c: &mut Criterion) {}
+
+criterion_group!(benches, encode, as_you_type);
+criterion_main!(benches);
@@ -3,6 +3,824 @@
 
 /* auto-generated by NAPI-RS */
 
+export class ExternalObject<T> {
+  readonly '': {
+    readonly '': unique symbol
+    [K: symbol]: T
+  }
+}
+/**
+ * A word in the text.
+ *
+ * @typedef {Object} Token
+ * @property {number} start The start index of the word
+ * @property {number} end The end index of the word
+ * @property {string} word The word
+ */
+export interface Token {
+  /** The start index of the word in the original text. */
+  start: number
+  /**
+   * The end index of the word in the original text.
+   *
+   * This is inclusive, meaning the end index is the last character of the word.
+   */
+  end: number
+  /** The word. */
+  word: string
+}
+/**
+ * A word from the caller's own tokenizer.
+ *
+ * @typedef {Object} TextToken
+ * @property {string} word The word to check
+ * @property {number} start The start index of the word in the original text
+ */
+export interface TextToken {
+  /** The word to check. */
+  word: string
+  /** The start index of the word in the original text, in UTF-16 code units. */
+  start: number
+}
+/**
+ * An issue found in the text.
+ *
+ * @typedef {Object} Diagnostic
+ * @property {number} start The start index of the string
+ * @property {number} end The end index of the string
+ * @property {string} word The text with the issue
+ * @property {string[]} suggestions The list of suggested replacements
+ * @property {string} category The kind of check: `spelling`, `grammar`, `style`, or `custom-rule`
+ * @property {string} severity The severity: `error`, `warning`, `info`, or `hint`
+ * @property {string} [language] The language which produced the issue
+ * @property {string[]} rejectedBy The languages which rejected the word
+ */
+export interface Diagnostic {
+  /** The start index of the text in the original text. */
+  start: number
+  /**
+   * The end index of the text in the original text.
+   *
+   * This is inclusive, meaning the end index is the last character of the text.
+   */
+  end: number
+  /** The text with the issue, e.g. the misspelled word. */
+  word: string
+  /** A list of suggested replacements for the text. */
+  suggestions: Array<string>
+  /**
+   * The kind of check which found the issue.
+   *
+   * - `spelling`: A misspelled word.
+   * - `grammar`: A grammar issue.
+   * - `style`: A style issue.
+   * - `custom-rule`: A custom rule defined by the user.
+   */
+  category: string
+  /**
+   * How important the issue is: `error`, `warning`, `info`, or `hint`.
+   *
+   * Misspelled words are reported as `warning`.
+   */
+  severity: string
+  /** The language or dictionary which produced the issue, if known. */
+  language?: string
+  /**
+   * All the languages which rejected the word, to help adjusting the language configuration.
+   *
+   * This includes the fallback languages of the `languages` option.
+   */
+  rejectedBy: Array<string>
+}
+/**
+ * A block of text from a rich-text editor document, e.g. a ProseMirror or TipTap paragraph.
+ *
+ * @typedef {Object} TextBlock
+ * @property {string} text The text of the block
+ * @property {number} pos The document position where the text starts
+ * @property {TextSegment[]} [segments] Where the text continues after the inline nodes
+ */
+export interface TextBlock {
+  /** The text of the block, e.g. from `node.textContent`. */
+  text: string
+  /** The document position where the text starts, e.g. `pos + 1` from `doc.descendants()`. */
+  pos: number
+  /**
+   * Where the text continues in the document, when the block has inline nodes or
+   * a flat text of the whole document is used.
+   *
+   * Each segment maps an offset in the text to a document position,
+   * the offsets between two segments are counted from the previous one.
+   */
+  segments?: Array<TextSegment>
+}
+/**
+ * The start of a text segment in the document.
+ *
+ * @typedef {Object} TextSegment
+ * @property {number} offset The offset in the text of the block
+ * @property {number} pos The document position of the offset
+ */
+export interface TextSegment {
+  /** The offset in the text of the block, in UTF-16 code units. */
+  offset: number
+  /** The document position of the offset. */
+  pos: number
+}
+/**
+ * An issue found in a block, with the document positions for an editor decoration.
+ *
+ * @typedef {Object} Decoration
+ * @property {number} from The document position where the issue starts
+ * @property {number} to The document position where the issue ends, exclusive
+ * @property {number} block The index of the block with the issue
+ * @property {string} word The text with the issue
+ * @property {string[]} suggestions The list of suggested replacements
+ * @property {string} category The kind of check: `spelling`, `grammar`, `style`, or `custom-rule`
+ * @property {string} severity The severity: `error`, `warning`, `info`, or `hint`
+ */
+export interface Decoration {
+  /** The document position where the issue starts. */
+  from: number
+  /**
+   * The document position where the issue ends.
+   *
+   * This is exclusive, the same as `Decoration.inline(from, to)` in ProseMirror.
+   */
+  to: number
+  /** The index of the block with the issue. */
+  block: number
+  /** The text with the issue, e.g. the misspelled word. */
+  word: string
+  /** A list of suggested replacements for the text. */
+  suggestions: Array<string>
+  /** The kind of check which found the issue, the same as `Diagnostic.category`. */
+  category: string
+  /** How important the issue is, the same as `Diagnostic.severity`. */
+  severity: string
+}
+/**
+ * A misspelled word found across the documents.
+ *
+ * @typedef {Object} UnknownWord
+ * @property {string} word The misspelled word
+ * @property {number} count How many times the word was found
+ * @property {number} documents How many documents have the word
+ * @property {string[]} contexts Some of the lines where the word was found
+ */
+export interface UnknownWord {
+  /** The misspelled word. */
+  word: string
+  /** How many times the word was found in all the documents. */
+  count: number
+  /** How many documents have the word. */
+  documents: number
+  /** Up to 3 sample lines where the word was found, trimmed around the word. */
+  contexts: Array<string>
+}
+/**
+ * A word proposed to be added to the dictionary.
+ *
+ * @typedef {Object} ProposedAddition
+ * @property {string} word The flagged word
+ * @property {number} count How many distinct occurrences of the word were flagged
+ */
+export interface ProposedAddition {
+  /** The word flagged as misspelled. */
+  word: string
+  /** How many distinct occurrences of the word were flagged in the session. */
+  count: number
+}
+/**
+ * Options for checking many files at once, like a linter.
+ *
+ * @typedef {Object} LintOptions
+ * @property {CheckOptions} [check] The options for checking each file
+ * @property {string} [cacheLocation] The file of the persistent cache, disabled by default
+ * @property {number} [concurrency] The number of files checked at once, 1 by default
+ * @property {number} [maxErrors] Stop once more than this number of issues are found
+ * @property {string} [failOn] Which issues are counted towards `maxErrors`: `any` or `new`
+ */
+export interface LintOptions {
+  /**
+   * The options for checking each file.
+   *
+   * The format of each file is guessed from its extension unless `format` is set.
+   */
+  check?: CheckOptions
+  /**
+   * The file of the persistent cache, e.g. `.ejaancache`, like ESLint's `--cache-location`.
+   *
+   * Each file is keyed by a hash of its content, the language, the check options, and
+   * the configuration of the spell checker, so repeated runs only check the modified files.
+   * The configuration includes the custom dictionaries and whether they are enabled,
+   * the words added or removed in this session, and the options such as `compounds`.
+   */
+  cacheLocation?: string
+  /**
+   * The number of files checked at once, 1 by default.
+   *
+   * Each worker checks the files with its own spell checker forked from this one,
+   * so the words added in this session are not known by the workers.
+   * The workers do not go through the call queue of `setMaxConcurrency()`, so the other calls
+   * are not blocked while checking. On macOS, the calls into the single native spell checker
+   * still run one at a time.
+   */
+  concurrency?: number
+  /**
+   * Stop checking the remaining files once more than this number of issues are found.
+   *
+   * The report tells whether the threshold was exceeded with `thresholdExceeded`,
+   * e.g. `0` to fail a quality gate on the first issue. There is no threshold by default.
+   */
+  maxErrors?: number
+  /**
+   * Which issues are counted towards `maxErrors`, `any` by default.
+   *
+   * - `any`: Every issue.
+   * - `new`: Only the issues not reported in the previous run, from the persistent cache.
+   *   Without `cacheLocation`, every issue is new.
+   */
+  failOn?: string
+}
+/**
+ * Options for downloading a dictionary.
+ *
+ * @typedef {Object} DownloadOptions
+ * @property {string} sha256 The expected SHA-256 checksum of the file, in hexadecimal
+ * @property {string} [language] The language of the words, instead of the one of the dictionary
+ * @property {string} [cacheLocation] The directory of the downloaded dictionaries
+ */
+export interface DownloadOptions {
+  /**
+   * The expected SHA-256 checksum of the file, in hexadecimal.
+   *
+   * The dictionary is rejected when the downloaded file does not match.
+   */
+  sha256: string
+  /**
+   * The language of the words, e.g. `id-ID`.
+   *
+   * By default, the words of a LibreOffice extension are used for the languages it declares,
+   * and the words of the other dictionaries for every language.
+   */
+  language?: string
+  /**
+   * The directory of the downloaded dictionaries.
+   *
+   * By default, `%LOCALAPPDATA%\ejaan\dictionaries` on Windows,
+   * `~/Library/Caches/ejaan/dictionaries` on macOS, and `~/.cache/ejaan/dictionaries` elsewhere.
+   */
+  cacheLocation?: string
+}
+/**
+ * The results of a checked file.
+ *
+ * @typedef {Object} FileResult
+ * @property {string} path The path of the file
+ * @property {Diagnostic[]} diagnostics The list of issues found in the file
+ * @property {boolean} cached Are the results from the persistent cache
+ * @property {string} [error] Why the file could not be checked
+ */
+export interface FileResult {
+  /** The path of the file, as given. */
+  path: string
+  /** The list of issues found in the file. */
+  diagnostics: Array<Diagnostic>
+  /** Are the results from the persistent cache, without checking the file again. */
+  cached: boolean
+  /** Why the file could not be checked, e.g. it could not be read as UTF-8. */
+  error?: string
+}
+/**
+ * The results of checking many files.
+ *
+ * @typedef {Object} LintReport
+ * @property {FileResult[]} files The results of each file
+ * @property {number} errorCount The number of issues found in all the files
+ * @property {boolean} thresholdExceeded Did the issues exceed the `maxErrors` option
+ * @property {Timings} [timings] How long each phase took, with the `debug` check option
+ */
+export interface LintReport {
+  /**
+   * The results of each file, in the same order as the paths.
+   *
+   * The files after the `maxErrors` threshold was exceeded are not checked, nor included.
+   */
+  files: Array<FileResult>
+  /** The number of issues found in all the files. */
+  errorCount: number
+  /** Did the issues counted by `failOn` exceed the `maxErrors` option. */
+  thresholdExceeded: boolean
+  /**
+   * How long each phase of checking all the files took, with the `debug` check option.
+   *
+   * With a `concurrency` above 1, the phases are summed over the workers
+   * and may add up to more than the total.
+   */
+  timings?: Timings
+}
+/**
+ * The statistics of the paragraph cache.
+ *
+ * @typedef {Object} CacheStats
+ * @property {number} entries The number of cached paragraphs
+ * @property {number} hits The number of paragraphs found in the cache
+ * @property {number} misses The number of paragraphs checked by the native spell checker
+ */
+export interface CacheStats {
+  /** The number of cached paragraphs. */
+  entries: number
+  /** The number of paragraphs found in the cache since the spell checker was created. */
+  hits: number
+  /** The number of paragraphs checked by the native spell checker with the cache enabled. */
+  misses: number
+}
+/**
+ * How long each phase of a check took, recorded with the `debug` check option.
+ *
+ * @typedef {Object} Timings
+ * @property {number} tokenizationMs Masking the format and splitting the words
+ * @property {number} checkMs Checking the words with the native spell checker and the word lists
+ * @property {number} suggestionsMs Getting the suggestions of the misspelled words
+ * @property {number} marshallingMs Converting the results for JavaScript
+ * @property {number} totalMs The whole check, in milliseconds
+ */
+export interface Timings {
+  /** Masking the format of the text and splitting the words with the `wordBreak` option. */
+  tokenizationMs: number
+  /** Checking the words, mostly the native spell checker. This is everything else of the check. */
+  checkMs: number
+  /** Getting the suggestions of the misspelled words from the native spell checker. */
+  suggestionsMs: number
+  /** Converting the results for JavaScript. */
+  marshallingMs: number
+  /** The whole check, in milliseconds. */
+  totalMs: number
+}
+/**
+ * The issues found by a check with the `debug` option, with the timings of the check.
+ *
+ * @typedef {Object} TimedDiagnostics
+ * @property {Diagnostic[]} results The list of issues found
+ * @property {Timings} timings How long each phase of the check took
+ */
+export interface TimedDiagnostics {
+  results: Array<Diagnostic>
+  timings: Timings
+}
+/**
+ * The decorations found by `checkBlocks()` with the `debug` option, with the timings of the check.
+ *
+ * @typedef {Object} TimedDecorations
+ * @property {Decoration[]} results The list of issues found
+ * @property {Timings} timings How long each phase of the check took
+ */
+export interface TimedDecorations {
+  results: Array<Decoration>
+  timings: Timings
+}
+/**
+ * The suggestions found by a check with the `debug` option, with the timings of the check.
+ *
+ * @typedef {Object} TimedSuggestions
+ * @property {Suggestion[]} results The list of suggested spellings
+ * @property {Timings} timings How long each phase of the check took
+ */
+export interface TimedSuggestions {
+  results: Array<Suggestion>
+  timings: Timings
+}
+/**
+ * Information about where the custom words are being stored.
+ *
+ * @typedef {Object} DictionaryInfo
+ * @property {string} backend The native spell checker backend
+ * @property {string} storage The storage kind: `system`, `session`, or `overlay`
+ * @property {string} [location] The location of the storage, if any
+ * @property {boolean} persistent Are the words kept after the process exits
+ */
+export interface DictionaryInfo {
+  /** The native spell checker backend in use. */
+  backend: string
+  /**
+   * Where the added words are stored.
+   *
+   * - `system`: The system-level user dictionary, shared with other applications.
+   * - `session`: The session ignore list of this spell checker instance.
+   * - `overlay`: The overlay dictionary maintained by this library.
+   */
+  storage: string
+  /** The location of the storage on disk, if any. */
+  location?: string
+  /** Are the added words kept after the process exits. */
+  persistent: boolean
+}
+/**
+ * The optional features supported by the native spell checker.
+ *
+ * @typedef {Object} Capabilities
+ * @property {boolean} removeWords Can the added words be removed again
+ * @property {boolean} languageDetection Is the language identified automatically when not set
+ */
+export interface Capabilities {
+  /**
+   * Can the added words be removed again with `removeWord()`.
+   *
+   * This is `false` on Windows when the provider does not implement `ISpellChecker2`.
+   */
+  removeWords: boolean
+  /** Is the language of the text identified automatically when no language is set. */
+  languageDetection: boolean
+}
+/**
+ * A single check of the self-test.
+ *
+ * @typedef {Object} SelfTestProbe
+ * @property {string} input The checked word or sentence
+ * @property {boolean} expected Is the input expected to be spelled correctly
+ * @property {boolean} passed Did the native spell checker give the expected result
+ * @property {string} [error] The error of the native spell checker, if the check failed
+ * @property {number} durationMs How long the check took, in milliseconds
+ */
+export interface SelfTestProbe {
+  /**
+   * The checked word or sentence.
+   *
+   * The sentence joins the correct words with a gibberish word, only the gibberish word
+   * is expected to be flagged.
+   */
+  input: string
+  /** Is the input expected to be spelled correctly. */
+  expected: boolean
+  /** Did the native spell checker give the expected result. */
+  passed: boolean
+  /** The error of the native spell checker, if the check failed. */
+  error?: string
+  /** How long the check took, in milliseconds. */
+  durationMs: number
+}
+/**
+ * The results of `selfTest()`.
+ *
+ * @typedef {Object} SelfTestReport
+ * @property {boolean} passed Did every probe pass
+ * @property {string} [backend] The native spell checker backend, if it can be queried
+ * @property {string} [language] The language of the native spell checker
+ * @property {SelfTestProbe[]} probes The result of each probe
+ * @property {number} durationMs How long the self-test took, in milliseconds
+ */
+export interface SelfTestReport {
+  /** Did every probe pass. */
+  passed: boolean
+  /** The native spell checker backend, if it can be queried, e.g. `NSSpellChecker`. */
+  backend?: string
+  /** The language of the native spell checker. */
+  language?: string
+  /** The result of each probe. */
+  probes: Array<SelfTestProbe>
+  /** How long the self-test took, in milliseconds. */
+  durationMs: number
+}
+/**
+ * A component of the platform, e.g. the spell checking provider on Windows.
+ *
+ * @typedef {Object} BuildComponent
+ * @property {string} name The name of the component
+ * @property {string} value The version or state of the component
+ */
+export interface BuildComponent {
+  /** The name of the component, e.g. `provider`. */
+  name: string
+  /** The version or state of the component, e.g. `MsSpell`. */
+  value: string
+}
+/**
+ * The results of `buildInfo()`.
+ *
+ * @typedef {Object} BuildInfo
+ * @property {string} version The version of this library
+ * @property {string[]} features The enabled cargo features
+ * @property {boolean} debug Is this a debug build
+ * @property {string} os The operating system of the build
+ * @property {string} arch The CPU architecture of the build
+ * @property {string} backend The native spell checker backend of the build
+ * @property {boolean} backendAvailable Can the native spell checker be created
+ * @property {string} [error] Why the native spell checker cannot be created
+ * @property {BuildComponent[]} components The platform components of the native spell checker
+ */
+export interface BuildInfo {
+  /** The version of this library. */
+  version: string
+  /** The enabled cargo features, e.g. `serde`. */
+  features: Array<string>
+  /** Is this a debug build. */
+  debug: boolean
+  /** The operating system of the build, e.g. `windows`, `macos`, or `linux`. */
+  os: string
+  /** The CPU architecture of the build, e.g. `x86_64` or `aarch64`. */
+  arch: string
+  /** The native spell checker backend of the build: `windows`, `macos`, or `unsupported`. */
+  backend: string
+  /** Can the native spell checker be created on this machine. */
+  backendAvailable: boolean
+  /** Why the native spell checker cannot be created. */
+  error?: string
+  /**
+   * The platform components of the native spell checker, e.g. the Windows spell checking
+   * provider or the macOS version.
+   */
+  components: Array<BuildComponent>
+}
+/**
+ * Options for looking up the similar words of the dictionaries.
+ *
+ * @typedef {Object} LookupOptions
+ * @property {number} [maxDistance] The maximum number of edits, 2 by default
+ * @property {number} [limit] The maximum number of words returned, 10 by default
+ */
+export interface LookupOptions {
+  /**
+   * The maximum number of edits from the word: inserting, removing, or replacing a character,
+   * or swapping two adjacent characters. This is 2 by default.
+   */
+  maxDistance?: number
+  /** The maximum number of words returned, the closest first. This is 10 by default. */
+  limit?: number
+}
+/**
+ * A dictionary word similar to the looked up word.
+ *
+ * @typedef {Object} SimilarWord
+ * @property {string} word The word of the dictionary
+ * @property {number} distance The number of edits from the looked up word
+ */
+export interface SimilarWord {
+  /** The word, as written in the dictionary. */
+  word: string
+  /** The number of edits from the looked up word, ignoring the case. */
+  distance: number
+}
+/**
+ * A custom dictionary accepted on top of the native spell checker.
+ *
+ * @typedef {Object} CustomDictionary
+ * @property {string} id The path of the file, followed by the dictionary inside a `.oxt` extension
+ * @property {string} format The format of the file: `wordlist` or `hunspell`
+ * @property {string[]} languages The languages of the words, empty for every language
+ * @property {number} words The number of words
+ * @property {boolean} enabled Are the words accepted by this spell checker
+ */
+export interface CustomDictionary {
+  /**
+   * The id of the dictionary, the path of the file.
+   *
+   * The dictionaries of a `.oxt` extension are suffixed with their path inside the extension,
+   * e.g. `dict-id.oxt#id_ID.dic`.
+   */
+  id: string
+  /**
+   * The format of the file.
+   *
+   * - `wordlist`: One word per line, e.g. the Office and LibreOffice personal dictionaries.
+   * - `hunspell`: A Hunspell dictionary expanded with its affix rules.
+   */
+  format: string
+  /** The languages of the words, empty when the words are accepted for every language. */
+  languages: Array<string>
+  /** The number of words, including the prefixed and suffixed forms of a Hunspell dictionary. */
+  words: number
+  /** Are the words accepted by this spell checker, see `setDictionaryEnabled()`. */
+  enabled: boolean
+}
+/**
+ * Options for creating the spell checker.
+ *
+ * @typedef {Object} SpellCheckerOptions
+ * @property {boolean} [sandboxed] Never write added words to the system dictionary
+ * @property {boolean} [allowNativeHandle] Allow access to the native spell checker object
+ * @property {boolean} [commonWords] Check the most common words without the native spell checker
+ * @property {boolean} [knownWords] Remember the correct words to skip the native spell checker
+ * @property {WordBreakOptions} [wordBreak] Customize which characters are part of a word
+ * @property {number} [proposeAdditions] Propose adding the words flagged more than this many times
+ * @property {boolean} [userDictionaries] Accept the words of the Office and LibreOffice personal dictionaries
+ * @property {string[]} [dictionaries] Accept the words of these word lists
+ * @property {boolean} [compounds] Accept the compounds of valid words in German, Dutch, and the Nordic languages
+ * @property {string[]} [hyphenation] Load the hyphenation patterns of these files for `hyphenate()`
+ * @property {string[]} [languages] Check with the first installed language, falling back to the others
+ * @property {boolean} [dictionaryFallback] Check with the loaded dictionaries only when the native spell checker is unavailable
+ */
+export interface SpellCheckerOptions {
+  /**
+   * Sandbox-safe mode, added words will never be written to the system dictionary.
+   *
+   * On macOS, words are ignored for this instance only instead of being learned
+   * into the user dictionary, which is required for sandboxed/App Store apps.
+   *
+   * On Windows, this has no effect since added words are always ignored per instance.
+   */
+  sandboxed?: boolean
+  /** Allow access to the underlying native spell checker object with `unsafeNativeHandle()`. */
+  allowNativeHandle?: boolean
+  /**
+   * Check the most common words of the language with a bundled list first,
+   * only the other words are checked by the native spell checker.
+   *
+   * This speeds up as-you-type checking, the native spell checker is skipped entirely when every
+   * word is common. The lists of `en` and `id` are bundled, the words are only accepted
+   * as listed, capitalized, or in all caps.
+   */
+  commonWords?: boolean
+  /**
+   * Remember the words confirmed correct by the native spell checker in this session,
+   * so re-checking unchanged text skips the native spell checker.
+   *
+   * The words are kept in a bloom filter per language, a misspelled word
+   * may be reported as correct with a very low probability (around 0.015%).
+   * Each new word is confirmed on its own first, so the first check of a text is slower.
+   */
+  knownWords?: boolean
+  /**
+   * Customize which characters are part of a word.
+   *
+   * When set, the text is split into words by this library before being checked,
+   * instead of relying on the word breaking of the native spell checker.
+   */
+  wordBreak?: WordBreakOptions
+  /**
+   * Propose adding the words flagged more than this many times in the session
+   * to the dictionary, see `proposedAdditions()`.
+   *
+   * Each occurrence in the checked text is counted once, re-checking an unchanged paragraph
+   * does not count its words again. The words of `checkWord()` are not counted.
+   *
+   * The words are not counted unless this is set.
+   */
+  proposeAdditions?: number
+  /**
+   * Accept the words of the existing personal dictionaries of other applications.
+   *
+   * On Windows, the Office custom dictionaries (`%APPDATA%\Microsoft\UProof\*.dic`) and the
+   * LibreOffice user wordbooks are loaded, so the words added years ago in Word are still accepted.
+   * The dictionaries are only read, the words added with this library are not written to them.
+   */
+  userDictionaries?: boolean
+  /**
+   * Accept the words of these word lists, e.g. an exported `CUSTOM.DIC` or a Hunspell dictionary.
+   *
+   * The files have one word per line. A Hunspell `.dic` next to its `.aff` file is expanded with the
+   * prefixes and suffixes of the affix rules, and a LibreOffice `.oxt` extension loads the Hunspell
+   * dictionaries it declares, each one only for the languages of the extension.
+   *
+   * A capitalized or uppercase word matches its lowercase entry with the case rules of the current
+   * language, e.g. the Turkish `Isparta` matches `ısparta` and the German `STRASSE` matches `straße`.
+   */
+  dictionaries?: Array<string>
+  /**
+   * Accept a flagged word when it splits into valid words, e.g. the German `Haustür`
+   * or the Dutch `fietsenwinkel`, with the linking elements (`-s-`, `-en-`) between them.
+   *
+   * This applies to German, Dutch, Danish, Norwegian, and Swedish. The native spell checkers
+   * only know some of the compounds, and the words of the `dictionaries` option none of them.
+   * Each flagged word costs a few more calls to the native spell checker.
+   */
+  compounds?: boolean
+  /**
+   * Load the hyphenation patterns of these files for `hyphenate()`.
+   *
+   * The files are the Hunspell and LibreOffice patterns, e.g. `hyph_de_DE.dic` (for the language
+   * in its name), or a LibreOffice `.oxt` extension with the patterns of its languages.
+   */
+  hyphenation?: Array<string>
+  /**
+   * Check with the first installed of these languages, e.g. `["en-US", "id-ID"]`,
+   * and fall back to the other installed ones.
+   *
+   * A word is only flagged when none of the languages accepts it, the suggestions are from the
+   * first language. A language matches an installed one of another region, e.g. `en-AU` uses
+   * `en-GB` when only that one is installed. The default language is kept when none is installed.
+   */
+  languages?: Array<string>
+  /**
+   * Check with the loaded dictionaries only, instead of throwing `BackendUnavailable`,
+   * when the native spell checker is unavailable, e.g. on Windows Server Core.
+   *
+   * Every word is flagged unless it is in the `dictionaries`, e.g. a Hunspell dictionary,
+   * or added with `addWord()`. The flagged words have no suggestions, see `lookupSimilar()`.
+   */
+  dictionaryFallback?: boolean
+}
+/**
+ * Options for splitting the text into words.
+ *
+ * @typedef {Object} WordBreakOptions
+ * @property {string} [wordCharacters] Extra characters always part of a word
+ * @property {string} [joinCharacters] Characters part of a word only inside of it
+ * @property {string} [breakCharacters] Characters always breaking a word
+ */
+export interface WordBreakOptions {
+  /** Extra characters which are always part of a word, e.g. `’` to treat it as a letter. */
+  wordCharacters?: string
+  /**
+   * Characters which are part of a word only between two word characters,
+   * e.g. `_` or `.` to keep identifiers together.
+   *
+   * This replaces the default, which is the apostrophes `'` and `’`.
+   */
+  joinCharacters?: string
+  /** Characters which always break a word, even letters. */
+  breakCharacters?: string
+}
+/**
+ * Options for checking the sentences.
+ *
+ * @typedef {Object} CheckOptions
+ * @property {number} [maxResults] Stop checking after this many misspelled words
+ * @property {string} [format] The format of the text: `plain`, `json`, `yaml`, `gettext`, `srt`, `vtt`, `ass`, `chat`, or `commit`
+ * @property {boolean} [checkKeys] Also check the keys of JSON and YAML documents
+ * @property {boolean} [skipEmoji] Skip the emoji shortcodes and sequences, enabled by default
+ * @property {boolean} [skipPlaceholders] Skip the common template placeholders, e.g. `{name}` or `%s`
+ * @property {PlaceholderDelimiters[]} [placeholders] Extra placeholder syntaxes to skip
+ * @property {boolean} [cache] Reuse the results of the unchanged paragraphs
+ * @property {boolean} [debug] Return the timings of the check with its results
+ */
+export interface CheckOptions {
+  /**
+   * Stop checking once this many misspelled words are found.
+   *
+   * This is useful for "document has 100+ issues" summaries,
+   * especially on Windows where each misspelled word costs an extra call for the suggestions.
+   */
+  maxResults?: number
+  /**
+   * The format of the text, only the prose inside of it is checked.
+   *
+   * - `plain`: Plain text, everything is checked. This is the default.
+   * - `json`: A JSON document, only the string values are checked.
+   * - `yaml`: A YAML document, only the string values are checked.
+   * - `gettext`: A gettext catalog (`.po`/`.pot`), only the `msgstr` translations are checked,
+   *   skipping the format placeholders. The catalog is checked in the language of its header.
+   * - `srt`/`vtt`: SubRip or WebVTT subtitles, only the caption text is checked,
+   *   skipping the sequence numbers, timestamps, cue settings, and inline tags.
+   * - `ass`/`ssa`: Advanced SubStation Alpha subtitles, only the text of the `Dialogue` lines is checked,
+   *   without the override tags (e.g. `{\an8}` or karaoke `{\k20}`) and drawing commands.
+   * - `chat`: Chat messages with BBCode or Discord-flavored markdown, skipping the code blocks,
+   *   inline code, BBCode tags (e.g. `[spoiler]`), custom emoji (`<:name:id>`), and mentions.
+   * - `commit`: A git commit message, skipping the comments, trailers (e.g. `Signed-off-by:`),
+   *   issue references (e.g. `#123`), and inline code spans.
+   *
+   * The offsets are still based on the original text.
+   */
+  format?: string
+  /** Also check the keys of JSON and YAML documents. */
+  checkKeys?: boolean
+  /**
+   * Skip the emoji shortcodes (e.g. `:thumbsup:`) and the raw emoji sequences, in every format.
+   *
+   * This is enabled by default.
+   */
+  skipEmoji?: boolean
+  /**
+   * Skip the common template placeholders, for i18n messages or email templates:
+   * `{name}`, `{{var}}`, `${env}`, and the printf-style `%s` or `%(key)s`.
+   */
+  skipPlaceholders?: boolean
+  /**
+   * Extra placeholder syntaxes to skip, e.g. `{ open: '[[', close: ']]' }`.
+   *
+   * These are skipped even without `skipPlaceholders`.
+   */
+  placeholders?: Array<PlaceholderDelimiters>
+  /**
+   * Reuse the results of the unchanged paragraphs from the previous checks,
+   * so re-checking an edited document only checks the changed paragraphs.
+   *
+   * The paragraphs are separated by blank lines. This is enabled by default
+   * for `checkParagraphs()` and `checkFile()`, and disabled for the other checks.
+   */
+  cache?: boolean
+  /**
+   * Record how long each phase of the check took, the check then returns
+   * `{ results, timings }` instead of the results alone, e.g. `TimedDiagnostics`.
+   * `checkFiles()` returns the timings in the `timings` of its report instead.
+   *
+   * This adds a little overhead to every word, keep it for diagnosing slow checks.
+   */
+  debug?: boolean
+}
+/**
+ * The delimiters of a template placeholder syntax.
+ *
+ * @typedef {Object} PlaceholderDelimiters
+ * @property {string} open The opening delimiter, e.g. `[[`
+ * @property {string} close The closing delimiter, e.g. `]]`
+ */
+export interface PlaceholderDelimiters {
+  /** The opening delimiter, e.g. `[[`. */
+  open: string
+  /** The closing delimiter, e.g. `]]`. */
+  close: string
+}
 /**
  * A suggestion for a misspelled word.
  *
@@ -43,9 +861,21 @@ export declare class SpellChecker {
    *
    * As a sidenote, all API returned can throw an error, especially on Windows.
    *
+   * @param {SpellCheckerOptions} [options] The options for the spell checker.
    * @returns {void}
    */
-  constructor()
+  constructor(options?: SpellCheckerOptions | undefined | null)
+  /**
+   * Create a spell checker for the preferred languages of the user in the operating system.
+   *
+   * The languages are the preferred languages of the user profile on Windows and `AppleLanguages`
+   * on macOS, the first installed one is used and the others are fallbacks, see the `languages` option.
+   * The languages of the options are tried after the preferred ones.
+   *
+   * @param {SpellCheckerOptions} [options] The options for the spell checker.
+   * @returns {SpellChecker}
+   */
+  static fromSystemPreferences(options?: SpellCheckerOptions | undefined | null): SpellChecker
   /**
    * Get the current language of the spell checker.
    *
@@ -72,15 +902,176 @@ export declare class SpellChecker {
    * @returns {boolean} Is the word spelled correctly or not.
    */
   checkWord(word: string): boolean
+  /**
+   * Check the sentences for any issues.
+   *
+   * This returns all the issues found, each with its category and severity.
+   * Currently only the spelling is checked.
+   *
+   * @param {string} sentences The sentences to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+   */
+  check(sentences: string, options?: CheckOptions | undefined | null): Array<Diagnostic> | TimedDiagnostics
+  /**
+   * Check the text paragraph by paragraph, reusing the results of the unchanged paragraphs.
+   *
+   * This is the same as `check()` with the `cache` option enabled by default,
+   * meant for re-checking a document after each edit.
+   * The cache is cleared when the words of the spell checker are changed.
+   *
+   * @param {string} text The text to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+   */
+  checkParagraphs(text: string, options?: CheckOptions | undefined | null): Array<Diagnostic> | TimedDiagnostics
+  /**
+   * Check a UTF-8 text file, paragraph by paragraph like `checkParagraphs()`.
+   *
+   * The format is guessed from the file extension (e.g. `.json`, `.po`, or `.srt`)
+   * unless the `format` option is set, unknown extensions are checked as plain text.
+   *
+   * @param {string} path The path of the file to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+   */
+  checkFile(path: string, options?: CheckOptions | undefined | null): Array<Diagnostic> | TimedDiagnostics
+  /**
+   * Check many files in a background task, like a linter.
+   *
+   * Each file is checked like `checkFile()`, a file which cannot be read is reported
+   * with its `error` instead of failing the whole run.
+   * With the `cacheLocation` option, the unchanged files are not checked again in the next runs.
+   *
+   * With `onRecord`, each finding is sent as an NDJSON record as soon as its file is checked,
+   * e.g. `{"path":"README.md","start":2,"end":5,"word":"tset",...}`, and a file which cannot
+   * be read as `{"path":"missing.md","error":"..."}`. The findings are then not kept in memory,
+   * the `diagnostics` of the report are empty but the `errorCount` is still counted.
+   *
+   * @param {string[]} paths The paths of the files to check
+   * @param {LintOptions} [options] The options for checking the files
+   * @param {Function} [onRecord] Called with a single-line JSON record for each finding
+   * @returns {Promise<LintReport>} The results of each file.
+   */
+  checkFiles(paths: Array<string>, options?: LintOptions | undefined | null, onRecord?: (record: string) => void): Promise<LintReport>
+  /**
+   * Download a dictionary in a background task, and accept its words from then on.
+   *
+   * The file can be a word list, a Hunspell `.dic` without affix rules, or a LibreOffice `.oxt`
+   * extension, see the `dictionaries` option. It is kept in a cache directory and named after
+   * its checksum, so it is only downloaded once and loaded from the cache afterwards.
+   *
+   * The words are added on top of the native spell checker for this instance and its forks,
+   * the system dictionaries are not modified.
+   *
+   * @param {string} url The URL of the dictionary
+   * @param {DownloadOptions} options The checksum and the language of the dictionary
+   * @returns {Promise<string>} The path of the downloaded dictionary.
+   */
+  downloadDictionary(url: string, options: DownloadOptions): Promise<string>
+  /**
+   * Remove all the cached paragraph results of this spell checker.
+   *
+   * @returns {void}
+   */
+  clearCache(): void
+  /**
+   * Get the statistics of the paragraph cache, e.g. to measure the hit rate.
+   *
+   * @returns {CacheStats} The cache statistics.
+   */
+  cacheStats(): CacheStats
+  /**
+   * Split the text into words, the same way as the checks done by this library.
+   *
+   * This is useful to build word counts, highlighting, or custom filters
+   * that agree with the words being checked.
+   *
+   * A word is a run of letters, apostrophes are only kept inside a word.
+   * This can be customized with the `wordBreak` option.
+   *
+   * The words only match the checked words exactly with the `wordBreak` option, the text is then
+   * split by this library before being checked. Otherwise, the native spell checker splits the text
+   * on its own and may disagree, e.g. on `123abc` or the URLs.
+   *
+   * @param {string} text The text to split
+   * @returns {Token[]} The list of words with their offsets.
+   */
+  tokenize(text: string): Array<Token>
+  /**
+   * Check the words from an existing token stream, e.g. from a syntax highlighter.
+   *
+   * Each token is checked as a single word, with at most one result spanning the whole token,
+   * e.g. `my_var` is reported as a whole instead of being split by the native spell checker.
+   * The offsets of the results are based on the `start` of the token.
+   * The `format` option is ignored, since the tokens are already extracted.
+   *
+   * @param {TextToken[]} tokens The tokens to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+   */
+  checkTokens(tokens: Array<TextToken>, options?: CheckOptions | undefined | null): Array<Diagnostic> | TimedDiagnostics
+  /**
+   * Check the blocks of a rich-text editor document, e.g. ProseMirror or TipTap.
+   *
+   * Each block is checked on its own, and the results are returned with the document positions,
+   * ready to be used as decorations without mapping the offsets again.
+   *
+   * @param {TextBlock[]} blocks The blocks to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Decoration[] | TimedDecorations} The list of issues found, with the timings with the `debug` option.
+   */
+  checkBlocks(blocks: Array<TextBlock>, options?: CheckOptions | undefined | null): Array<Decoration> | TimedDecorations
+  /**
+   * Find the misspelled words across many documents, the most frequent first.
+   *
+   * This is useful to seed a project dictionary from an existing corpus,
+   * since the project-specific words are usually the most frequent ones.
+   * The `maxResults` option applies to each document.
+   *
+   * @param {string[]} texts The documents to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {UnknownWord[]} The misspelled words, ranked by frequency.
+   */
+  analyzeUnknownWords(texts: Array<string>, options?: CheckOptions | undefined | null): Array<UnknownWord>
   /**
    * Check if a word is spelled correctly.
    *
    * This will also return a list of suggestions if the word is misspelled.
    *
+   * This only returns the spelling issues of `check()`, in the older `Suggestion` shape.
+   *
+   * @param {string} sentences The sentences to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Suggestion[] | TimedSuggestions} The list of suggested spellings, with the timings with the `debug` option.
+   */
+  checkAndSuggest(sentences: string, options?: CheckOptions | undefined | null): Array<Suggestion> | TimedSuggestions
+  /**
+   * Check if a word is spelled correctly, using the UTF-16 string from JavaScript directly.
+   *
+   * This is the same as `checkAndSuggest()`, but skips the conversion to UTF-8 and back,
+   * since both native spell checkers work on UTF-16 strings.
+   *
+   * The string is only passed as-is when there is nothing to skip with the options, e.g. the emoji,
+   * the placeholders, or a `format`, and without the `cache` option. Otherwise, it is checked exactly
+   * like `checkAndSuggest()`. Unpaired surrogates are always passed to the spell checker as-is,
+   * only `maxResults` is applied to them.
+   *
+   * @param {string} sentences The sentences to check
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Suggestion[] | TimedSuggestions} The list of suggested spellings, with the timings with the `debug` option.
+   */
+  checkAndSuggestUtf16(sentences: string, options?: CheckOptions | undefined | null): Array<Suggestion> | TimedSuggestions
+  /**
+   * Check the sentences in a background task.
+   *
+   * This is the same as `checkAndSuggest()`, but will not block the event loop.
+   *
    * @param {string} sentences The sentences to check
-   * @returns {Suggestion[]} The list of suggested spellings.
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {Promise<Suggestion[] | TimedSuggestions>} The list of suggested spellings, with the timings with the `debug` option.
    */
-  checkAndSuggest(sentences: string): Array<Suggestion>
+  checkAndSuggestAsync(sentences: string, options?: CheckOptions | undefined | null): Promise<Array<Suggestion> | TimedSuggestions>
   /**
    * Add a single word to the spell checker.
    *
@@ -88,6 +1079,9 @@ export declare class SpellChecker {
    * On Windows, this will add the word to the IGNORE list instead of the dictionary.
    * This is done to avoid adding the word permanently to the dictionary,
    *
+   * On macOS, this will learn the word into the user dictionary,
+   * unless the spell checker is created with the `sandboxed` option.
+   *
    * @param {string} word The word to add
    * @returns {void}
    */
@@ -99,10 +1093,173 @@ export declare class SpellChecker {
    * On Windows, this will add the word to the IGNORE list instead of the dictionary.
    * This is done to avoid adding the word permanently to the dictionary,
    *
+   * On macOS, this will learn the word into the user dictionary,
+   * unless the spell checker is created with the `sandboxed` option.
+   *
    * @param {string[]} words The words to add
    * @returns {void}
    */
   addWords(words: Array<string>): void
+  /**
+   * Start an interactive correction session over the text, like a "Spelling…" dialog.
+   *
+   * The misspelled words are visited one at a time, the session keeps the corrected text
+   * and the offsets of the remaining words up to date.
+   *
+   * @param {string} text The text to correct
+   * @param {CheckOptions} [options] The options for checking
+   * @returns {CorrectionSession} The correction session.
+   */
+  correctionSession(text: string, options?: CheckOptions | undefined | null): CorrectionSession
+  /**
+   * Create a new spell checker that shares the expensive backend resources with this one.
+   *
+   * The forked spell checker starts with the same language and options,
+   * but has its own language and session word list afterwards.
+   * This is cheaper than creating a new instance, e.g. for per-tab spell checkers.
+   *
+   * ### Implementation Note
+   * On macOS, each instance applies its own language and ignored words to the shared native
+   * spell checker for every call, only the words learned into the user dictionary are shared.
+   *
+   * On Windows, all the spell checkers and their forks live on a single thread
+   * (the native objects never leave it), every call waits for its turn on that thread.
+   *
+   * @returns {SpellChecker} The new spell checker.
+   */
+  fork(): SpellChecker
+  /**
+   * Get the underlying native spell checker object as an external value.
+   *
+   * This is the `ISpellChecker2` COM pointer on Windows (`ISpellChecker` when it is unavailable)
+   * and the `NSSpellChecker` object on macOS, meant for advanced interop from other native modules.
+   *
+   * This requires the `allowNativeHandle` option when creating the spell checker.
+   *
+   * ### Safety
+   * The handle is borrowed, it is only valid as long as this spell checker is alive
+   * and the language or session is not changed (which may recreate the native object on Windows).
+   *
+   * @returns {ExternalObject<unknown>} The native handle.
+   */
+  unsafeNativeHandle(): ExternalObject<unknown>
+  /**
+   * Reset the session state of the spell checker.
+   *
+   * This clears all the ignored/added words and per-document state for this instance,
+   * without needing to create a new spell checker.
+   *
+   * ### Implementation Note
+   * On macOS, words learned into the user dictionary are persistent and will not be removed,
+   * use the `sandboxed` option to keep added words in the session instead.
+   *
+   * @returns {void}
+   */
+  resetSession(): void
+  /**
+   * Get the words flagged more times than the `proposeAdditions` option in this session,
+   * the most frequent first.
+   *
+   * This is useful to prompt "you've typed 'napi' 14 times, add it to the dictionary?".
+   * The words are no longer proposed once added, or dismissed with `dismissProposal()`.
+   *
+   * @returns {ProposedAddition[]} The proposed words with their counts.
+   */
+  proposedAdditions(): Array<ProposedAddition>
+  /**
+   * Stop proposing the word for the rest of the session, e.g. when the user declined to add it.
+   *
+   * @param {string} word The word to stop proposing
+   * @returns {void}
+   */
+  dismissProposal(word: string): void
+  /**
+   * Get information about where the added words are being stored.
+   *
+   * This is useful to answer "where did my added words go?".
+   *
+   * @returns {DictionaryInfo} The dictionary storage information.
+   */
+  dictionaryInfo(): DictionaryInfo
+  /**
+   * Get the optional features supported by the native spell checker on this system.
+   *
+   * On Windows, some older systems and third-party providers cannot remove words,
+   * `removeWord()` then throws an error.
+   *
+   * @returns {Capabilities} The supported features.
+   */
+  capabilities(): Capabilities
+  /**
+   * Check a few known correct and misspelled words with the native spell checker.
+   *
+   * This tells a broken system dictionary apart from a bug in this library: the probes skip
+   * the custom dictionaries and caches of this library, and do not change any state.
+   * The probe words are bundled for `de`, `en`, `es`, `fr`, `id`, `it`, `nl`, and `pt`,
+   * only a gibberish word is checked for the other languages.
+   *
+   * The failures are reported in the probes instead of being thrown.
+   *
+   * @returns {SelfTestReport} The results of the probes.
+   */
+  selfTest(): SelfTestReport
+  /**
+   * Get the custom dictionaries of this spell checker.
+   *
+   * This includes the personal dictionaries from the `userDictionaries` option, the files
+   * from the `dictionaries` option, and the dictionaries from `downloadDictionary()`.
+   *
+   * @returns {CustomDictionary[]} The list of custom dictionaries.
+   */
+  listDictionaries(): Array<CustomDictionary>
+  /**
+   * Enable or disable a custom dictionary for this spell checker only.
+   *
+   * This is useful to toggle domain dictionaries (e.g. medical or legal) per document type,
+   * the other instances are not affected. A forked spell checker starts with the same state.
+   *
+   * @param {string} id The id of the dictionary, from `listDictionaries()`
+   * @param {boolean} enabled Should the words of the dictionary be accepted
+   * @returns {void}
+   */
+  setDictionaryEnabled(id: string, enabled: boolean): void
+  /**
+   * Get the stems of a word, e.g. `walk` for `walked`, for search or highlight features.
+   *
+   * The stems come from the Hunspell dictionaries of the language (see the `dictionaries` option),
+   * so they agree with the words accepted by the spell checker. A word may have more than one.
+   * The words missing from the dictionaries are stemmed with the Snowball stemmer
+   * of the language, the result is empty when there is none, e.g. for Indonesian.
+   *
+   * @param {string} word The word to stem
+   * @param {string} [language] The language of the word, the current language by default
+   * @returns {string[]} The stems of the word.
+   */
+  stem(word: string, language?: string | undefined | null): Array<string>
+  /**
+   * Get the positions in a word where it can be hyphenated, e.g. `[2, 6]` for `hy-phen-ation`.
+   *
+   * The positions are the UTF-16 offsets before which a hyphen can be inserted, from the patterns
+   * of the `hyphenation` option for the language. This throws when there are no patterns for it.
+   *
+   * @param {string} word The word to hyphenate
+   * @param {string} [language] The language of the word, the current language by default
+   * @returns {number[]} The break points of the word.
+   */
+  hyphenate(word: string, language?: string | undefined | null): Array<number>
+  /**
+   * Find the words of the custom dictionaries similar to a word, the closest first.
+   *
+   * This is useful for "did you mean" features of a search box, which want the words
+   * of the dictionaries rather than the suggestions of the native spell checker.
+   * The enabled dictionaries for the current language are searched, and on macOS the words
+   * added with the `sandboxed` option. The native dictionaries cannot be listed.
+   *
+   * @param {string} word The word to look up
+   * @param {LookupOptions} [options] The options for the lookup
+   * @returns {SimilarWord[]} The similar words, sorted by distance.
+   */
+  lookupSimilar(word: string, options?: LookupOptions | undefined | null): Array<SimilarWord>
   /**
    * Remove a single word from the spell checker.
    *
@@ -117,4 +1274,100 @@ export declare class SpellChecker {
    * @returns {void}
    */
   removeWords(words: Array<string>): void
+  /**
+   * Set the maximum number of calls running at once into the native spell checkers.
+   *
+   * All calls from every spell checker instance go through a single fair queue,
+   * by default only one call is running at once.
+   * Increasing this allows independent per-language spell checkers to run in parallel.
+   *
+   * ### Implementation Note
+   * On macOS, all instances share the same native spell checker,
+   * so the calls into it still run one at a time.
+   *
+   * @param {number} concurrency The maximum number of concurrent calls, at least 1.
+   * @returns {void}
+   */
+  static setMaxConcurrency(concurrency: number): void
+  /**
+   * Get the version and the build of this library, with the state of the native spell checker.
+   *
+   * This is meant for bug reports, a native spell checker is created to probe the platform
+   * components, e.g. the spell checking provider on Windows or the macOS version.
+   *
+   * @returns {BuildInfo} The build info.
+   */
+  static buildInfo(): BuildInfo
+  /**
+   * Add words to the spell checker in a background task.
+   *
+   * This will not block the event loop, which is useful for a large project dictionary.
+   * The spell checker is locked for each chunk of 1000 words, the other calls run between the chunks.
+   *
+   * @param {string[]} words The words to add
+   * @param {Function} [onProgress] Called with the number of added words and the total
+   * @returns {Promise<void>}
+   */
+  addWordsAsync(words: Array<string>, onProgress?: (done: number, total: number) => void): Promise<void>
+  /**
+   * Remove words from the spell checker in a background task.
+   *
+   * This will not block the event loop, which is useful for a large project dictionary.
+   * The spell checker is locked for each chunk of 1000 words, the other calls run between the chunks.
+   *
+   * @param {string[]} words The words to remove
+   * @param {Function} [onProgress] Called with the number of removed words and the total
+   * @returns {Promise<void>}
+   */
+  removeWordsAsync(words: Array<string>, onProgress?: (done: number, total: number) => void): Promise<void>
+}
+/**
+ * An interactive correction session over a text, created with `SpellChecker.correctionSession()`.
+ *
+ * @class CorrectionSession
+ */
+export declare class CorrectionSession {
+  /**
+   * Get the current misspelled word, with the offsets in the corrected text.
+   *
+   * @returns {Suggestion | null} The current misspelled word, or `null` once finished.
+   */
+  current(): Suggestion | null
+  /**
+   * Get the number of the remaining misspelled words, including the current one.
+   *
+   * @returns {number} The number of the remaining words.
+   */
+  remaining(): number
+  /**
+   * Get the text with all the corrections so far.
+   *
+   * @returns {string} The corrected text.
+   */
+  text(): string
+  /**
+   * Replace the current word, e.g. with one of its suggestions, and move to the next one.
+   *
+   * @param {string} suggestion The replacement for the current word
+   * @returns {void}
+   */
+  accept(suggestion: string): void
+  /**
+   * Keep the current word as-is and move to the next one.
+   *
+   * @returns {void}
+   */
+  ignoreOnce(): void
+  /**
+   * Keep the current word and all its remaining occurrences in the text as-is.
+   *
+   * @returns {void}
+   */
+  ignoreAll(): void
+  /**
+   * Add the current word to the spell checker, keeping all its remaining occurrences.
+   *
+   * @returns {void}
+   */
+  addToDictionary(): void
 }
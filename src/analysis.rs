@@ -0,0 +1,169 @@
+//! Aggregate the misspelled words across many documents, e.g. to seed a project dictionary.
+
+use std::collections::HashMap;
+
+use crate::utils::Token;
+
+/// The number of UTF-16 code units kept on each side of the word in a sample context.
+const CONTEXT_LENGTH: usize = 30;
+/// The default number of sample contexts kept for each word.
+pub const MAX_CONTEXTS: usize = 3;
+
+/// A misspelled word found in the documents.
+#[derive(Debug, Clone)]
+pub struct UnknownWord {
+    pub word: String,
+    /// How many times the word was found.
+    pub count: usize,
+    /// How many documents have the word.
+    pub documents: usize,
+    /// Some of the lines where the word was found.
+    pub contexts: Vec<String>,
+    /// The last document which had the word, to count each document once.
+    last_document: usize,
+}
+
+/// Count the misspelled words of each document.
+#[derive(Debug)]
+pub struct UnknownWords {
+    words: HashMap<String, UnknownWord>,
+    max_contexts: usize,
+    documents: usize,
+}
+
+impl UnknownWords {
+    /// Create the analysis, keeping up to `max_contexts` sample contexts for each word.
+    pub fn new(max_contexts: usize) -> Self {
+        UnknownWords {
+            words: HashMap::new(),
+            max_contexts,
+            documents: 0,
+        }
+    }
+
+    /// Add the misspelled words found in the text of a document.
+    pub fn add_document<'a>(&mut self, text: &str, tokens: impl IntoIterator<Item = &'a Token>) {
+        let document = self.documents;
+        self.documents += 1;
+
+        let wide: Vec<u16> = text.encode_utf16().collect();
+        for token in tokens {
+            let entry = self
+                .words
+                .entry(token.word().to_string())
+                .or_insert_with(|| UnknownWord {
+                    word: token.word().to_string(),
+                    count: 0,
+                    documents: 0,
+                    contexts: Vec::new(),
+                    last_document: usize::MAX,
+                });
+
+            entry.count += 1;
+            if entry.last_document != document {
+                entry.last_document = document;
+                entry.documents += 1;
+            }
+            if entry.contexts.len() < self.max_contexts {
+                let context = context(&wide, token.start(), token.end() + 1);
+                if !entry.contexts.contains(&context) {
+                    entry.contexts.push(context);
+                }
+            }
+        }
+    }
+
+    /// Get the words, the most frequent first.
+    pub fn into_ranked(self) -> Vec<UnknownWord> {
+        let mut words: Vec<UnknownWord> = self.words.into_values().collect();
+        words.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(b.documents.cmp(&a.documents))
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        words
+    }
+}
+
+/// Get the text around the word on the same line, without splitting a surrogate pair.
+fn context(wide: &[u16], start: usize, end: usize) -> String {
+    let is_line_break = |unit: &u16| *unit == u16::from(b'\n') || *unit == u16::from(b'\r');
+    let end = end.min(wide.len());
+    let start = start.min(end);
+
+    let line_start = wide[..start]
+        .iter()
+        .rposition(is_line_break)
+        .map_or(0, |index| index + 1);
+    let line_end = wide[end..]
+        .iter()
+        .position(is_line_break)
+        .map_or(wide.len(), |index| end + index);
+
+    let mut from = start.saturating_sub(CONTEXT_LENGTH).max(line_start);
+    let mut to = (end + CONTEXT_LENGTH).min(line_end);
+    if wide
+        .get(from)
+        .is_some_and(|unit| (0xDC00..=0xDFFF).contains(unit))
+    {
+        from += 1;
+    }
+    if to > from && (0xD800..=0xDBFF).contains(&wide[to - 1]) {
+        to -= 1;
+    }
+
+    String::from_utf16_lossy(&wide[from..to]).trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str, word: &str) -> Vec<Token> {
+        text.match_indices(word)
+            .map(|(index, _)| {
+                let start = text[..index].encode_utf16().count();
+                Token::new(
+                    start,
+                    start + word.encode_utf16().count() - 1,
+                    word.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ranked() {
+        let mut analysis = UnknownWords::new(2);
+        let first = "The tset and another tset.\nA snetences here.";
+        let second = "One more tset, at the very end of a rather long line of text";
+        let first_tokens = [tokens(first, "tset"), tokens(first, "snetences")].concat();
+        analysis.add_document(first, &first_tokens);
+        analysis.add_document(second, &tokens(second, "tset"));
+
+        let words = analysis.into_ranked();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "tset");
+        assert_eq!(words[0].count, 3);
+        assert_eq!(words[0].documents, 2);
+        assert_eq!(
+            words[0].contexts,
+            vec![
+                "The tset and another tset.",
+                "One more tset, at the very end of a rather"
+            ]
+        );
+        assert_eq!(words[1].word, "snetences");
+        assert_eq!(words[1].contexts, vec!["A snetences here."]);
+    }
+
+    #[test]
+    fn test_context() {
+        let wide: Vec<u16> = "😀 ab".encode_utf16().collect();
+        assert_eq!(context(&wide, 3, 5), "😀 ab");
+        // The line is not crossed
+        let wide: Vec<u16> = "first\nsecond\nthird".encode_utf16().collect();
+        assert_eq!(context(&wide, 6, 12), "second");
+    }
+}
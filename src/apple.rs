@@ -1,43 +1,200 @@
 //! Apple-specific implementation of the spell checker.
+//!
+//! There is a single `NSSpellChecker` per process, so each instance keeps its own state
+//! (document tag, language, ignored words) and applies it to the shared checker for every call.
 
-use std::ptr::NonNull;
+use std::{
+    ptr::NonNull,
+    sync::{Mutex, PoisonError},
+};
 
-use objc2::rc::{Retained, autoreleasepool};
+use objc2::{
+    AllocAnyThread,
+    rc::{Retained, autoreleasepool},
+};
 use objc2_app_kit::NSSpellChecker;
-use objc2_foundation::{NSRange, NSString, NSTextCheckingType};
+use objc2_foundation::{NSArray, NSInteger, NSRange, NSString, NSTextCheckingType};
 
 use crate::{
     SpellCheckerImpl,
-    utils::{EjaanError, Token, TokenWithSuggestions},
+    overlay::OverlayDictionary,
+    timings::Phase,
+    utils::{
+        Capabilities, CheckOptions, DictionaryInfo, DictionaryStorage, EjaanError, Token,
+        TokenWithSuggestions,
+    },
 };
 
+/// Serializes the calls into the shared `NSSpellChecker`, so the language of one instance
+/// is never used by the call of another one, even with a higher call concurrency.
+static SHARED_CHECKER: Mutex<()> = Mutex::new(());
+
 pub struct AppleSpellChecker {
     shared: Retained<NSSpellChecker>,
+    /// The spell document tag used by this instance.
+    tag: NSInteger,
+    /// The language of this instance, `None` to identify the language automatically.
+    language: Option<String>,
+    /// When enabled, added words are ignored in our document instead of learned.
+    sandboxed: bool,
+    /// The words added while in sandboxed mode.
+    overlay: OverlayDictionary,
 }
 
+// SAFETY: NSSpellChecker is not bound to the main thread for checking and learning words.
+// Access to the shared spell checker is serialized by `SHARED_CHECKER`.
+unsafe impl Send for AppleSpellChecker {}
+
 impl AppleSpellChecker {
     /// Creates a shared instance of the Apple spell checker.
-    pub fn new() -> Self {
+    ///
+    /// In sandboxed mode, added words will never be written to the user dictionary.
+    pub fn new(sandboxed: bool) -> Self {
         unsafe {
             let shared = NSSpellChecker::sharedSpellChecker();
-            // By default, we guess the language automatically.
-            shared.setAutomaticallyIdentifiesLanguages(true);
-            Self { shared }
+            let tag = NSSpellChecker::uniqueSpellDocumentTag();
+            Self {
+                shared,
+                tag,
+                // By default, we guess the language automatically.
+                language: None,
+                sandboxed,
+                overlay: OverlayDictionary::new(),
+            }
+        }
+    }
+
+    /// Run `f` with the shared spell checker configured for this instance.
+    ///
+    /// This must never be nested, the lock is not reentrant.
+    fn configured<R>(&self, f: impl FnOnce(&NSSpellChecker) -> R) -> R {
+        let _guard = SHARED_CHECKER
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        unsafe {
+            match &self.language {
+                Some(language) => {
+                    self.shared.setAutomaticallyIdentifiesLanguages(false);
+                    self.shared.setLanguage(&NSString::from_str(language));
+                }
+                None => self.shared.setAutomaticallyIdentifiesLanguages(true),
+            }
         }
+        f(&self.shared)
+    }
+
+    /// Get the language used for the checks, the configured one or the identified one.
+    fn current_language(shared: &NSSpellChecker) -> Option<String> {
+        let language = unsafe { shared.language() };
+        (!language.is_empty()).then(|| language.to_string())
     }
 
-    fn suggest<S: AsRef<str>>(&self, word: S) -> Vec<String> {
+    /// Replace the ignored words of our document with the overlay dictionary.
+    fn sync_ignored_words(&self) {
+        let words: Vec<_> = self
+            .overlay
+            .words()
+            .iter()
+            .map(|word| NSString::from_str(word))
+            .collect();
+        let ns_words = NSArray::from_retained_slice(&words);
+        self.configured(|shared| unsafe {
+            shared.setIgnoredWords_inSpellDocumentWithTag(&ns_words, self.tag)
+        });
+    }
+
+    /// Check the spelling of the string, returning the misspelled words with their suggestions.
+    fn check_ns_string(
+        &self,
+        ns_string: &NSString,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.configured(|shared| self.check_configured(shared, ns_string, options))
+    }
+
+    fn check_configured(
+        &self,
+        shared: &NSSpellChecker,
+        ns_string: &NSString,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // The language used for all the results of this check
+        let language = Self::current_language(shared);
+
+        let mut numbers: isize = 0;
+        let mispellings = unsafe {
+            shared.checkString_range_types_options_inSpellDocumentWithTag_orthography_wordCount(
+                ns_string,
+                NSRange::new(0, ns_string.length()),
+                NSTextCheckingType::Spelling.0,
+                None,
+                self.tag,
+                None,
+                &mut numbers,
+            )
+        };
+
+        let mut misspelling = Vec::with_capacity(numbers.try_into().unwrap_or(ns_string.length()));
+        let counter = mispellings.count();
+        for i in 0..counter {
+            if options.is_full(misspelling.len()) {
+                // Skip the suggestions lookup for the remaining results
+                break;
+            }
+
+            let result = mispellings.objectAtIndex(i);
+            let ranges = unsafe { result.range() };
+            if ranges.is_empty() {
+                // In case the range is empty, skip this result
+                continue;
+            }
+
+            let buffer_size = ranges.length.saturating_mul(2);
+            let mut buffers = vec![0u16; buffer_size];
+            unsafe {
+                ns_string.getCharacters_range(
+                    NonNull::new(buffers.as_mut_ptr()).ok_or(crate::utils::Error::new(format!(
+                        "Failed to initialize buffer for misspelled word at range: {:#?}",
+                        ranges
+                    )))?,
+                    ranges,
+                )
+            };
+            let text_data = String::from_utf16_lossy(&buffers)
+                .trim_end_matches('\0')
+                .to_string();
+            if self.sandboxed && self.overlay.contains(&text_data) {
+                // Make sure the words we added are never reported
+                continue;
+            }
+
+            let st_index = ranges.location;
+            let end_index = (st_index + ranges.length).saturating_sub(1);
+            let suggestions = options
+                .timings()
+                .time(Phase::Suggestions, || self.suggest(shared, &text_data));
+            misspelling.push(
+                TokenWithSuggestions::new(Token::new(st_index, end_index, text_data), suggestions)
+                    .with_language(language.clone()),
+            );
+        }
+
+        // Trim the size of capacity until the actual length
+        misspelling.shrink_to_fit();
+        Ok(misspelling)
+    }
+
+    fn suggest<S: AsRef<str>>(&self, shared: &NSSpellChecker, word: S) -> Vec<String> {
         let ns_word = NSString::from_str(word.as_ref());
         let range = NSRange::new(0, ns_word.len());
-        let language = unsafe { self.shared.language() };
+        let language = unsafe { shared.language() };
         let suggestions = unsafe {
-            self.shared
-                .guessesForWordRange_inString_language_inSpellDocumentWithTag(
-                    range,
-                    &ns_word,
-                    Some(&language),
-                    0,
-                )
+            shared.guessesForWordRange_inString_language_inSpellDocumentWithTag(
+                range,
+                &ns_word,
+                Some(&language),
+                self.tag,
+            )
         };
         if let Some(suggestions) = suggestions {
             // Convert NSArray to Vec<String>
@@ -57,29 +214,116 @@ impl AppleSpellChecker {
     }
 }
 
+impl Drop for AppleSpellChecker {
+    fn drop(&mut self) {
+        // Release the ignored words of our document
+        self.configured(|shared| unsafe { shared.closeSpellDocumentWithTag(self.tag) });
+    }
+}
+
 impl SpellCheckerImpl for AppleSpellChecker {
     fn add_word(&self, word: &str) -> EjaanError<()> {
         // &str -> NSString
         let ns_word = NSString::from_str(word);
-        unsafe { self.shared.learnWord(&ns_word) };
+        if self.sandboxed {
+            // Ignore the word only in our document, so nothing is written to the user dictionary.
+            if self.overlay.add(word) {
+                self.configured(|shared| unsafe {
+                    shared.ignoreWord_inSpellDocumentWithTag(&ns_word, self.tag)
+                });
+            }
+        } else {
+            // Learned words are shared by every instance and application, see `dictionary_info`
+            self.configured(|shared| unsafe { shared.learnWord(&ns_word) });
+        }
         Ok(())
     }
 
     fn remove_word(&self, word: &str) -> EjaanError<()> {
+        if self.sandboxed {
+            // There is no API to un-ignore a single word, so we replace the whole list.
+            if self.overlay.remove(word) {
+                self.sync_ignored_words();
+            }
+            return Ok(());
+        }
+
         // &str -> NSString
         let ns_word = NSString::from_str(word);
-        unsafe {
-            if self.shared.hasLearnedWord(&ns_word) {
-                self.shared.unlearnWord(&ns_word);
+        self.configured(|shared| unsafe {
+            if shared.hasLearnedWord(&ns_word) {
+                shared.unlearnWord(&ns_word);
             }
-        }
+        });
         Ok(())
     }
 
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        Some(Retained::as_ptr(&self.shared) as *mut std::ffi::c_void)
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        // Only the sandboxed words are known, the learned words cannot be listed
+        self.overlay.similar(word, max_distance)
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        // The native spell checker is shared already, we only need a new document
+        let mut forked = Self::new(self.sandboxed);
+        forked.language = self.language.clone();
+        Ok(Box::new(forked))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        // Drop the ignored words of the current document and start a new one
+        self.configured(|shared| unsafe { shared.closeSpellDocumentWithTag(self.tag) });
+        self.tag = unsafe { NSSpellChecker::uniqueSpellDocumentTag() };
+        self.overlay.clear();
+
+        Ok(())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        if self.sandboxed {
+            return Ok(DictionaryInfo::new(
+                "NSSpellChecker",
+                DictionaryStorage::Overlay,
+                None,
+                false,
+            ));
+        }
+
+        // Learned words are stored in the user spelling dictionary,
+        // for sandboxed apps this will be inside the app container instead.
+        let location = std::env::var("HOME")
+            .ok()
+            .map(|home| format!("{}/Library/Spelling/LocalDictionary", home));
+        Ok(DictionaryInfo::new(
+            "NSSpellChecker",
+            DictionaryStorage::System,
+            location,
+            true,
+        ))
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        // Learned and ignored words can both be removed, see `remove_word`
+        Ok(Capabilities::new(true, true))
+    }
+
     fn set_language(&mut self, language: &str) -> EjaanError<bool> {
         // &str -> NSString
         let ns_language = NSString::from_str(language);
-        Ok(unsafe { self.shared.setLanguage(&ns_language) })
+        // Only used to find out if the language is supported, each call applies its own language
+        let supported = self.configured(|shared| unsafe { shared.setLanguage(&ns_language) });
+        if supported {
+            self.language = Some(language.to_string());
+        }
+        Ok(supported)
     }
 
     fn get_available_languages(&self) -> EjaanError<Vec<String>> {
@@ -101,74 +345,50 @@ impl SpellCheckerImpl for AppleSpellChecker {
 
     fn check_word(&self, word: &str) -> EjaanError<bool> {
         let ns_word = NSString::from_str(word);
-        let ranges = unsafe { self.shared.checkSpellingOfString_startingAt(&ns_word, 0) };
+        let ns_language = self.language.as_deref().map(NSString::from_str);
+        let ranges = self.configured(|shared| unsafe {
+            shared.checkSpellingOfString_startingAt_language_wrap_inSpellDocumentWithTag_wordCount(
+                &ns_word,
+                0,
+                ns_language.as_deref(),
+                false,
+                self.tag,
+                std::ptr::null_mut(),
+            )
+        });
         // If the range is empty, the word is spelled correctly
         Ok(ranges.is_empty())
     }
 
     fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
-        let ns_string = NSString::from_str(sentence);
-
-        let mut numbers: isize = 0;
-        let mispellings = unsafe {
-            self.shared
-                .checkString_range_types_options_inSpellDocumentWithTag_orthography_wordCount(
-                    &ns_string,
-                    NSRange::new(0, ns_string.length()),
-                    NSTextCheckingType::Spelling.0,
-                    None,
-                    0,
-                    None,
-                    &mut numbers,
-                )
-        };
-
-        let mut misspelling = Vec::with_capacity(numbers.try_into().unwrap_or(ns_string.length()));
-        let counter = mispellings.count();
-        for i in 0..counter {
-            let result = mispellings.objectAtIndex(i);
-            let ranges = unsafe { result.range() };
-            if ranges.is_empty() {
-                // In case the range is empty, skip this result
-                continue;
-            }
-
-            let buffer_size = ranges.length.saturating_mul(2);
-            let mut buffers = vec![0u16; buffer_size];
-            unsafe {
-                ns_string.getCharacters_range(
-                    NonNull::new(buffers.as_mut_ptr()).ok_or(crate::utils::Error::new(format!(
-                        "Failed to initialize buffer for misspelled word at range: {:#?}",
-                        ranges
-                    )))?,
-                    ranges,
-                )
-            };
-            let text_data = String::from_utf16_lossy(&buffers)
-                .trim_end_matches('\0')
-                .to_string();
+        self.check_ns_string(&NSString::from_str(sentence), &CheckOptions::new())
+    }
 
-            let st_index = ranges.location;
-            let end_index = (st_index + ranges.length).saturating_sub(1);
-            let suggestions = self.suggest(&text_data);
-            misspelling.push(TokenWithSuggestions::new(
-                Token::new(st_index, end_index, text_data),
-                suggestions,
-            ));
-        }
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_ns_string(&NSString::from_str(sentence), options)
+    }
 
-        // Trim the size of capacity until the actual length
-        misspelling.shrink_to_fit();
-        Ok(misspelling)
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // NSString is UTF-16 already, so the code units are copied as-is
+        let ns_string = unsafe {
+            NSString::initWithCharacters_length(
+                NSString::alloc(),
+                NonNull::new(sentence.as_ptr() as *mut u16).unwrap_or(NonNull::dangling()),
+                sentence.len(),
+            )
+        };
+        self.check_ns_string(&ns_string, &CheckOptions::new())
     }
 
     fn get_language(&self) -> EjaanError<Option<String>> {
-        let language = unsafe { self.shared.language() };
-        if language.is_empty() {
-            Ok(None) // No language set
-        } else {
-            Ok(Some(language.to_string()))
+        if let Some(language) = &self.language {
+            return Ok(Some(language.clone()));
         }
+        Ok(self.configured(Self::current_language))
     }
 }
 
@@ -178,7 +398,7 @@ mod tests {
 
     #[test]
     fn test_simple_spellcheck() {
-        let spell_checker = AppleSpellChecker::new();
+        let spell_checker = AppleSpellChecker::new(false);
         let word = "test";
         let is_correct = spell_checker
             .check_word(word)
@@ -192,7 +412,7 @@ mod tests {
 
     #[test]
     fn test_simple_sentences() {
-        let spell_checker = AppleSpellChecker::new();
+        let spell_checker = AppleSpellChecker::new(false);
         let sentence = "This is a test sentence.";
         let tokens = spell_checker
             .check_sentences(sentence)
@@ -207,7 +427,7 @@ mod tests {
 
     #[test]
     fn test_simple_sentences_with_typos() {
-        let spell_checker = AppleSpellChecker::new();
+        let spell_checker = AppleSpellChecker::new(false);
         let sentence = "This is a tset sentence.";
         let tokens = spell_checker
             .check_sentences(sentence)
@@ -232,4 +452,101 @@ mod tests {
             "Expected suggestions for the misspelled word"
         );
     }
+
+    #[test]
+    fn test_sandboxed_add_word() {
+        let spell_checker = AppleSpellChecker::new(true);
+        let sentence = "This is a tset sentence.";
+
+        spell_checker.add_word("tset").expect("Failed to add word");
+        let tokens = spell_checker
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert!(tokens.is_empty(), "Expected the added word to be ignored");
+        assert!(
+            !unsafe {
+                spell_checker
+                    .shared
+                    .hasLearnedWord(&NSString::from_str("tset"))
+            },
+            "Expected the word to not be learned by the system"
+        );
+
+        spell_checker
+            .remove_word("tset")
+            .expect("Failed to remove word");
+        let tokens = spell_checker
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert_eq!(
+            tokens.len(),
+            1,
+            "Expected the removed word to be misspelled again"
+        );
+    }
+
+    #[test]
+    fn test_fork() {
+        let spell_checker = AppleSpellChecker::new(true);
+        let sentence = "This is a tset sentence.";
+
+        let forked = spell_checker.fork().expect("Failed to fork spell checker");
+        forked.add_word("tset").expect("Failed to add word");
+
+        let tokens = forked
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert!(tokens.is_empty(), "Expected the added word to be ignored");
+
+        let tokens = spell_checker
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert_eq!(
+            tokens.len(),
+            1,
+            "Expected the added word to only affect the forked instance"
+        );
+    }
+
+    #[test]
+    fn test_isolated_language() {
+        let mut first = AppleSpellChecker::new(true);
+        let mut second = AppleSpellChecker::new(true);
+        let languages = first
+            .get_available_languages()
+            .expect("Failed to get languages");
+        if languages.len() < 2 {
+            return;
+        }
+
+        assert!(first.set_language(&languages[0]).unwrap());
+        assert!(second.set_language(&languages[1]).unwrap());
+        assert_eq!(first.get_language().unwrap(), Some(languages[0].clone()));
+        assert_eq!(second.get_language().unwrap(), Some(languages[1].clone()));
+
+        // The ignored words stay in their own document
+        first.add_word("tset").expect("Failed to add word");
+        assert!(first.check_word("tset").unwrap());
+        assert!(!second.check_word("tset").unwrap());
+    }
+
+    #[test]
+    fn test_reset_session() {
+        let mut spell_checker = AppleSpellChecker::new(true);
+        let sentence = "This is a tset sentence.";
+
+        spell_checker.add_word("tset").expect("Failed to add word");
+        spell_checker
+            .reset_session()
+            .expect("Failed to reset session");
+
+        let tokens = spell_checker
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert_eq!(
+            tokens.len(),
+            1,
+            "Expected the added word to be cleared after reset"
+        );
+    }
 }
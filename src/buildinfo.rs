@@ -0,0 +1,145 @@
+//! The build and platform details for the bug reports, see `buildInfo()`.
+//!
+//! The native spell checker is probed on its own, without the layers of this library,
+//! so the components describe what is installed on the machine.
+
+use crate::utils::EjaanError;
+
+/// The name of the native spell checker backend of this build.
+#[cfg(target_os = "windows")]
+const BACKEND: &str = "windows";
+#[cfg(target_os = "macos")]
+const BACKEND: &str = "macos";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const BACKEND: &str = "unsupported";
+
+/// The build of this library and the state of the native spell checker.
+#[derive(Debug, Clone)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    /// The enabled cargo features.
+    pub features: Vec<&'static str>,
+    /// Is this a build with the debug assertions.
+    pub debug: bool,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub backend: &'static str,
+    /// Can the native spell checker be created.
+    pub available: bool,
+    /// The error from creating the native spell checker.
+    pub error: Option<String>,
+    /// The name and value of each platform component, e.g. the spell checking provider.
+    pub components: Vec<(String, String)>,
+}
+
+/// Collect the build info, creating a native spell checker to probe the platform.
+pub fn collect() -> BuildInfo {
+    let mut features = Vec::new();
+    if cfg!(feature = "serde") {
+        features.push("serde");
+    }
+
+    let mut components = Vec::new();
+    let error = probe(&mut components).err();
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        features,
+        debug: cfg!(debug_assertions),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        backend: BACKEND,
+        available: error.is_none(),
+        error: error.map(|error| error.message().to_string()),
+        components,
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn probe(components: &mut Vec<(String, String)>) -> EjaanError<()> {
+    use crate::{SpellCheckerImpl, utils::Error, winrt};
+
+    // COM is initialized on a thread of its own, like the owner thread of the spell checkers
+    let probed = std::thread::spawn(|| {
+        let mut probed = Vec::new();
+        // The spell checker is released before `_com` uninitializes COM
+        let result = winrt::init_thread().and_then(|_com| {
+            let checker = winrt::WindowsSpellChecker::new()?;
+            let (id, name) = checker.provider()?;
+            probed.push(("provider".to_string(), id));
+            probed.push(("providerName".to_string(), name));
+            let backend = checker.dictionary_info()?.backend().to_string();
+            probed.push(("interface".to_string(), backend));
+            let language = checker.get_language()?.unwrap_or_default();
+            probed.push(("language".to_string(), language));
+            let languages = checker.get_available_languages()?.join(", ");
+            probed.push(("languages".to_string(), languages));
+            Ok(())
+        });
+        (probed, result)
+    })
+    .join()
+    .map_err(|_| Error::new("The Windows spell checker panicked while probing it"))?;
+
+    components.extend(probed.0);
+    probed.1
+}
+
+#[cfg(target_os = "macos")]
+fn probe(components: &mut Vec<(String, String)>) -> EjaanError<()> {
+    use objc2::runtime::AnyClass;
+    use objc2_foundation::NSProcessInfo;
+
+    use crate::{
+        SpellCheckerImpl, apple,
+        utils::{Error, ErrorKind},
+    };
+
+    let version = NSProcessInfo::processInfo().operatingSystemVersionString();
+    components.push(("macOS".to_string(), version.to_string()));
+    let present = AnyClass::get(c"NSSpellChecker").is_some();
+    components.push((
+        "AppKit".to_string(),
+        match present {
+            true => "present",
+            false => "missing",
+        }
+        .to_string(),
+    ));
+    if !present {
+        return Err(Error::with_kind(
+            ErrorKind::BackendUnavailable,
+            "NSSpellChecker is not available, AppKit is not loaded",
+        ));
+    }
+
+    let checker = apple::AppleSpellChecker::new(false);
+    let languages = checker.get_available_languages()?.join(", ");
+    components.push(("languages".to_string(), languages));
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn probe(_components: &mut Vec<(String, String)>) -> EjaanError<()> {
+    use crate::SpellCheckerImpl;
+
+    crate::stubs::StubSpellChecker::new()
+        .get_available_languages()
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let info = collect();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.os, std::env::consts::OS);
+        assert_eq!(info.available, info.error.is_none());
+        if cfg!(not(any(target_os = "macos", target_os = "windows"))) {
+            // The stub backend is never available
+            assert!(!info.available);
+        }
+    }
+}
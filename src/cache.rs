@@ -0,0 +1,360 @@
+//! Cache the results of each paragraph, so re-checking an edited document
+//! only calls the native spell checker for the changed paragraphs.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    SpellCheckerImpl,
+    dictionaries::CustomDictionary,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, TokenWithSuggestions},
+};
+
+/// The number of paragraphs in the cache before it is cleared.
+const CACHE_CAPACITY: usize = 10_000;
+
+/// The statistics of the paragraph cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The number of cached paragraphs.
+    pub entries: usize,
+    /// The number of paragraphs found in the cache.
+    pub hits: usize,
+    /// The number of paragraphs checked by the native spell checker.
+    pub misses: usize,
+}
+
+/// The results of each paragraph, keyed by the hash of the language and the paragraph.
+#[derive(Debug, Default)]
+pub struct ParagraphCache {
+    /// The misspelled words of each paragraph, with the offsets inside the paragraph.
+    results: HashMap<u64, Vec<TokenWithSuggestions>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl ParagraphCache {
+    fn key(language: &str, paragraph: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        language.hash(&mut hasher);
+        paragraph.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get the results of the paragraph, checking it on a miss.
+    ///
+    /// The results are only cached when `check` returns every misspelled word.
+    pub fn get_or_check(
+        &mut self,
+        language: &str,
+        paragraph: &str,
+        check: impl FnOnce() -> EjaanError<(Vec<TokenWithSuggestions>, bool)>,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let key = Self::key(language, paragraph);
+        if let Some(tokens) = self.results.get(&key) {
+            self.hits += 1;
+            return Ok(tokens.clone());
+        }
+
+        self.misses += 1;
+        let (tokens, complete) = check()?;
+        if complete {
+            if self.results.len() >= CACHE_CAPACITY {
+                // Start over instead of tracking the least recently used paragraphs
+                self.results.clear();
+            }
+            self.results.insert(key, tokens.clone());
+        }
+        Ok(tokens)
+    }
+
+    /// Remove all the cached results, e.g. after the dictionary is changed.
+    pub fn clear(&mut self) {
+        self.results.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.results.len(),
+            hits: self.hits,
+            misses: self.misses,
+        }
+    }
+}
+
+/// Split the text into paragraphs separated by blank lines.
+///
+/// Returns each paragraph with its start offset in UTF-16 code units,
+/// the line breaks between the paragraphs are kept at the end of the previous one.
+pub fn paragraphs(text: &str) -> Vec<(usize, &str)> {
+    let mut paragraphs = Vec::new();
+    let (mut start, mut offset) = (0, 0);
+    let mut in_paragraph = false;
+    let mut units = 0;
+
+    for line in text.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        if !blank && !in_paragraph && offset > start {
+            paragraphs.push((units, &text[start..offset]));
+            units += text[start..offset].encode_utf16().count();
+            start = offset;
+        }
+        in_paragraph = !blank;
+        offset += line.len();
+    }
+    if offset > start {
+        paragraphs.push((units, &text[start..offset]));
+    }
+    paragraphs
+}
+
+/// A spell checker reusing the results of the unchanged paragraphs.
+///
+/// The cache is only used when the `cache` check option is enabled.
+pub struct CachedSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    /// The current language, part of the cache key.
+    language: String,
+    cache: RefCell<ParagraphCache>,
+}
+
+impl CachedSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>) -> Self {
+        let language = inner.get_language().ok().flatten().unwrap_or_default();
+        CachedSpellChecker {
+            inner,
+            language,
+            cache: RefCell::default(),
+        }
+    }
+
+    fn forget(&self) {
+        self.cache.borrow_mut().clear();
+    }
+}
+
+impl SpellCheckerImpl for CachedSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        self.inner.check_word(word)
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.inner.check_sentences(sentence)
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        if !options.cache() {
+            return self.inner.check_sentences_with(sentence, options);
+        }
+
+        let mut cache = self.cache.borrow_mut();
+        let mut tokens = Vec::new();
+        for (start, paragraph) in paragraphs(sentence) {
+            if options.is_full(tokens.len()) {
+                break;
+            }
+
+            // Only ask for the remaining results
+            let remaining = options
+                .max_results()
+                .map(|max_results| max_results - tokens.len());
+            let paragraph_options = options.clone().with_max_results(remaining);
+            let results = cache.get_or_check(&self.language, paragraph, || {
+                let results = self
+                    .inner
+                    .check_sentences_with(paragraph, &paragraph_options)?;
+                let complete = !paragraph_options.is_full(results.len());
+                Ok((results, complete))
+            })?;
+            tokens.extend(
+                results
+                    .into_iter()
+                    .take(remaining.unwrap_or(usize::MAX))
+                    .map(|token| token.offset(start)),
+            );
+        }
+
+        Ok(tokens)
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.inner.check_sentences_utf16(sentence)
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)?;
+        self.forget();
+        Ok(())
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)?;
+        self.forget();
+        Ok(())
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.add_words(words)?;
+        self.forget();
+        Ok(())
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        // Forget even on failure, some words may have been added already
+        let result = self.inner.add_words_chunked(words, on_chunk);
+        self.forget();
+        result
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)?;
+        self.forget();
+        Ok(())
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        let result = self.inner.remove_words_chunked(words, on_chunk);
+        self.forget();
+        result
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        Ok(Box::new(Self::new(self.inner.fork()?)))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.inner.reset_session()?;
+        self.forget();
+        Ok(())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.inner.custom_dictionaries()
+    }
+
+    fn fingerprint(&self, state: &mut dyn Hasher) {
+        self.inner.fingerprint(state)
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        let changed = self.inner.set_dictionary_enabled(id, enabled);
+        if changed {
+            self.forget();
+        }
+        changed
+    }
+
+    fn clear_cache(&self) {
+        self.forget();
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        self.cache.borrow().stats()
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        let changed = self.inner.set_language(language)?;
+        if changed {
+            self.language = language.to_string();
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Token;
+
+    #[test]
+    fn test_paragraphs() {
+        let text = "First 😀 line\nsecond line\n\n\nNext\r\n  \r\nLast";
+        assert_eq!(
+            paragraphs(text),
+            vec![
+                (0, "First 😀 line\nsecond line\n\n\n"),
+                (28, "Next\r\n  \r\n"),
+                (38, "Last")
+            ]
+        );
+        assert_eq!(paragraphs(""), vec![]);
+    }
+
+    #[test]
+    fn test_cache() {
+        let mut cache = ParagraphCache::default();
+        let token = || TokenWithSuggestions::new(Token::new(0, 3, "tset".into()), Vec::new());
+
+        let tokens = cache
+            .get_or_check("en", "tset", || Ok((vec![token()], true)))
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        let tokens = cache
+            .get_or_check("en", "tset", || panic!("should be cached"))
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+
+        // The language is part of the key, and incomplete results are not cached
+        cache
+            .get_or_check("id", "tset", || Ok((vec![token()], false)))
+            .unwrap();
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                entries: 1,
+                hits: 1,
+                misses: 2
+            }
+        );
+
+        cache.clear();
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
@@ -0,0 +1,102 @@
+//! Locale-aware case mapping, picked from the language of the spell checker.
+//!
+//! The default Unicode mapping is wrong for a few languages: Turkish and Azerbaijani have
+//! a dotted and a dotless `i`, and German writes `ß` as `SS` in uppercase text.
+
+/// The case mapping rules of a language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Casing {
+    /// The default Unicode case mapping.
+    #[default]
+    Default,
+    /// Turkish and Azerbaijani, `I` is the uppercase of `ı` and `İ` is the uppercase of `i`.
+    Turkic,
+    /// German, the uppercase `SS` may be a lowercase `ß`.
+    German,
+}
+
+impl Casing {
+    /// Get the case mapping of the language, by its primary subtag.
+    pub fn for_language(language: Option<&str>) -> Casing {
+        let primary = language
+            .and_then(|language| language.split(['-', '_']).next())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "tr" | "az" => Casing::Turkic,
+            "de" => Casing::German,
+            _ => Casing::Default,
+        }
+    }
+
+    pub fn lowercase(&self, word: &str) -> String {
+        match self {
+            Casing::Turkic => word
+                .chars()
+                .map(|c| match c {
+                    'I' => 'ı',
+                    'İ' => 'i',
+                    c => c,
+                })
+                .collect::<String>()
+                .to_lowercase(),
+            _ => word.to_lowercase(),
+        }
+    }
+
+    /// Get the forms of the word to look up in a dictionary, the word itself first.
+    ///
+    /// A capitalized or uppercase word also matches its lowercase entry, e.g. `Istanbul` is
+    /// `ıstanbul` in Turkish, and the German `STRASSE` matches both `strasse` and `straße`.
+    pub fn lookup_forms(&self, word: &str) -> Vec<String> {
+        let mut forms = vec![word.to_string()];
+        let lowercase = self.lowercase(word);
+        if *self == Casing::German && word.chars().all(|c| !c.is_lowercase()) {
+            for (index, _) in lowercase.match_indices("ss") {
+                let sharp = format!("{}ß{}", &lowercase[..index], &lowercase[index + 2..]);
+                if !forms.contains(&sharp) {
+                    forms.push(sharp);
+                }
+            }
+        }
+        if !forms.contains(&lowercase) {
+            forms.insert(1, lowercase);
+        }
+        forms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_language() {
+        assert_eq!(Casing::for_language(Some("tr-TR")), Casing::Turkic);
+        assert_eq!(Casing::for_language(Some("az_AZ")), Casing::Turkic);
+        assert_eq!(Casing::for_language(Some("de-CH")), Casing::German);
+        assert_eq!(Casing::for_language(Some("en-US")), Casing::Default);
+        assert_eq!(Casing::for_language(None), Casing::Default);
+    }
+
+    #[test]
+    fn test_lowercase() {
+        assert_eq!(Casing::Turkic.lowercase("DİYARBAKIR"), "diyarbakır");
+        assert_eq!(Casing::Default.lowercase("DİYARBAKIR"), "di̇yarbakir");
+        assert_eq!(Casing::German.lowercase("Straße"), "straße");
+    }
+
+    #[test]
+    fn test_lookup_forms() {
+        assert_eq!(
+            Casing::German.lookup_forms("STRASSE"),
+            ["STRASSE", "strasse", "straße"]
+        );
+        assert_eq!(Casing::German.lookup_forms("Klasse"), ["Klasse", "klasse"]);
+        assert_eq!(
+            Casing::Turkic.lookup_forms("Isparta"),
+            ["Isparta", "ısparta"]
+        );
+        assert_eq!(Casing::Default.lookup_forms("walk"), ["walk"]);
+    }
+}
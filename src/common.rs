@@ -0,0 +1,349 @@
+//! Bundled lists of the most common words, checked before calling the native spell checker.
+//!
+//! The overwhelmingly common words are always spelled correctly, a hash lookup
+//! avoids paying the COM/Objective-C overhead for them. The lists are generated from a word
+//! frequency corpus with `tools/common_words.py`, see `data/common/README.md`.
+
+use std::{
+    collections::HashSet,
+    hash::{Hash, Hasher},
+    sync::{Arc, OnceLock},
+};
+
+use crate::{
+    SpellCheckerImpl,
+    dictionaries::CustomDictionary,
+    tokenizer::Tokenizer,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, TokenWithSuggestions},
+};
+
+/// The bundled word lists, keyed by the primary language subtag.
+const BUNDLED_WORDS: &[(&str, &str)] = &[
+    ("en", include_str!("../data/common/en.txt")),
+    ("id", include_str!("../data/common/id.txt")),
+];
+
+/// A set of common words for a single language.
+#[derive(Debug)]
+pub struct CommonWords {
+    words: HashSet<&'static str>,
+}
+
+impl CommonWords {
+    fn parse(list: &'static str) -> Self {
+        CommonWords {
+            words: list
+                .lines()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Get the bundled common words for the language, e.g. `en-US` or `id_ID`.
+    pub fn for_language(language: &str) -> Option<&'static CommonWords> {
+        static PARSED: OnceLock<Vec<(&'static str, CommonWords)>> = OnceLock::new();
+
+        let primary = language.split(['-', '_']).next()?.to_ascii_lowercase();
+        PARSED
+            .get_or_init(|| {
+                BUNDLED_WORDS
+                    .iter()
+                    .map(|(language, list)| (*language, CommonWords::parse(list)))
+                    .collect()
+            })
+            .iter()
+            .find(|(language, _)| *language == primary)
+            .map(|(_, words)| words)
+    }
+
+    /// Check if the word is a common word, as listed, capitalized, or in all caps.
+    ///
+    /// The other casings are left to the native spell checker, it flags e.g. `THe` and `i`.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        let lowercase = word.to_lowercase();
+        if !self.words.contains(lowercase.as_str()) {
+            return false;
+        }
+        let mut chars = lowercase.chars();
+        let capitalized = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>());
+        capitalized.as_deref() == Some(word) || lowercase.to_uppercase() == word
+    }
+
+    /// Check if all the words in the text, split by the tokenizer, are common words.
+    pub fn contains_all(&self, text: &str, tokenizer: &Tokenizer) -> bool {
+        tokenizer.words(text).all(|word| self.contains(word.text))
+    }
+
+    /// Replace the common words with spaces, keeping the UTF-16 length, so the native spell
+    /// checker only checks the other words. `None` when no word is masked.
+    ///
+    /// Only the words between spaces or sentence punctuation are masked, the native spell checker
+    /// may split the text differently, e.g. `e-mail` or the URLs.
+    pub fn mask(&self, text: &str, tokenizer: &Tokenizer) -> Option<String> {
+        let is_boundary =
+            |c: char| c.is_whitespace() || matches!(c, ',' | ';' | '!' | '?' | '(' | ')' | '"');
+
+        let mut masked = String::with_capacity(text.len());
+        let mut kept = 0;
+        for word in tokenizer.words(text) {
+            // The words are slices of the text
+            let start = word.text.as_ptr() as usize - text.as_ptr() as usize;
+            let end = start + word.text.len();
+            let bounded = text[..start].chars().next_back().is_none_or(is_boundary)
+                && text[end..].chars().next().is_none_or(is_boundary);
+            if !bounded || !self.contains(word.text) {
+                continue;
+            }
+
+            masked.push_str(&text[kept..start]);
+            masked.extend(std::iter::repeat_n(' ', word.end - word.start));
+            kept = end;
+        }
+        if masked.is_empty() {
+            return None;
+        }
+        masked.push_str(&text[kept..]);
+        Some(masked)
+    }
+}
+
+/// A spell checker skipping the native spell checker for common words.
+pub struct CommonWordsSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    /// The common words of the current language, if bundled.
+    common: Option<&'static CommonWords>,
+    /// The tokenizer used to split the text, matching the rest of the checks.
+    tokenizer: Arc<Tokenizer>,
+}
+
+impl CommonWordsSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>, tokenizer: Arc<Tokenizer>) -> Self {
+        let common = Self::common_words(inner.as_ref());
+        CommonWordsSpellChecker {
+            inner,
+            common,
+            tokenizer,
+        }
+    }
+
+    /// Check the sentence without its common words, skipping the native spell checker
+    /// when every word is common.
+    fn check_uncommon(
+        &self,
+        sentence: &str,
+        check: impl FnOnce(&str) -> EjaanError<Vec<TokenWithSuggestions>>,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let Some(common) = self.common else {
+            return check(sentence);
+        };
+        if common.contains_all(sentence, &self.tokenizer) {
+            return Ok(Vec::new());
+        }
+        match common.mask(sentence, &self.tokenizer) {
+            Some(masked) => check(&masked),
+            None => check(sentence),
+        }
+    }
+
+    fn common_words(inner: &dyn SpellCheckerImpl) -> Option<&'static CommonWords> {
+        inner
+            .get_language()
+            .ok()
+            .flatten()
+            .and_then(|language| CommonWords::for_language(&language))
+    }
+}
+
+impl SpellCheckerImpl for CommonWordsSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        if self.common.is_some_and(|common| common.contains(word)) {
+            return Ok(true);
+        }
+        self.inner.check_word(word)
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_uncommon(sentence, |sentence| self.inner.check_sentences(sentence))
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_uncommon(sentence, |sentence| {
+            self.inner.check_sentences_with(sentence, options)
+        })
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // Unpaired surrogates cannot be split into words, always ask the native spell checker
+        let (Some(common), Ok(text)) = (self.common, String::from_utf16(sentence)) else {
+            return self.inner.check_sentences_utf16(sentence);
+        };
+        if common.contains_all(&text, &self.tokenizer) {
+            return Ok(Vec::new());
+        }
+        match common.mask(&text, &self.tokenizer) {
+            Some(masked) => self
+                .inner
+                .check_sentences_utf16(&masked.encode_utf16().collect::<Vec<_>>()),
+            None => self.inner.check_sentences_utf16(sentence),
+        }
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.add_words(words)
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.add_words_chunked(words, on_chunk)
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.remove_words_chunked(words, on_chunk)
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        Ok(Box::new(Self::new(
+            self.inner.fork()?,
+            self.tokenizer.clone(),
+        )))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.inner.reset_session()
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.inner.custom_dictionaries()
+    }
+
+    fn fingerprint(&self, mut state: &mut dyn Hasher) {
+        self.inner.fingerprint(state);
+        "common-words".hash(&mut state);
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        self.inner.set_dictionary_enabled(id, enabled)
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        let changed = self.inner.set_language(language)?;
+        if changed {
+            self.common = Self::common_words(self.inner.as_ref());
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_language() {
+        assert!(CommonWords::for_language("en-US").is_some());
+        assert!(CommonWords::for_language("id_ID").is_some());
+        assert!(CommonWords::for_language("EN").is_some());
+        assert!(CommonWords::for_language("xx").is_none());
+    }
+
+    #[test]
+    fn test_contains_all() {
+        let tokenizer = Tokenizer::new();
+        let english = CommonWords::for_language("en").unwrap();
+        assert!(english.contains("the"));
+        assert!(english.contains("The"));
+        assert!(english.contains("THE"));
+        assert!(english.contains("I"));
+        // The mixed casings are typos, as is a lowercase `i`
+        assert!(!english.contains("THe"));
+        assert!(!english.contains("tHE"));
+        assert!(!english.contains("i"));
+        assert!(!english.contains_all("THe way", &tokenizer));
+        assert!(english.contains_all("This is the way, and it was good.", &tokenizer));
+        assert!(!english.contains_all("This is the wya.", &tokenizer));
+
+        let indonesian = CommonWords::for_language("id").unwrap();
+        assert!(indonesian.contains_all("Saya dan kamu.", &tokenizer));
+        assert!(!indonesian.contains_all("Saya dan kmau.", &tokenizer));
+
+        // Identifiers are a single word which is not common
+        let tokenizer = Tokenizer::new().with_join_chars("_");
+        assert!(!english.contains_all("the_way", &tokenizer));
+    }
+
+    #[test]
+    fn test_mask() {
+        let tokenizer = Tokenizer::new();
+        let english = CommonWords::for_language("en").unwrap();
+        assert_eq!(
+            english
+                .mask("The wya is (not) here, café", &tokenizer)
+                .unwrap(),
+            "    wya    (   )     , café"
+        );
+        // The words inside of a URL or joined with a hyphen are kept
+        assert!(english.mask("e-mail www.the.com", &tokenizer).is_none());
+        assert!(english.mask("wya", &tokenizer).is_none());
+    }
+}
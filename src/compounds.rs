@@ -0,0 +1,300 @@
+//! Compound words of the compounding languages, e.g. German `Haustür` or Dutch `fietsenwinkel`.
+//!
+//! The native spell checkers only know some of the compounds, a flagged word is accepted
+//! when it splits into valid words, with the linking elements of the language between them.
+
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    SpellCheckerImpl,
+    cache::CacheStats,
+    dictionaries::CustomDictionary,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, TokenWithSuggestions},
+};
+
+/// The shortest part of a compound, shorter parts would accept too many typos.
+const MIN_PART: usize = 3;
+/// The most parts in a compound, to bound the number of calls to the native spell checker.
+const MAX_PARTS: usize = 4;
+
+/// Get the linking elements between the parts of a compound, for the compounding languages.
+fn linking_elements(language: &str) -> Option<&'static [&'static str]> {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match primary.as_str() {
+        // e.g. Arbeit-s-zimmer, Hund-e-hütte, Sonne-n-schein
+        "de" => Some(&["s", "es", "n", "en", "e", "er", "ens"]),
+        // e.g. schaap-s-kooi, boek-en-kast
+        "nl" => Some(&["s", "en", "e"]),
+        "da" | "nb" | "nn" | "no" | "sv" => Some(&["s", "e"]),
+        _ => None,
+    }
+}
+
+/// Split a word into valid parts, the linking elements are kept with the part before them.
+///
+/// `valid` is only called once for each candidate part.
+fn split(word: &str, links: &[&str], valid: &mut dyn FnMut(&str) -> bool) -> Option<Vec<String>> {
+    let mut known: HashMap<String, bool> = HashMap::new();
+    let mut valid = |part: &str| {
+        if let Some(valid) = known.get(part) {
+            return *valid;
+        }
+        // The nouns inside a German compound are lowercase, e.g. `tür` in `Haustür`
+        let mut chars = part.chars();
+        let capitalized = chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect::<String>());
+        let result = valid(part) || capitalized.is_some_and(|word| word != part && valid(&word));
+        known.insert(part.to_string(), result);
+        result
+    };
+    let chars: Vec<char> = word.chars().collect();
+    split_parts(&chars, links, &mut valid, 1)
+}
+
+fn split_parts(
+    chars: &[char],
+    links: &[&str],
+    valid: &mut dyn FnMut(&str) -> bool,
+    parts: usize,
+) -> Option<Vec<String>> {
+    if parts > MAX_PARTS || chars.len() < MIN_PART * 2 {
+        return None;
+    }
+
+    for index in MIN_PART..=chars.len() - MIN_PART {
+        let head: String = chars[..index].iter().collect();
+        let head_valid = valid(&head)
+            || links.iter().any(|link| match head.strip_suffix(link) {
+                Some(stem) if stem.chars().count() >= MIN_PART => valid(stem),
+                _ => false,
+            });
+        if !head_valid {
+            continue;
+        }
+
+        let rest = &chars[index..];
+        let tail: String = rest.iter().collect();
+        if valid(&tail) {
+            return Some(vec![head, tail]);
+        }
+        if let Some(mut tail) = split_parts(rest, links, valid, parts + 1) {
+            tail.insert(0, head);
+            return Some(tail);
+        }
+    }
+    None
+}
+
+/// A spell checker accepting the compounds of valid words.
+pub struct CompoundsSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    /// The linking elements of the current language, `None` for the other languages.
+    links: Option<&'static [&'static str]>,
+}
+
+impl CompoundsSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>) -> Self {
+        let links = inner
+            .get_language()
+            .ok()
+            .flatten()
+            .and_then(|language| linking_elements(&language));
+        CompoundsSpellChecker { inner, links }
+    }
+
+    fn is_compound(&self, word: &str) -> bool {
+        let Some(links) = self.links else {
+            return false;
+        };
+        let mut valid = |part: &str| self.inner.check_word(part).unwrap_or(false);
+        split(word, links, &mut valid).is_some()
+    }
+
+    fn filter(
+        &self,
+        tokens: EjaanError<Vec<TokenWithSuggestions>>,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let mut tokens = tokens?;
+        if self.links.is_some() {
+            tokens.retain(|token| !self.is_compound(token.word()));
+        }
+        Ok(tokens)
+    }
+}
+
+impl SpellCheckerImpl for CompoundsSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        Ok(self.inner.check_word(word)? || self.is_compound(word))
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.filter(self.inner.check_sentences(sentence))
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.filter(self.inner.check_sentences_with(sentence, options))
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.filter(self.inner.check_sentences_utf16(sentence))
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.add_words(words)
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.add_words_chunked(words, on_chunk)
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.remove_words_chunked(words, on_chunk)
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        Ok(Box::new(Self::new(self.inner.fork()?)))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.inner.reset_session()
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.inner.custom_dictionaries()
+    }
+
+    fn fingerprint(&self, mut state: &mut dyn Hasher) {
+        self.inner.fingerprint(state);
+        "compounds".hash(&mut state);
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        self.inner.set_dictionary_enabled(id, enabled)
+    }
+
+    fn clear_cache(&self) {
+        self.inner.clear_cache()
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        self.inner.cache_stats()
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        let changed = self.inner.set_language(language)?;
+        if changed {
+            self.links = linking_elements(language);
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::dictionaries::{DictionariesSpellChecker, WordList};
+
+    const WORDS: &[&str] = &["Haus", "Tür", "Arbeit", "Zimmer", "fiets", "winkel"];
+
+    fn split_words(word: &str, language: &str) -> Option<Vec<String>> {
+        let mut valid = |part: &str| WORDS.contains(&part);
+        split(word, linking_elements(language)?, &mut valid)
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split_words("Haustür", "de-DE").unwrap(), ["Haus", "tür"]);
+        assert_eq!(
+            split_words("Arbeitszimmertür", "de").unwrap(),
+            ["Arbeits", "zimmer", "tür"]
+        );
+        assert_eq!(
+            split_words("fietsenwinkel", "nl-NL").unwrap(),
+            ["fietsen", "winkel"]
+        );
+        assert_eq!(split_words("Haustüx", "de-DE"), None);
+        assert_eq!(split_words("Haustür", "en-US"), None);
+    }
+
+    #[test]
+    fn test_compounds() {
+        let lists = Arc::new(RwLock::new(vec![
+            WordList::parse("Haus\nTür\n").with_id("de.dic"),
+        ]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        let inner = Box::new(DictionariesSpellChecker::new(inner, lists));
+        let mut checker = CompoundsSpellChecker::new(inner);
+        // The stub backend has no language, the compounds are not split
+        assert!(!checker.is_compound("Haustür"));
+
+        checker.links = linking_elements("de-DE");
+        assert!(checker.is_compound("Haustür"));
+        assert!(!checker.is_compound("Haustüx"));
+    }
+}
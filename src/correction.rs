@@ -0,0 +1,139 @@
+//! Step through the misspelled words of a text one at a time, like a "Spelling…" dialog.
+//!
+//! The text is corrected in place, the offsets of the remaining words are moved
+//! after each replacement so they always point into the corrected text.
+
+use std::collections::VecDeque;
+
+use crate::utils::TokenWithSuggestions;
+
+/// The state of a correction session over a text.
+#[derive(Debug, Clone)]
+pub struct Correction {
+    /// The corrected text, in UTF-16 code units like the offsets.
+    text: Vec<u16>,
+    /// The remaining misspelled words, sorted by their start.
+    remaining: VecDeque<TokenWithSuggestions>,
+}
+
+impl Correction {
+    /// Start correcting the text with the misspelled words found in it.
+    pub fn new(text: &str, mut misspelled: Vec<TokenWithSuggestions>) -> Self {
+        misspelled.sort_by_key(|token| token.start());
+        Correction {
+            text: text.encode_utf16().collect(),
+            remaining: misspelled.into(),
+        }
+    }
+
+    /// Get the current misspelled word, or `None` once everything is corrected.
+    pub fn current(&self) -> Option<&TokenWithSuggestions> {
+        self.remaining.front()
+    }
+
+    /// Get the number of the remaining misspelled words, including the current one.
+    pub fn remaining(&self) -> usize {
+        self.remaining.len()
+    }
+
+    /// Get the corrected text.
+    pub fn text(&self) -> String {
+        String::from_utf16_lossy(&self.text)
+    }
+
+    /// Replace the current word and move to the next one.
+    ///
+    /// Returns `false` if there is no current word.
+    pub fn replace(&mut self, replacement: &str) -> bool {
+        let Some(current) = self.remaining.pop_front() else {
+            return false;
+        };
+
+        let start = current.start().min(self.text.len());
+        let end = (current.end() + 1).clamp(start, self.text.len());
+        let replacement: Vec<u16> = replacement.encode_utf16().collect();
+        let (removed, added) = (end - start, replacement.len());
+        self.text.splice(start..end, replacement);
+
+        // The remaining words are all after the replaced one
+        self.remaining = std::mem::take(&mut self.remaining)
+            .into_iter()
+            .map(|token| token.map_offsets(|offset| offset - removed + added))
+            .collect();
+        true
+    }
+
+    /// Skip the current word and move to the next one.
+    ///
+    /// Returns `false` if there is no current word.
+    pub fn skip(&mut self) -> bool {
+        self.remaining.pop_front().is_some()
+    }
+
+    /// Skip the current word and all its remaining occurrences.
+    ///
+    /// Returns the skipped word, or `None` if there is no current word.
+    pub fn skip_all(&mut self) -> Option<String> {
+        let word = self.remaining.pop_front()?.word().to_string();
+        self.remaining.retain(|token| token.word() != word);
+        Some(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::Token;
+
+    fn misspelled(text: &str, words: &[&str]) -> Vec<TokenWithSuggestions> {
+        words
+            .iter()
+            .flat_map(|word| {
+                text.match_indices(word).map(|(index, word)| {
+                    let start = text[..index].encode_utf16().count();
+                    let end = start + word.encode_utf16().count() - 1;
+                    TokenWithSuggestions::new(Token::new(start, end, word.to_string()), Vec::new())
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replace() {
+        let text = "A tset, then 😀 snetences and tset.";
+        let mut correction = Correction::new(text, misspelled(text, &["snetences", "tset"]));
+        assert_eq!(correction.remaining(), 3);
+        assert_eq!(correction.current().unwrap().word(), "tset");
+
+        assert!(correction.replace("test"));
+        assert!(correction.replace("sentences"));
+        assert_eq!(correction.text(), "A test, then 😀 sentences and tset.");
+
+        // The offsets of the last word are moved by the longer replacement
+        let current = correction.current().unwrap();
+        let corrected: Vec<u16> = correction.text().encode_utf16().collect();
+        assert_eq!(
+            String::from_utf16_lossy(&corrected[current.start()..=current.end()]),
+            "tset"
+        );
+        assert!(correction.replace("t"));
+        assert_eq!(correction.text(), "A test, then 😀 sentences and t.");
+        assert!(!correction.replace("unused"));
+    }
+
+    #[test]
+    fn test_skip() {
+        let text = "tset and tset, then snetences";
+        let mut correction = Correction::new(text, misspelled(text, &["tset", "snetences"]));
+        assert!(correction.skip());
+        assert_eq!(correction.remaining(), 2);
+
+        let mut correction = Correction::new(text, misspelled(text, &["tset", "snetences"]));
+        assert_eq!(correction.skip_all().as_deref(), Some("tset"));
+        assert_eq!(correction.current().unwrap().word(), "snetences");
+        assert!(correction.skip());
+        assert!(correction.current().is_none());
+        assert!(correction.skip_all().is_none());
+        assert_eq!(correction.text(), text);
+    }
+}
@@ -0,0 +1,73 @@
+//! Map the offsets of the results into the positions of a rich-text editor document.
+//!
+//! Editors like ProseMirror count the node boundaries as positions too, so the text of
+//! a block is split into segments, each starting at a known document position.
+
+/// Map the offsets in the text of a block into the document positions.
+#[derive(Debug, Clone, Default)]
+pub struct PositionMap {
+    /// The base position of the block, for the offsets before the first segment.
+    base: usize,
+    /// The offset in the text and the document position where each segment starts, sorted.
+    segments: Vec<(usize, usize)>,
+}
+
+impl PositionMap {
+    /// Create the map for a block of text starting at the document position.
+    pub fn new(base: usize) -> Self {
+        PositionMap {
+            base,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Add a segment starting at the offset in the text, and at the position in the document.
+    pub fn with_segment(mut self, offset: usize, position: usize) -> Self {
+        let index = self.segments.partition_point(|(start, _)| *start <= offset);
+        self.segments.insert(index, (offset, position));
+        self
+    }
+
+    /// Get the document position of the offset in the text.
+    pub fn position(&self, offset: usize) -> usize {
+        let index = self.segments.partition_point(|(start, _)| *start <= offset);
+        match index.checked_sub(1) {
+            Some(index) => {
+                let (start, position) = self.segments[index];
+                position + (offset - start)
+            }
+            None => self.base + offset,
+        }
+    }
+
+    /// Get the document range of the text between the inclusive start and end offsets.
+    ///
+    /// The range is exclusive at the end, like the editor decorations.
+    pub fn range(&self, start: usize, end: usize) -> (usize, usize) {
+        // Map the last character, since the end can be on the next segment
+        (self.position(start), self.position(end) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_position() {
+        let map = PositionMap::new(10);
+        assert_eq!(map.position(0), 10);
+        assert_eq!(map.range(2, 5), (12, 16));
+    }
+
+    #[test]
+    fn test_segments() {
+        // "Hello ", an inline image, "wrold", another image, and "again" in a paragraph at 0
+        let map = PositionMap::new(1).with_segment(11, 14).with_segment(6, 8);
+        assert_eq!(map.position(0), 1);
+        assert_eq!(map.position(5), 6);
+        assert_eq!(map.position(6), 8);
+        assert_eq!(map.range(6, 10), (8, 13));
+        assert_eq!(map.position(11), 14);
+    }
+}
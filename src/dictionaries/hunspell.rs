@@ -0,0 +1,375 @@
+//! Hunspell dictionaries (`.aff`/`.dic`), expanded into the full list of word forms.
+//!
+//! Only the prefix and suffix rules are applied, which covers the inflections of most
+//! dictionaries. Compounding, continuation classes, and the morphology are ignored.
+//! The stems of the expanded forms are kept for `stem()`.
+
+use std::collections::{HashMap, HashSet};
+
+use encoding_rs::Encoding;
+
+use crate::utils::{EjaanError, Error};
+
+/// How the flags are written in the dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum FlagMode {
+    /// A single character per flag, the default.
+    #[default]
+    Char,
+    /// Two characters per flag, `FLAG long`.
+    Long,
+    /// Comma-separated numbers, `FLAG num`.
+    Numeric,
+}
+
+impl FlagMode {
+    fn split(&self, flags: &str) -> Vec<String> {
+        match self {
+            FlagMode::Char => flags.chars().map(String::from).collect(),
+            FlagMode::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagMode::Numeric => flags
+                .split(',')
+                .map(|flag| flag.trim().to_string())
+                .filter(|flag| !flag.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// A single element of the condition of an affix rule.
+#[derive(Debug, Clone)]
+enum Condition {
+    Any,
+    Char(char),
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl Condition {
+    fn parse(pattern: &str) -> Vec<Condition> {
+        if pattern == "." {
+            return Vec::new();
+        }
+
+        let mut conditions = Vec::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => conditions.push(Condition::Any),
+                '[' => {
+                    let class: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                    match class.strip_prefix('^') {
+                        Some(class) => conditions.push(Condition::NoneOf(class.chars().collect())),
+                        None => conditions.push(Condition::OneOf(class.chars().collect())),
+                    }
+                }
+                c => conditions.push(Condition::Char(c)),
+            }
+        }
+        conditions
+    }
+
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Condition::Any => true,
+            Condition::Char(expected) => c == *expected,
+            Condition::OneOf(class) => class.contains(&c),
+            Condition::NoneOf(class) => !class.contains(&c),
+        }
+    }
+}
+
+/// A prefix or suffix rule.
+#[derive(Debug, Clone)]
+struct Affix {
+    strip: String,
+    add: String,
+    conditions: Vec<Condition>,
+    cross_product: bool,
+}
+
+impl Affix {
+    /// The conditions are matched against the end of the stem.
+    fn apply_suffix(&self, stem: &str) -> Option<String> {
+        let mut chars = stem.chars().rev();
+        let matches = self
+            .conditions
+            .iter()
+            .rev()
+            .all(|condition| chars.next().is_some_and(|c| condition.matches(c)));
+        let base = stem.strip_suffix(self.strip.as_str()).filter(|_| matches)?;
+        Some(format!("{}{}", base, self.add))
+    }
+
+    /// The conditions are matched against the start of the stem.
+    fn apply_prefix(&self, stem: &str) -> Option<String> {
+        let mut chars = stem.chars();
+        let matches = self
+            .conditions
+            .iter()
+            .all(|condition| chars.next().is_some_and(|c| condition.matches(c)));
+        let base = stem.strip_prefix(self.strip.as_str()).filter(|_| matches)?;
+        Some(format!("{}{}", self.add, base))
+    }
+}
+
+/// The word forms of a dictionary.
+#[derive(Debug, Default)]
+pub struct Expansion {
+    pub words: HashSet<String>,
+    /// The stems of the prefixed and suffixed forms, from the entries of the dictionary.
+    pub stems: HashMap<String, Vec<String>>,
+}
+
+impl Expansion {
+    fn insert(&mut self, word: String, stem: &str) {
+        if word != stem {
+            let stems = self.stems.entry(word.clone()).or_default();
+            if !stems.iter().any(|existing| existing == stem) {
+                stems.push(stem.to_string());
+            }
+        }
+        self.words.insert(word);
+    }
+}
+
+/// The rules of an affix file.
+#[derive(Debug, Default)]
+pub struct Affixes {
+    mode: FlagMode,
+    /// The flag aliases, `AF`, referenced by their 1-based index in the dictionary.
+    aliases: Vec<String>,
+    prefixes: HashMap<String, Vec<Affix>>,
+    suffixes: HashMap<String, Vec<Affix>>,
+    /// The flags of the stems which are not words by themselves.
+    hidden: HashSet<String>,
+}
+
+impl Affixes {
+    /// Get the encoding of the dictionary from the `SET` line, `UTF-8` by default.
+    pub fn encoding(content: &[u8]) -> String {
+        String::from_utf8_lossy(content)
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("SET "))
+            .map(|encoding| encoding.trim().to_ascii_uppercase())
+            .unwrap_or_else(|| "UTF-8".to_string())
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut affixes = Affixes::default();
+        // The cross product is declared once in the header of each rule group
+        let mut cross_products: HashMap<(String, String), bool> = HashMap::new();
+        let mut aliases_header = false;
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", mode, ..] => {
+                    affixes.mode = match *mode {
+                        "long" => FlagMode::Long,
+                        "num" => FlagMode::Numeric,
+                        _ => FlagMode::Char,
+                    }
+                }
+                // The first line is the number of aliases
+                ["AF", flags, ..] => match aliases_header {
+                    true => affixes.aliases.push(flags.to_string()),
+                    false => aliases_header = true,
+                },
+                ["FORBIDDENWORD" | "NEEDAFFIX" | "ONLYINCOMPOUND", flag, ..] => {
+                    affixes.hidden.insert(flag.to_string());
+                }
+                // The header, e.g. `SFX A Y 1`
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count]
+                    if count.parse::<usize>().is_ok() =>
+                {
+                    let key = (kind.to_string(), flag.to_string());
+                    cross_products.insert(key, *cross_product == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let key = (kind.to_string(), flag.to_string());
+                    let cross_product = cross_products.get(&key).copied().unwrap_or(false);
+                    let add = add.split('/').next().unwrap_or_default();
+                    let affix = Affix {
+                        strip: if *strip == "0" { "" } else { strip }.to_string(),
+                        add: if add == "0" { "" } else { add }.to_string(),
+                        conditions: Condition::parse(rest.first().copied().unwrap_or(".")),
+                        cross_product,
+                    };
+                    let rules = match *kind {
+                        "PFX" => &mut affixes.prefixes,
+                        _ => &mut affixes.suffixes,
+                    };
+                    rules.entry(flag.to_string()).or_default().push(affix);
+                }
+                _ => {}
+            }
+        }
+        affixes
+    }
+
+    fn flags(&self, flags: &str) -> Vec<String> {
+        if self.aliases.is_empty() {
+            return self.mode.split(flags);
+        }
+        let alias = flags
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| self.aliases.get(index.checked_sub(1)?));
+        self.mode.split(alias.map_or(flags, String::as_str))
+    }
+
+    fn rules<'a>(
+        rules: &'a HashMap<String, Vec<Affix>>,
+        flags: &'a [String],
+    ) -> impl Iterator<Item = &'a Affix> {
+        flags.iter().filter_map(|flag| rules.get(flag)).flatten()
+    }
+
+    /// Expand the words of the dictionary with all their prefixed and suffixed forms.
+    pub fn expand(&self, dictionary: &str) -> Expansion {
+        let mut words = Expansion::default();
+        let mut lines = dictionary.lines();
+        // The first line is the approximate number of words
+        lines.next();
+
+        for line in lines {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (stem, flags) = match entry.split_once('/') {
+                Some((stem, flags)) => (stem, self.flags(flags)),
+                None => (entry, Vec::new()),
+            };
+            if stem.is_empty() {
+                continue;
+            }
+
+            if !flags.iter().any(|flag| self.hidden.contains(flag)) {
+                words.insert(stem.to_string(), stem);
+            }
+            for suffix in Self::rules(&self.suffixes, &flags) {
+                let Some(suffixed) = suffix.apply_suffix(stem) else {
+                    continue;
+                };
+                if suffix.cross_product {
+                    for prefix in Self::rules(&self.prefixes, &flags) {
+                        if let Some(word) = prefix
+                            .cross_product
+                            .then(|| prefix.apply_prefix(&suffixed))
+                            .flatten()
+                        {
+                            words.insert(word, stem);
+                        }
+                    }
+                }
+                words.insert(suffixed, stem);
+            }
+            for prefix in Self::rules(&self.prefixes, &flags) {
+                if let Some(word) = prefix.apply_prefix(stem) {
+                    words.insert(word, stem);
+                }
+            }
+        }
+        words
+    }
+}
+
+/// Decode the content of a Hunspell file in its `SET` encoding, e.g. `ISO8859-2` or `KOI8-R`.
+///
+/// Fails for the encodings without a decoder, e.g. `ISCII-DEVANAGARI`.
+pub fn decode(content: &[u8], encoding: &str) -> EjaanError<String> {
+    let label = match encoding {
+        // The `iso-8859-1` label of the Encoding Standard is windows-1252, keep it exactly Latin-1
+        "ISO8859-1" | "ISO-8859-1" => {
+            return Ok(content.iter().map(|byte| char::from(*byte)).collect());
+        }
+        // The names used by Hunspell which are not labels of the Encoding Standard
+        "MICROSOFT-CP1251" => "windows-1251".to_string(),
+        "TIS620-2533" => "tis-620".to_string(),
+        _ => encoding.replacen("ISO8859-", "ISO-8859-", 1),
+    };
+    let decoder = Encoding::for_label(label.as_bytes())
+        .ok_or_else(|| Error::new(format!("Unsupported dictionary encoding: {}", encoding)))?;
+    Ok(decoder.decode_without_bom_handling(content).0.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFFIXES: &str = "SET UTF-8
+# A comment
+NEEDAFFIX X
+
+PFX U Y 1
+PFX U 0 un .
+
+SFX S Y 2
+SFX S y ies [^aeiou]y
+SFX S 0 s [^y]
+
+SFX D N 1
+SFX D 0 ed [^e]
+";
+
+    #[test]
+    fn test_expand() {
+        let affixes = Affixes::parse(AFFIXES);
+        let expansion = affixes.expand("4\nwalk/SDU\nfly/S\nbake/D\npart/X\n");
+        let mut words: Vec<&str> = expansion.words.iter().map(String::as_str).collect();
+        words.sort();
+        assert_eq!(
+            words,
+            [
+                "bake", "flies", "fly", "unwalk", "unwalks", "walk", "walked", "walks"
+            ]
+        );
+        assert_eq!(expansion.stems["unwalks"], ["walk"]);
+        assert_eq!(expansion.stems["flies"], ["fly"]);
+        assert!(!expansion.stems.contains_key("walk"));
+    }
+
+    #[test]
+    fn test_flag_modes() {
+        let affixes = Affixes::parse("FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n");
+        assert_eq!(affixes.expand("1\ncat/AaBb\n").words.len(), 2);
+
+        let affixes = Affixes::parse("FLAG num\nAF 1\nAF 10,20\nSFX 10 Y 1\nSFX 10 0 s .\n");
+        let expansion = affixes.expand("1\ndog/1\n");
+        assert!(expansion.words.contains("dogs"));
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            Affixes::encoding(b"# A comment\nSET ISO8859-1\n"),
+            "ISO8859-1"
+        );
+        assert_eq!(Affixes::encoding(b"TRY abc\n"), "UTF-8");
+        assert_eq!(decode(b"caf\xe9", "ISO8859-1").unwrap(), "café");
+        assert_eq!(decode(b"\xa4", "ISO8859-15").unwrap(), "€");
+        assert_eq!(
+            decode(b"\xf0\xd2\xc9\xd7\xc5\xd4", "KOI8-R").unwrap(),
+            "Привет"
+        );
+        assert_eq!(
+            decode(b"abc", "ISCII-DEVANAGARI").unwrap_err().message(),
+            "Unsupported dictionary encoding: ISCII-DEVANAGARI"
+        );
+    }
+
+    #[test]
+    fn test_expand_legacy_encoding() {
+        // Polish in ISO-8859-2, `ą` is 0xb1 and `ę` is 0xea, both `±` and `ê` in Latin-1
+        let affixes = b"SET ISO8859-2\nSFX E Y 1\nSFX E 0 \xea .\n";
+        let encoding = Affixes::encoding(affixes);
+        let rules = Affixes::parse(&decode(affixes, &encoding).unwrap());
+        let expansion = rules.expand(&decode(b"1\nr\xb1k/E\n", &encoding).unwrap());
+        let mut words: Vec<&str> = expansion.words.iter().map(String::as_str).collect();
+        words.sort();
+        assert_eq!(words, ["rąk", "rąkę"]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Hyphenation patterns of LibreOffice and Hunspell (`hyph_*.dic`), the TeX patterns of Liang.
+//!
+//! The first line is the encoding, followed by the patterns where the digits between
+//! the letters are the hyphenation values, e.g. `.ab1c` or `2b1c`. The odd values allow
+//! a break, the highest value of all the matching patterns wins.
+
+use std::collections::HashMap;
+
+use super::hunspell;
+use crate::{casing::Casing, utils::EjaanError};
+
+/// A set of hyphenation patterns for some languages.
+#[derive(Debug, Clone)]
+pub struct Patterns {
+    /// The path of the file, followed by the patterns inside a `.oxt` extension.
+    pub id: String,
+    /// The languages of the patterns, empty for every language.
+    pub languages: Vec<String>,
+    /// The values between the letters of each pattern, one more than the letters.
+    patterns: HashMap<String, Vec<u8>>,
+    /// The longest pattern, in characters.
+    longest: usize,
+    /// The fewest characters before the first break.
+    left_min: usize,
+    /// The fewest characters after the last break.
+    right_min: usize,
+}
+
+impl Patterns {
+    /// Parse the patterns, failing if the encoding of the first line is not supported.
+    pub fn parse(content: &[u8]) -> EjaanError<Self> {
+        let encoding = String::from_utf8_lossy(content)
+            .lines()
+            .next()
+            .map(|line| line.trim().to_ascii_uppercase())
+            .unwrap_or_default();
+        let content = hunspell::decode(content, &encoding)?;
+
+        let mut patterns = Patterns {
+            id: String::new(),
+            languages: Vec::new(),
+            patterns: HashMap::new(),
+            longest: 0,
+            left_min: 2,
+            right_min: 2,
+        };
+        // Skip the encoding
+        for line in content.lines().skip(1) {
+            let line = line.trim();
+            let mut fields = line.split_whitespace();
+            match (
+                fields.next(),
+                fields.next().and_then(|value| value.parse().ok()),
+            ) {
+                (Some("LEFTHYPHENMIN"), Some(value)) => patterns.left_min = value,
+                (Some("RIGHTHYPHENMIN"), Some(value)) => patterns.right_min = value,
+                // The non-standard patterns with replacements are not supported
+                (Some(pattern), None) if !pattern.starts_with('%') && !pattern.contains('/') => {
+                    patterns.insert(pattern);
+                }
+                _ => {}
+            }
+        }
+        Ok(patterns)
+    }
+
+    fn insert(&mut self, pattern: &str) {
+        let mut letters = String::new();
+        let mut values = vec![0];
+        for c in pattern.chars() {
+            match (c.to_digit(10), values.last_mut()) {
+                (Some(value), Some(last)) => *last = value as u8,
+                _ => {
+                    letters.push(c);
+                    values.push(0);
+                }
+            }
+        }
+        if letters.is_empty() || letters.chars().all(|c| c.is_ascii_uppercase()) {
+            // The keywords, e.g. `NEXTLEVEL` or `COMPOUNDLEFTHYPHENMIN`
+            return;
+        }
+        self.longest = self.longest.max(letters.chars().count());
+        self.patterns.insert(letters, values);
+    }
+
+    pub fn with_id<T: Into<String>>(mut self, id: T) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Get the UTF-16 offsets in the word where a hyphen can be inserted.
+    pub fn hyphenate(&self, word: &str, casing: Casing) -> Vec<usize> {
+        // Lowercase each letter on its own, the offsets must match the word
+        let letters: Vec<char> = word
+            .chars()
+            .map(|c| {
+                let lowercase = casing.lowercase(&c.to_string());
+                let mut lowercase = lowercase.chars();
+                match (lowercase.next(), lowercase.next()) {
+                    (Some(lowercase), None) => lowercase,
+                    _ => c,
+                }
+            })
+            .collect();
+        let count = letters.len();
+        if count < self.left_min + self.right_min {
+            return Vec::new();
+        }
+
+        let text: Vec<char> = std::iter::once('.')
+            .chain(letters.iter().copied())
+            .chain(std::iter::once('.'))
+            .collect();
+        let mut values = vec![0u8; text.len() + 1];
+        for start in 0..text.len() {
+            let end = text.len().min(start + self.longest);
+            for stop in start + 1..=end {
+                let key: String = text[start..stop].iter().collect();
+                if let Some(pattern) = self.patterns.get(&key) {
+                    for (offset, value) in pattern.iter().enumerate() {
+                        values[start + offset] = values[start + offset].max(*value);
+                    }
+                }
+            }
+        }
+
+        // The value before the letter at `index` of the word is after the leading dot
+        let mut offsets = Vec::new();
+        let mut offset = 0;
+        for (index, c) in word.chars().enumerate() {
+            if index >= self.left_min
+                && count - index >= self.right_min
+                && values[index + 1] % 2 == 1
+            {
+                offsets.push(offset);
+            }
+            offset += c.len_utf16();
+        }
+        offsets
+    }
+}
+
+/// Get the languages from the name of a patterns file, e.g. `de-DE` for `hyph_de_DE.dic`.
+pub fn languages_from_name(name: &str) -> Vec<String> {
+    name.strip_prefix("hyph_")
+        .and_then(|name| name.split('.').next())
+        .filter(|language| !language.is_empty())
+        .map(|language| vec![language.replace('_', "-")])
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A few patterns of the English hyphenation, enough for the words below.
+    const PATTERNS: &str = "UTF-8
+LEFTHYPHENMIN 2
+RIGHTHYPHENMIN 3
+% A comment
+.hy3ph
+he2n
+hena4
+hen5at
+1na
+n2at
+1tio
+2io
+o2n
+";
+
+    fn offsets(word: &str) -> Vec<usize> {
+        Patterns::parse(PATTERNS.as_bytes())
+            .unwrap()
+            .hyphenate(word, Casing::Default)
+    }
+
+    #[test]
+    fn test_hyphenate() {
+        // hy-phen-ation
+        assert_eq!(offsets("hyphenation"), [2, 6]);
+        assert_eq!(offsets("Hyphenation"), [2, 6]);
+        assert!(offsets("hyp").is_empty());
+    }
+
+    #[test]
+    fn test_keywords() {
+        let patterns = Patterns::parse(b"ISO8859-1\nNEXTLEVEL\n1b\n").unwrap();
+        assert_eq!(patterns.patterns.len(), 1);
+        assert_eq!(patterns.right_min, 2);
+    }
+
+    #[test]
+    fn test_languages_from_name() {
+        assert_eq!(languages_from_name("hyph_de_DE.dic"), ["de-DE"]);
+        assert!(languages_from_name("patterns.dic").is_empty());
+    }
+}
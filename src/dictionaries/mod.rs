@@ -0,0 +1,863 @@
+//! Custom word lists loaded by this library, e.g. the personal dictionaries of other applications.
+//!
+//! Users often added words to Word or LibreOffice years ago, loading those dictionaries
+//! keeps their words accepted instead of flagging them again.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+mod hunspell;
+pub mod hyphenation;
+mod oxt;
+
+use crate::{
+    SpellCheckerImpl,
+    casing::Casing,
+    distance,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, Error, TokenWithSuggestions},
+};
+
+/// The first line of a LibreOffice user wordbook.
+const LIBREOFFICE_HEADER: &str = "OOoUserDict1";
+
+/// The word lists of a spell checker and its forks, the downloaded dictionaries are added later.
+pub type SharedWordLists = Arc<RwLock<Vec<WordList>>>;
+
+/// The format of a word list file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// One word per line, e.g. the Office and LibreOffice personal dictionaries.
+    #[default]
+    WordList,
+    /// A Hunspell dictionary expanded with its affix rules.
+    Hunspell,
+}
+
+impl Format {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::WordList => "wordlist",
+            Format::Hunspell => "hunspell",
+        }
+    }
+}
+
+/// A custom dictionary of a spell checker, with whether it is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomDictionary {
+    /// The path of the file, followed by the dictionary inside a `.oxt` extension.
+    pub id: String,
+    pub format: Format,
+    pub languages: Vec<String>,
+    /// The number of words, including the expanded forms of a Hunspell dictionary.
+    pub words: usize,
+    pub enabled: bool,
+}
+
+/// A list of words accepted on top of the native spell checker.
+#[derive(Debug, Clone, Default)]
+pub struct WordList {
+    /// The path of the file, see `CustomDictionary::id`.
+    id: String,
+    format: Format,
+    /// The languages of the words, empty for every language.
+    languages: Vec<String>,
+    words: HashSet<String>,
+    /// The stems of the expanded forms of a Hunspell dictionary.
+    stems: HashMap<String, Vec<String>>,
+}
+
+impl WordList {
+    /// Load a word list from a file, see `parse()` for the supported formats.
+    pub fn load(path: &Path) -> EjaanError<Self> {
+        let content = fs::read(path)
+            .map_err(|e| Error::new(format!("Failed to read {}: {}", path.display(), e)))?;
+        Ok(Self::parse(&decode(&content)).with_id(path.display().to_string()))
+    }
+
+    /// Expand a Hunspell dictionary with the rules of its affix file.
+    ///
+    /// Fails if the `SET` encoding of the affix file is not supported.
+    pub fn hunspell(affixes: &[u8], dictionary: &[u8], languages: Vec<String>) -> EjaanError<Self> {
+        let encoding = hunspell::Affixes::encoding(affixes);
+        let rules = hunspell::Affixes::parse(&hunspell::decode(affixes, &encoding)?);
+        let dictionary = hunspell::decode(dictionary, &encoding)?;
+        let expansion = rules.expand(dictionary.trim_start_matches('\u{feff}'));
+        Ok(WordList {
+            id: String::new(),
+            format: Format::Hunspell,
+            languages,
+            words: expansion.words,
+            stems: expansion.stems,
+        })
+    }
+
+    /// Parse a word list, one word per line.
+    ///
+    /// Supports the Office custom dictionaries (`CUSTOM.DIC`), the LibreOffice user wordbooks
+    /// (`OOoUserDict1`), and the Hunspell dictionaries, where the word count and the affix flags
+    /// are skipped. The negative LibreOffice wordbooks and the forbidden Hunspell words are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut lines = content.lines().map(str::trim).peekable();
+        let mut languages = Vec::new();
+
+        if lines.peek() == Some(&LIBREOFFICE_HEADER) {
+            for line in lines.by_ref() {
+                if line == "---" {
+                    break;
+                }
+                match line.split_once(':').map(|(key, value)| (key, value.trim())) {
+                    Some(("lang", value)) if value != "<none>" => languages.push(value.to_string()),
+                    Some(("type", "negative")) => return WordList::default(),
+                    _ => {}
+                }
+            }
+            let words = lines
+                .filter(|line| !line.is_empty())
+                // The `=` are the hyphenation points
+                .map(|line| line.replace('=', ""))
+                .collect();
+            return WordList {
+                languages,
+                words,
+                ..WordList::default()
+            };
+        }
+
+        if lines
+            .peek()
+            .is_some_and(|line| !line.is_empty() && line.bytes().all(|b| b.is_ascii_digit()))
+        {
+            lines.next();
+        }
+        let words = lines
+            .filter(|line| !line.is_empty() && !line.starts_with(['*', '#']))
+            .filter_map(|line| {
+                let word = line.split(['/', '\t']).next()?.trim();
+                (!word.is_empty()).then(|| word.to_string())
+            })
+            .collect();
+        WordList {
+            languages,
+            words,
+            ..WordList::default()
+        }
+    }
+
+    pub fn with_id<T: Into<String>>(mut self, id: T) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Use the words for these languages only, instead of the languages from the file.
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Check if the list has no words.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Check if the words are for the language, e.g. `en-US` for an `en` wordbook.
+    fn applies_to(&self, language: Option<&str>) -> bool {
+        applies_to(&self.languages, language)
+    }
+
+    /// Get the words within `max_distance` edits of the word, with their distance.
+    pub fn similar<'a>(
+        &'a self,
+        word: &'a str,
+        max_distance: usize,
+    ) -> impl Iterator<Item = (String, usize)> + 'a {
+        self.words.iter().filter_map(move |entry| {
+            distance::within(word, entry, max_distance).map(|distance| (entry.clone(), distance))
+        })
+    }
+
+    /// Get the stems of a word in the list, the word itself when it is not an expanded form.
+    pub fn stems(&self, word: &str, casing: Casing) -> Option<Vec<String>> {
+        casing
+            .lookup_forms(word)
+            .into_iter()
+            .find_map(|word| match self.stems.get(&word) {
+                Some(stems) => Some(stems.clone()),
+                None => self.words.contains(&word).then_some(vec![word]),
+            })
+    }
+
+    /// Check if the word is in the list, a lowercase entry also accepts the capitalized word.
+    pub fn contains(&self, word: &str) -> bool {
+        self.contains_with(word, Casing::Default)
+    }
+
+    /// Check if the word is in the list, lowercasing it with the case mapping of the language.
+    pub fn contains_with(&self, word: &str, casing: Casing) -> bool {
+        casing
+            .lookup_forms(word)
+            .iter()
+            .any(|form| self.words.contains(form))
+    }
+}
+
+/// Check if the languages of a dictionary cover the language, by its primary subtag.
+///
+/// The dictionaries without languages cover every language.
+fn applies_to(languages: &[String], language: Option<&str>) -> bool {
+    let Some(language) = language.filter(|_| !languages.is_empty()) else {
+        return true;
+    };
+    let primary = |language: &str| {
+        language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+    languages
+        .iter()
+        .any(|list| primary(list) == primary(language))
+}
+
+/// Load the hyphenation patterns of a file, a `hyph_*.dic` or a LibreOffice `.oxt` extension.
+///
+/// The languages of a `.dic` come from its name, e.g. `hyph_de_DE.dic` is for `de-DE`.
+pub fn load_hyphenation(path: &Path) -> EjaanError<Vec<hyphenation::Patterns>> {
+    let content = fs::read(path)
+        .map_err(|e| Error::new(format!("Failed to read {}: {}", path.display(), e)))?;
+    let is_oxt = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("oxt"));
+    if is_oxt {
+        let patterns = oxt::load_hyphenation(&content).map_err(|e| {
+            Error::new(format!(
+                "Failed to load {}: {}",
+                path.display(),
+                e.message()
+            ))
+        })?;
+        return Ok(patterns
+            .into_iter()
+            .map(|patterns| {
+                let id = format!("{}#{}", path.display(), patterns.id);
+                patterns.with_id(id)
+            })
+            .collect());
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let patterns = hyphenation::Patterns::parse(&content).map_err(|e| {
+        Error::new(format!(
+            "Failed to load {}: {}",
+            path.display(),
+            e.message()
+        ))
+    })?;
+    Ok(vec![
+        patterns
+            .with_id(path.display().to_string())
+            .with_languages(hyphenation::languages_from_name(&name)),
+    ])
+}
+
+/// Find the hyphenation patterns of the language, preferring the ones for its region.
+pub fn find_hyphenation<'a>(
+    patterns: &'a [hyphenation::Patterns],
+    language: &str,
+) -> Option<&'a hyphenation::Patterns> {
+    let normalized = language.replace('_', "-");
+    patterns
+        .iter()
+        .find(|patterns| {
+            patterns
+                .languages
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(&normalized))
+        })
+        .or_else(|| {
+            patterns
+                .iter()
+                .find(|patterns| applies_to(&patterns.languages, Some(language)))
+        })
+}
+
+/// Load the word lists of a dictionary file.
+///
+/// - `.oxt`, a LibreOffice extension with the Hunspell dictionaries of its languages.
+/// - `.dic` next to an `.aff` file, a Hunspell dictionary expanded with its affix rules.
+/// - Any other file, a word list, see `WordList::parse()`.
+pub fn load(path: &Path) -> EjaanError<Vec<WordList>> {
+    let read = |path: &Path| {
+        fs::read(path).map_err(|e| Error::new(format!("Failed to read {}: {}", path.display(), e)))
+    };
+    let has_extension = |path: &Path, expected: &str| {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(expected))
+    };
+
+    if has_extension(path, "oxt") {
+        let lists = oxt::load(&read(path)?).map_err(|e| {
+            Error::new(format!(
+                "Failed to load {}: {}",
+                path.display(),
+                e.message()
+            ))
+        })?;
+        return Ok(lists
+            .into_iter()
+            .map(|list| {
+                let id = format!("{}#{}", path.display(), list.id);
+                list.with_id(id)
+            })
+            .collect());
+    }
+    let affixes = path.with_extension("aff");
+    if has_extension(path, "dic") && affixes.is_file() {
+        let list = WordList::hunspell(&read(&affixes)?, &read(path)?, Vec::new()).map_err(|e| {
+            Error::new(format!(
+                "Failed to load {}: {}",
+                path.display(),
+                e.message()
+            ))
+        })?;
+        return Ok(vec![list.with_id(path.display().to_string())]);
+    }
+    Ok(vec![WordList::load(path)?])
+}
+
+/// Decode the content of a dictionary file, Office saves them as UTF-16 with a BOM.
+fn decode(content: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| from([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+
+    match content {
+        [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+        _ => String::from_utf8_lossy(content).into_owned(),
+    }
+}
+
+/// Find the personal dictionaries of Microsoft Office and LibreOffice in the roaming app data.
+///
+/// - `Microsoft\UProof\*.dic`, the custom dictionaries of Office.
+/// - `LibreOffice\4\user\wordbook\*.dic`, the user wordbooks of LibreOffice.
+pub fn discover(appdata: &Path) -> Vec<PathBuf> {
+    let directories = [
+        appdata.join("Microsoft").join("UProof"),
+        appdata
+            .join("LibreOffice")
+            .join("4")
+            .join("user")
+            .join("wordbook"),
+    ];
+
+    let mut paths = Vec::new();
+    for directory in directories {
+        let Ok(entries) = fs::read_dir(&directory) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("dic"))
+            })
+            .collect();
+        found.sort();
+        paths.extend(found);
+    }
+    paths
+}
+
+/// Load the personal dictionaries of Microsoft Office and LibreOffice on Windows.
+///
+/// The dictionaries which cannot be read are skipped, this is empty on the other platforms.
+pub fn user_dictionaries() -> Vec<WordList> {
+    let Some(appdata) = std::env::var_os("APPDATA") else {
+        return Vec::new();
+    };
+    discover(Path::new(&appdata))
+        .iter()
+        .filter_map(|path| WordList::load(path).ok())
+        .filter(|list| !list.is_empty())
+        .collect()
+}
+
+/// A spell checker accepting the words of the custom word lists.
+pub struct DictionariesSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    lists: SharedWordLists,
+    /// The current language, to skip the word lists of the other languages.
+    language: Option<String>,
+    /// The ids of the word lists disabled for this spell checker.
+    disabled: HashSet<String>,
+    /// The words added (`true`) or removed (`false`) in this session, see `fingerprint()`.
+    session: RefCell<BTreeMap<String, bool>>,
+}
+
+impl DictionariesSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>, lists: SharedWordLists) -> Self {
+        let language = inner.get_language().ok().flatten();
+        DictionariesSpellChecker {
+            inner,
+            lists,
+            language,
+            disabled: HashSet::new(),
+            session: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Remember the words added or removed in this session.
+    fn record(&self, words: &[impl AsRef<str>], added: bool) {
+        let mut session = self.session.borrow_mut();
+        for word in words {
+            session.insert(word.as_ref().to_string(), added);
+        }
+    }
+
+    fn accepts(&self, word: &str) -> bool {
+        let language = self.language.as_deref();
+        let casing = Casing::for_language(language);
+        self.lists
+            .read()
+            .map(|lists| {
+                lists
+                    .iter()
+                    .filter(|list| !self.disabled.contains(&list.id))
+                    .any(|list| list.applies_to(language) && list.contains_with(word, casing))
+            })
+            .unwrap_or(false)
+    }
+
+    fn filter(&self, mut tokens: Vec<TokenWithSuggestions>) -> Vec<TokenWithSuggestions> {
+        tokens.retain(|token| !self.accepts(token.word()));
+        tokens
+    }
+}
+
+impl SpellCheckerImpl for DictionariesSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        if self.accepts(word) {
+            return Ok(true);
+        }
+        self.inner.check_word(word)
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        Ok(self.filter(self.inner.check_sentences(sentence)?))
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        if self.lists.read().is_ok_and(|lists| lists.is_empty()) {
+            return self.inner.check_sentences_with(sentence, options);
+        }
+        // The word lists may accept some of the first results, only stop after filtering them
+        let uncapped = options.clone().with_max_results(None);
+        let mut tokens = self.filter(self.inner.check_sentences_with(sentence, &uncapped)?);
+        if let Some(max_results) = options.max_results() {
+            tokens.truncate(max_results);
+        }
+        Ok(tokens)
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        Ok(self.filter(self.inner.check_sentences_utf16(sentence)?))
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.record(&[word], true);
+        self.inner.add_word(word)
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.record(&[word], false);
+        self.inner.remove_word(word)
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.record(&words, true);
+        self.inner.add_words(words)
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.record(words, true);
+        self.inner.add_words_chunked(words, on_chunk)
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.record(&words, false);
+        self.inner.remove_words(words)
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.record(words, false);
+        self.inner.remove_words_chunked(words, on_chunk)
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        let mut fork = Self::new(self.inner.fork()?, self.lists.clone());
+        fork.disabled = self.disabled.clone();
+        fork.session = self.session.clone();
+        Ok(Box::new(fork))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        // The word lists are not part of the session
+        self.session.borrow_mut().clear();
+        self.inner.reset_session()
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        let Ok(lists) = self.lists.read() else {
+            return Vec::new();
+        };
+        lists
+            .iter()
+            .map(|list| CustomDictionary {
+                id: list.id.clone(),
+                format: list.format,
+                languages: list.languages.clone(),
+                words: list.words.len(),
+                enabled: !self.disabled.contains(&list.id),
+            })
+            .collect()
+    }
+
+    fn fingerprint(&self, mut state: &mut dyn Hasher) {
+        self.inner.fingerprint(state);
+        for dictionary in self.custom_dictionaries() {
+            dictionary.id.hash(&mut state);
+            dictionary.words.hash(&mut state);
+            dictionary.enabled.hash(&mut state);
+        }
+        self.session.borrow().hash(&mut state);
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        let known = self
+            .lists
+            .read()
+            .is_ok_and(|lists| lists.iter().any(|list| list.id == id));
+        if known {
+            match enabled {
+                true => self.disabled.remove(id),
+                false => self.disabled.insert(id.to_string()),
+            };
+        }
+        known
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        let mut stems = self.inner.dictionary_stems(word, language);
+        if let Ok(lists) = self.lists.read() {
+            let found = lists
+                .iter()
+                .filter(|list| !self.disabled.contains(&list.id) && list.applies_to(Some(language)))
+                .filter_map(|list| list.stems(word, Casing::for_language(Some(language))))
+                .flatten();
+            for stem in found {
+                if !stems.contains(&stem) {
+                    stems.push(stem);
+                }
+            }
+        }
+        stems
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        let mut similar = self.inner.similar_words(word, max_distance);
+        if let Ok(lists) = self.lists.read() {
+            let language = self.language.as_deref();
+            similar.extend(
+                lists
+                    .iter()
+                    .filter(|list| !self.disabled.contains(&list.id) && list.applies_to(language))
+                    .flat_map(|list| list.similar(word, max_distance)),
+            );
+        }
+        similar
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        let changed = self.inner.set_language(language)?;
+        if changed {
+            self.language = Some(language.to_string());
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16_le(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn test_parse_office() {
+        let list = WordList::parse(&decode(&utf16_le("napi\r\nEjaan\r\n\r\nnoaione\r\n")));
+        assert_eq!(list.words.len(), 3);
+        assert!(list.contains("napi"));
+        assert!(list.contains("Napi"));
+        assert!(list.contains("Ejaan"));
+        assert!(!list.contains("ejaan"));
+        assert!(list.applies_to(Some("id-ID")));
+    }
+
+    #[test]
+    fn test_parse_libreoffice() {
+        let content = "OOoUserDict1\nlang: en-US\ntype: positive\n---\nnapi\nhy=phen=ated\n";
+        let list = WordList::parse(content);
+        assert_eq!(list.words.len(), 2);
+        assert!(list.contains("hyphenated"));
+        assert!(list.applies_to(Some("en-GB")));
+        assert!(!list.applies_to(Some("id-ID")));
+
+        let negative = "OOoUserDict1\nlang: <none>\ntype: negative\n---\nteh//the\n";
+        assert_eq!(WordList::parse(negative).words.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_hunspell() {
+        let list = WordList::parse("3\nnapi/S\nejaan\tpo:noun\n*badword\n");
+        assert_eq!(list.words.len(), 2);
+        assert!(list.contains("napi"));
+        assert!(list.contains("ejaan"));
+        assert!(!list.contains("badword"));
+    }
+
+    #[test]
+    fn test_load() {
+        let directory = std::env::temp_dir().join(format!("ejaan-hunspell-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("custom.aff"), "SFX S Y 1\nSFX S 0 s .\n").unwrap();
+        fs::write(directory.join("custom.dic"), "1\nnapi/S\n").unwrap();
+        fs::write(directory.join("plain.dic"), "1\nejaan/S\n").unwrap();
+
+        let lists = load(&directory.join("custom.dic")).unwrap();
+        assert!(lists[0].contains("napis"));
+        assert_eq!(lists[0].format, Format::Hunspell);
+        assert_eq!(
+            lists[0].id,
+            directory.join("custom.dic").display().to_string()
+        );
+        let lists = load(&directory.join("plain.dic")).unwrap();
+        assert!(lists[0].contains("ejaan"));
+        assert!(!lists[0].contains("ejaans"));
+        assert!(load(&directory.join("missing.oxt")).is_err());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_set_enabled() {
+        let lists = Arc::new(RwLock::new(vec![
+            WordList::parse("napi\n").with_id("napi.dic"),
+            WordList::parse("ejaan\n").with_id("ejaan.dic"),
+        ]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        let mut checker = DictionariesSpellChecker::new(inner, lists);
+        assert!(checker.check_word("napi").unwrap());
+
+        assert!(checker.set_dictionary_enabled("napi.dic", false));
+        assert!(!checker.set_dictionary_enabled("missing.dic", false));
+        // The stub backend cannot check the words by itself
+        assert!(checker.check_word("napi").is_err());
+        assert!(checker.check_word("ejaan").unwrap());
+
+        let enabled: Vec<(String, bool)> = checker
+            .custom_dictionaries()
+            .into_iter()
+            .map(|dictionary| (dictionary.id, dictionary.enabled))
+            .collect();
+        assert_eq!(
+            enabled,
+            [
+                ("napi.dic".to_string(), false),
+                ("ejaan.dic".to_string(), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let fingerprint = |checker: &dyn SpellCheckerImpl| {
+            let mut hasher = std::hash::DefaultHasher::new();
+            checker.fingerprint(&mut hasher);
+            hasher.finish()
+        };
+        let lists = Arc::new(RwLock::new(vec![
+            WordList::parse("napi\n").with_id("napi.dic"),
+        ]));
+        let inner = Box::new(crate::stubs::WordListSpellChecker::new());
+        let mut checker = DictionariesSpellChecker::new(inner, lists.clone());
+        let initial = fingerprint(&checker);
+        assert_eq!(initial, fingerprint(&checker));
+
+        checker.add_word("ejaan").unwrap();
+        let added = fingerprint(&checker);
+        assert_ne!(added, initial);
+        assert_eq!(fingerprint(checker.fork().unwrap().as_ref()), added);
+        checker.remove_word("ejaan").unwrap();
+        assert_ne!(fingerprint(&checker), added);
+        checker.reset_session().unwrap();
+        assert_eq!(fingerprint(&checker), initial);
+
+        checker.set_dictionary_enabled("napi.dic", false);
+        assert_ne!(fingerprint(&checker), initial);
+        checker.set_dictionary_enabled("napi.dic", true);
+        lists
+            .write()
+            .unwrap()
+            .push(WordList::parse("ejaan\n").with_id("ejaan.dic"));
+        assert_ne!(fingerprint(&checker), initial);
+    }
+
+    #[test]
+    fn test_dictionary_stems() {
+        let affixes = b"SFX S Y 1\nSFX S 0 s .\n";
+        let lists = Arc::new(RwLock::new(vec![
+            WordList::hunspell(affixes, b"1\nwalk/S\n", vec!["en".to_string()])
+                .unwrap()
+                .with_id("en.dic"),
+            WordList::parse("walks\n")
+                .with_id("de.dic")
+                .with_languages(vec!["de".to_string()]),
+        ]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        let checker = DictionariesSpellChecker::new(inner, lists);
+
+        assert_eq!(checker.dictionary_stems("Walks", "en-US"), ["walk"]);
+        assert_eq!(checker.dictionary_stems("walk", "en-US"), ["walk"]);
+        assert_eq!(checker.dictionary_stems("walks", "de-DE"), ["walks"]);
+        assert!(checker.dictionary_stems("ran", "en-US").is_empty());
+    }
+
+    #[test]
+    fn test_language_casing() {
+        let lists = Arc::new(RwLock::new(vec![
+            WordList::parse("ısparta\nstraße\n").with_id("words.dic"),
+        ]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        let mut checker = DictionariesSpellChecker::new(inner, lists);
+        // The stub backend cannot check the words by itself
+        assert!(checker.check_word("Isparta").is_err());
+        assert!(checker.check_word("STRASSE").is_err());
+
+        checker.language = Some("tr-TR".to_string());
+        assert!(checker.check_word("Isparta").unwrap());
+        checker.language = Some("de-DE".to_string());
+        assert!(checker.check_word("STRASSE").unwrap());
+        assert_eq!(checker.dictionary_stems("STRASSE", "de-DE"), ["straße"]);
+    }
+
+    #[test]
+    fn test_similar_words() {
+        let lists = Arc::new(RwLock::new(vec![
+            WordList::parse("ejaan\nejekan\nnapi\n").with_id("id.dic"),
+            WordList::parse("ejaan\n")
+                .with_id("extra.dic")
+                .with_languages(vec!["ms".to_string()]),
+        ]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        let mut checker = DictionariesSpellChecker::new(inner, lists);
+
+        let mut similar = checker.similar_words("Ejana", 2);
+        similar.sort();
+        // The stub backend has no language, so every list applies
+        assert_eq!(
+            similar,
+            [("ejaan".to_string(), 1), ("ejaan".to_string(), 1)]
+        );
+        assert_eq!(checker.similar_words("ejaan", 2).len(), 3);
+
+        checker.set_dictionary_enabled("extra.dic", false);
+        assert_eq!(
+            checker.similar_words("ejaan", 0),
+            [("ejaan".to_string(), 0)]
+        );
+    }
+
+    #[test]
+    fn test_discover() {
+        let appdata = std::env::temp_dir().join(format!("ejaan-appdata-{}", std::process::id()));
+        let uproof = appdata.join("Microsoft").join("UProof");
+        let wordbook = appdata.join("LibreOffice/4/user/wordbook");
+        fs::create_dir_all(&uproof).unwrap();
+        fs::create_dir_all(&wordbook).unwrap();
+        fs::write(uproof.join("CUSTOM.DIC"), utf16_le("napi\r\n")).unwrap();
+        fs::write(uproof.join("notes.txt"), "ignored").unwrap();
+        fs::write(wordbook.join("standard.dic"), "OOoUserDict1\n---\nejaan\n").unwrap();
+
+        let paths = discover(&appdata);
+        assert_eq!(
+            paths,
+            vec![uproof.join("CUSTOM.DIC"), wordbook.join("standard.dic")]
+        );
+        let lists: Vec<WordList> = paths
+            .iter()
+            .map(|path| WordList::load(path).unwrap())
+            .collect();
+        assert!(lists[0].contains("napi"));
+        assert!(lists[1].contains("ejaan"));
+
+        fs::remove_dir_all(&appdata).unwrap();
+        assert!(discover(&appdata).is_empty());
+    }
+}
@@ -0,0 +1,379 @@
+//! LibreOffice dictionary extensions (`.oxt`), a zip archive with the Hunspell dictionaries.
+//!
+//! The dictionaries are declared in the `dictionaries.xcu` of the extension, with their
+//! locations inside the archive and the languages they cover.
+
+use super::{WordList, hyphenation::Patterns};
+use crate::utils::{EjaanError, Error};
+
+/// The signature of the end of the central directory record.
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+/// The signature of a central directory file header.
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4b50;
+/// The signature of a local file header.
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// A file of the archive, from the central directory.
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    method: u16,
+    compressed_size: usize,
+    offset: usize,
+}
+
+/// A minimal zip reader, for the stored and deflated files of the extensions.
+///
+/// ZIP64 and the encrypted archives are not supported, which the extensions never use.
+struct Archive<'a> {
+    data: &'a [u8],
+    entries: Vec<Entry>,
+}
+
+impl<'a> Archive<'a> {
+    fn parse(data: &'a [u8]) -> EjaanError<Self> {
+        let invalid = || Error::new("Not a valid .oxt extension, the zip archive is corrupted");
+
+        // The record is at the end, followed by a comment of up to 64 KiB
+        let last = data.len().checked_sub(22).ok_or_else(invalid)?;
+        let end = (last.saturating_sub(u16::MAX as usize)..=last)
+            .rev()
+            .find(|offset| u32_at(data, *offset) == Some(END_OF_CENTRAL_DIRECTORY))
+            .ok_or_else(invalid)?;
+        let count = u16_at(data, end + 10).ok_or_else(invalid)?;
+        let mut offset = u32_at(data, end + 16).ok_or_else(invalid)? as usize;
+
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            if u32_at(data, offset) != Some(CENTRAL_DIRECTORY_HEADER) {
+                return Err(invalid());
+            }
+            let field = |at: usize| u16_at(data, offset + at).ok_or_else(invalid);
+            let name_length = field(28)? as usize;
+            let extra_length = field(30)? as usize;
+            let comment_length = field(32)? as usize;
+            let name = data
+                .get(offset + 46..offset + 46 + name_length)
+                .ok_or_else(invalid)?;
+            entries.push(Entry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                method: field(10)?,
+                compressed_size: u32_at(data, offset + 20).ok_or_else(invalid)? as usize,
+                offset: u32_at(data, offset + 42).ok_or_else(invalid)? as usize,
+            });
+            offset += 46 + name_length + extra_length + comment_length;
+        }
+        Ok(Archive { data, entries })
+    }
+
+    /// Find a file by its path, ignoring the case like the extension manager of LibreOffice.
+    fn find(&self, name: &str) -> Option<&Entry> {
+        let name = name.trim_start_matches('/');
+        self.entries
+            .iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name))
+    }
+
+    fn read(&self, entry: &Entry) -> EjaanError<Vec<u8>> {
+        let invalid = || Error::new(format!("Failed to read {} in the extension", entry.name));
+
+        let header = entry.offset;
+        if u32_at(self.data, header) != Some(LOCAL_FILE_HEADER) {
+            return Err(invalid());
+        }
+        let name_length = u16_at(self.data, header + 26).ok_or_else(invalid)? as usize;
+        let extra_length = u16_at(self.data, header + 28).ok_or_else(invalid)? as usize;
+        let start = header + 30 + name_length + extra_length;
+        let content = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or_else(invalid)?;
+
+        match entry.method {
+            0 => Ok(content.to_vec()),
+            8 => miniz_oxide::inflate::decompress_to_vec(content).map_err(|_| invalid()),
+            method => Err(Error::new(format!(
+                "Unsupported compression method {} for {} in the extension",
+                method, entry.name
+            ))),
+        }
+    }
+}
+
+/// A spelling dictionary declared in the `dictionaries.xcu` of the extension.
+#[derive(Debug, PartialEq, Eq)]
+struct Declaration {
+    affixes: String,
+    dictionary: String,
+    languages: Vec<String>,
+}
+
+/// Get the value of a property of a configuration node.
+fn property<'a>(node: &'a str, name: &str) -> Option<&'a str> {
+    let start = node.find(&format!("oor:name=\"{}\"", name))?;
+    let prop = &node[start..];
+    let prop = &prop[..prop.find("</prop>").unwrap_or(prop.len())];
+    let value = &prop[prop.find("<value")?..];
+    let value = &value[value.find('>')? + 1..];
+    Some(value[..value.find("</value>")?].trim())
+}
+
+/// Get the locations and the languages of the dictionaries of a format in a `dictionaries.xcu`,
+/// e.g. `DICT_SPELL` or `DICT_HYPH`.
+fn nodes<'a>(
+    xcu: &'a str,
+    format: &'a str,
+) -> impl Iterator<Item = (Vec<&'a str>, Vec<String>)> + 'a {
+    xcu.split("<node ")
+        .filter(move |node| property(node, "Format") == Some(format))
+        .filter_map(|node| {
+            let locations = property(node, "Locations")?
+                .split_whitespace()
+                .map(|location| location.trim_start_matches("%origin%"))
+                .collect();
+            let languages = property(node, "Locales")
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(|language| language.replace('_', "-"))
+                .collect();
+            Some((locations, languages))
+        })
+}
+
+/// Find the location of a file by its extension.
+fn find(locations: &[&str], extension: &str) -> Option<String> {
+    locations
+        .iter()
+        .find(|location| location.to_ascii_lowercase().ends_with(extension))
+        .map(|location| location.to_string())
+}
+
+/// Parse the spelling dictionaries of a `dictionaries.xcu`, the hyphenation and thesaurus
+/// dictionaries are skipped.
+fn declarations(xcu: &str) -> Vec<Declaration> {
+    nodes(xcu, "DICT_SPELL")
+        .filter_map(|(locations, languages)| {
+            Some(Declaration {
+                affixes: find(&locations, ".aff")?,
+                dictionary: find(&locations, ".dic")?,
+                languages,
+            })
+        })
+        .collect()
+}
+
+impl Archive<'_> {
+    /// Read the `.xcu` configuration files of the extension.
+    fn configurations(&self) -> EjaanError<Vec<String>> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.name.to_ascii_lowercase().ends_with(".xcu"))
+            .map(|entry| Ok(String::from_utf8_lossy(&self.read(entry)?).into_owned()))
+            .collect()
+    }
+
+    /// Read a file by its path, failing if the extension does not have it.
+    fn read_path(&self, name: &str) -> EjaanError<Vec<u8>> {
+        let entry = self.find(name).ok_or_else(|| {
+            Error::new(format!(
+                "The extension is missing {}",
+                name.trim_start_matches('/')
+            ))
+        })?;
+        self.read(entry)
+    }
+}
+
+/// Load the spelling dictionaries of an extension.
+pub fn load(data: &[u8]) -> EjaanError<Vec<WordList>> {
+    let archive = Archive::parse(data)?;
+
+    let mut lists = Vec::new();
+    for xcu in archive.configurations()? {
+        for declaration in declarations(&xcu) {
+            let read = |name: &str| archive.read_path(name);
+            let list = WordList::hunspell(
+                &read(&declaration.affixes)?,
+                &read(&declaration.dictionary)?,
+                declaration.languages,
+            )?;
+            lists.push(list.with_id(declaration.dictionary.trim_start_matches('/')));
+        }
+    }
+
+    if lists.is_empty() {
+        return Err(Error::new("The extension has no spelling dictionary"));
+    }
+    Ok(lists)
+}
+
+/// Load the hyphenation patterns of an extension.
+pub fn load_hyphenation(data: &[u8]) -> EjaanError<Vec<Patterns>> {
+    let archive = Archive::parse(data)?;
+
+    let mut patterns = Vec::new();
+    for xcu in archive.configurations()? {
+        for (locations, languages) in nodes(&xcu, "DICT_HYPH") {
+            let Some(location) = find(&locations, ".dic") else {
+                continue;
+            };
+            let id = location.trim_start_matches('/').to_string();
+            patterns.push(
+                Patterns::parse(&archive.read_path(&location)?)?
+                    .with_id(id)
+                    .with_languages(languages),
+            );
+        }
+    }
+
+    if patterns.is_empty() {
+        return Err(Error::new("The extension has no hyphenation patterns"));
+    }
+    Ok(patterns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XCU: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<oor:component-data xmlns:oor="http://openoffice.org/2001/registry" oor:name="Linguistic" oor:package="org.openoffice.Office">
+  <node oor:name="ServiceManager">
+    <node oor:name="Dictionaries">
+      <node oor:name="HunSpellDic_id" oor:op="fuse">
+        <prop oor:name="Locations" oor:type="oor:string-list">
+          <value>%origin%/id_ID.aff %origin%/id_ID.dic</value>
+        </prop>
+        <prop oor:name="Format" oor:type="xs:string">
+          <value>DICT_SPELL</value>
+        </prop>
+        <prop oor:name="Locales" oor:type="oor:string-list">
+          <value>id-ID ms_MY</value>
+        </prop>
+      </node>
+      <node oor:name="HyphDic_id" oor:op="fuse">
+        <prop oor:name="Locations" oor:type="oor:string-list">
+          <value>%origin%/hyph_id_ID.dic</value>
+        </prop>
+        <prop oor:name="Format" oor:type="xs:string">
+          <value>DICT_HYPH</value>
+        </prop>
+      </node>
+    </node>
+  </node>
+</oor:component-data>
+"#;
+
+    /// Build a zip archive, deflating the files when `deflate` is set.
+    fn zip(files: &[(&str, &[u8])], deflate: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut directory = Vec::new();
+        for (name, content) in files {
+            let (method, content) = match deflate {
+                true => (8u16, miniz_oxide::deflate::compress_to_vec(content, 6)),
+                false => (0u16, content.to_vec()),
+            };
+            let offset = data.len() as u32;
+            data.extend(LOCAL_FILE_HEADER.to_le_bytes());
+            data.extend([20, 0, 0, 0]);
+            data.extend(method.to_le_bytes());
+            data.extend([0; 8]);
+            data.extend((content.len() as u32).to_le_bytes());
+            data.extend(0u32.to_le_bytes());
+            data.extend((name.len() as u16).to_le_bytes());
+            data.extend(0u16.to_le_bytes());
+            data.extend(name.as_bytes());
+            data.extend(&content);
+
+            directory.extend(CENTRAL_DIRECTORY_HEADER.to_le_bytes());
+            directory.extend([20, 0, 20, 0, 0, 0]);
+            directory.extend(method.to_le_bytes());
+            directory.extend([0; 8]);
+            directory.extend((content.len() as u32).to_le_bytes());
+            directory.extend(0u32.to_le_bytes());
+            directory.extend((name.len() as u16).to_le_bytes());
+            directory.extend([0; 12]);
+            directory.extend(offset.to_le_bytes());
+            directory.extend(name.as_bytes());
+        }
+
+        let directory_offset = data.len() as u32;
+        data.extend(&directory);
+        data.extend(END_OF_CENTRAL_DIRECTORY.to_le_bytes());
+        data.extend([0; 4]);
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((directory.len() as u32).to_le_bytes());
+        data.extend(directory_offset.to_le_bytes());
+        data.extend(0u16.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_declarations() {
+        assert_eq!(
+            declarations(XCU),
+            vec![Declaration {
+                affixes: "/id_ID.aff".to_string(),
+                dictionary: "/id_ID.dic".to_string(),
+                languages: vec!["id-ID".to_string(), "ms-MY".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let files: [(&str, &[u8]); 4] = [
+            ("META-INF/manifest.xml", b"<manifest:manifest/>"),
+            ("dictionaries.xcu", XCU.as_bytes()),
+            ("id_ID.aff", b"SET UTF-8\nPFX M Y 1\nPFX M 0 mem .\n"),
+            ("id_ID.dic", b"2\nbaca/M\nejaan\n"),
+        ];
+        for deflate in [false, true] {
+            let lists = load(&zip(&files, deflate)).unwrap();
+            assert_eq!(lists.len(), 1);
+            assert_eq!(lists[0].id, "id_ID.dic");
+            assert!(lists[0].contains("membaca"));
+            assert!(lists[0].contains("ejaan"));
+            assert!(lists[0].applies_to(Some("ms-MY")));
+            assert!(!lists[0].applies_to(Some("en-US")));
+        }
+    }
+
+    #[test]
+    fn test_load_hyphenation() {
+        let files: [(&str, &[u8]); 2] = [
+            ("dictionaries.xcu", XCU.as_bytes()),
+            ("hyph_id_ID.dic", b"UTF-8\n1ba\n"),
+        ];
+        let patterns = load_hyphenation(&zip(&files, true)).unwrap();
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].id, "hyph_id_ID.dic");
+        assert!(patterns[0].languages.is_empty());
+        assert_eq!(
+            patterns[0].hyphenate("membaca", crate::casing::Casing::Default),
+            [3]
+        );
+
+        let files: [(&str, &[u8]); 1] = [("dictionaries.xcu", XCU.as_bytes())];
+        assert!(load_hyphenation(&zip(&files, false)).is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(load(b"not a zip").is_err());
+        let files: [(&str, &[u8]); 1] = [("description.xml", b"<description/>")];
+        let error = load(&zip(&files, false)).unwrap_err();
+        assert_eq!(error.message(), "The extension has no spelling dictionary");
+    }
+}
@@ -0,0 +1,257 @@
+//! A persistent cache of the results of each file, like ESLint's `--cache`.
+//!
+//! Each file is keyed by a hash of its content, the language, the check options, and
+//! the configuration of the spell checker, so repeated runs over a large repository
+//! only check the modified files.
+//! The cache is a small line-based text file, written as a whole after each run.
+
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+};
+
+use crate::utils::{CheckOptions, EjaanError, Error, Token, TokenWithSuggestions};
+
+/// The first line of the cache file, the cache is discarded when it differs.
+const HEADER: &str = concat!("ejaan-cache 1 ", env!("CARGO_PKG_VERSION"));
+
+/// The cached results of the checked files.
+#[derive(Debug, Default)]
+pub struct DiskCache {
+    /// The key and the misspelled words of each file, by path.
+    entries: HashMap<String, (u64, Vec<TokenWithSuggestions>)>,
+    changed: bool,
+}
+
+impl DiskCache {
+    /// Load the cache from the file, starting empty if it is missing, outdated, or invalid.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Write the cache into the file, if anything changed since it was loaded.
+    pub fn save(&self, path: &Path) -> EjaanError<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)
+                .map_err(|e| Error::new(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        fs::write(path, self.serialize())
+            .map_err(|e| Error::new(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Get the key of a file from its content, the language, the check options, and
+    /// the fingerprint of the spell checker, see `SpellCheckerImpl::fingerprint()`.
+    pub fn key(content: &str, language: &str, options: &CheckOptions, fingerprint: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        language.hash(&mut hasher);
+        options.hash(&mut hasher);
+        fingerprint.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Get the cached results of the file, if its key is unchanged.
+    pub fn get(&self, path: &str, key: u64) -> Option<&[TokenWithSuggestions]> {
+        self.entries
+            .get(path)
+            .filter(|(cached, _)| *cached == key)
+            .map(|(_, tokens)| tokens.as_slice())
+    }
+
+    /// Count the misspelled words which were not reported for the file in the previous run.
+    ///
+    /// The words are compared instead of the offsets, which move when the file is edited.
+    /// Every word is new when the file was not cached before.
+    pub fn count_new(&self, path: &str, tokens: &[TokenWithSuggestions]) -> usize {
+        let Some((_, previous)) = self.entries.get(path) else {
+            return tokens.len();
+        };
+
+        let mut known: HashMap<&str, usize> = HashMap::new();
+        for token in previous {
+            *known.entry(token.word()).or_default() += 1;
+        }
+        tokens
+            .iter()
+            .filter(|token| match known.get_mut(token.word()) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            })
+            .count()
+    }
+
+    /// Store the results of the file.
+    pub fn insert(&mut self, path: &str, key: u64, tokens: Vec<TokenWithSuggestions>) {
+        self.entries.insert(path.to_string(), (key, tokens));
+        self.changed = true;
+    }
+
+    fn serialize(&self) -> String {
+        let mut output = format!("{}\n", HEADER);
+        let mut paths: Vec<&String> = self.entries.keys().collect();
+        paths.sort();
+        for path in paths {
+            let (key, tokens) = &self.entries[path];
+            output.push_str(&format!(
+                "{}\t{:016x}\t{}\n",
+                escape(path),
+                key,
+                tokens.len()
+            ));
+            for token in tokens {
+                let mut fields = vec![
+                    token.start().to_string(),
+                    token.end().to_string(),
+                    escape(token.word()),
+                    escape(token.language().unwrap_or_default()),
+                ];
+                fields.extend(
+                    token
+                        .suggestions()
+                        .iter()
+                        .map(|suggestion| escape(suggestion)),
+                );
+                output.push_str(&fields.join("\t"));
+                output.push('\n');
+            }
+        }
+        output
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+
+        let mut entries = HashMap::new();
+        while let Some(line) = lines.next() {
+            let mut fields = line.split('\t');
+            let path = unescape(fields.next()?);
+            let key = u64::from_str_radix(fields.next()?, 16).ok()?;
+            let count: usize = fields.next()?.parse().ok()?;
+
+            let mut tokens = Vec::with_capacity(count);
+            for _ in 0..count {
+                let mut fields = lines.next()?.split('\t');
+                let start = fields.next()?.parse().ok()?;
+                let end = fields.next()?.parse().ok()?;
+                let word = unescape(fields.next()?);
+                let language =
+                    Some(unescape(fields.next()?)).filter(|language| !language.is_empty());
+                let suggestions = fields.map(unescape).collect();
+                tokens.push(
+                    TokenWithSuggestions::new(Token::new(start, end, word), suggestions)
+                        .with_language(language),
+                );
+            }
+            entries.insert(path, (key, tokens));
+        }
+
+        Some(DiskCache {
+            entries,
+            changed: false,
+        })
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => output.push('\t'),
+            Some('n') => output.push('\n'),
+            Some('r') => output.push('\r'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut cache = DiskCache::default();
+        let token = TokenWithSuggestions::new(
+            Token::new(2, 5, "tset".into()),
+            vec!["test".into(), "te\tst".into()],
+        )
+        .with_language(Some("en-US".into()));
+        cache.insert("docs/a\\b.md", 42, vec![token]);
+        cache.insert("docs/empty.md", 7, Vec::new());
+
+        let parsed = DiskCache::parse(&cache.serialize()).unwrap();
+        assert!(parsed.get("docs/a\\b.md", 41).is_none());
+        let tokens = parsed.get("docs/a\\b.md", 42).unwrap();
+        assert_eq!(tokens[0].range(), 2..=5);
+        assert_eq!(tokens[0].word(), "tset");
+        assert_eq!(tokens[0].suggestions(), ["test", "te\tst"]);
+        assert_eq!(tokens[0].language(), Some("en-US"));
+        assert_eq!(parsed.get("docs/empty.md", 7).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(DiskCache::parse("ejaan-cache 0 0.0.0\n").is_none());
+        let truncated = format!("{}\nfile.md\t000000000000002a\t2\n1\t2\tab\t\n", HEADER);
+        assert!(DiskCache::parse(&truncated).is_none());
+    }
+
+    #[test]
+    fn test_count_new() {
+        let token = |start, word: &str| {
+            TokenWithSuggestions::new(Token::new(start, start + 3, word.into()), Vec::new())
+        };
+        let mut cache = DiskCache::default();
+        cache.insert("a.md", 1, vec![token(0, "tset"), token(10, "snet")]);
+
+        // The moved word is not new, but its second occurrence is
+        let tokens = [token(5, "tset"), token(20, "tset"), token(30, "abcd")];
+        assert_eq!(cache.count_new("a.md", &tokens), 2);
+        assert_eq!(cache.count_new("b.md", &tokens), 3);
+    }
+
+    #[test]
+    fn test_key() {
+        let options = CheckOptions::new();
+        let key = DiskCache::key("A tset", "en-US", &options, 0);
+        assert_eq!(key, DiskCache::key("A tset", "en-US", &options, 0));
+        assert_ne!(key, DiskCache::key("A tset", "id-ID", &options, 0));
+        assert_ne!(
+            key,
+            DiskCache::key("A tset", "en-US", &options.clone().with_check_keys(true), 0)
+        );
+        // e.g. after adding a word
+        assert_ne!(key, DiskCache::key("A tset", "en-US", &options, 1));
+    }
+}
@@ -0,0 +1,51 @@
+//! Edit distance between the words, for the fuzzy lookups of the dictionaries.
+
+/// Get the edit distance between two words if it is at most `max`, ignoring the case.
+///
+/// This is the optimal string alignment distance: the insertions, deletions, substitutions,
+/// and transpositions of two adjacent characters each cost one edit.
+pub fn within(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    // Only the last two rows are needed for the transpositions
+    let mut previous: Vec<usize> = Vec::new();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (row[j] + 1).min(current[j - 1] + 1).min(row[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(previous[j - 2] + 1);
+            }
+        }
+        // The distance never goes below the smallest value of a row
+        if current.iter().min().is_some_and(|min| *min > max) {
+            return None;
+        }
+        previous = std::mem::replace(&mut row, current);
+    }
+
+    Some(row[b.len()]).filter(|distance| *distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within() {
+        assert_eq!(within("ejaan", "ejaan", 0), Some(0));
+        assert_eq!(within("Ejaan", "ejaan", 0), Some(0));
+        assert_eq!(within("ejan", "ejaan", 2), Some(1));
+        assert_eq!(within("ejaan", "ejana", 2), Some(1));
+        assert_eq!(within("kitten", "sitting", 3), Some(3));
+        assert_eq!(within("kitten", "sitting", 2), None);
+        assert_eq!(within("", "abc", 3), Some(3));
+        assert_eq!(within("straße", "strase", 1), Some(1));
+    }
+}
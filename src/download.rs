@@ -0,0 +1,202 @@
+//! Download the dictionaries into a cache directory, verified by their SHA-256 checksum.
+//!
+//! Each file is named after its checksum, so a dictionary is only downloaded once
+//! and a corrupted or outdated file is downloaded again.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::utils::{EjaanError, Error};
+
+/// The largest dictionary accepted, the biggest Hunspell extensions are around 30 MiB.
+const MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The default cache directory of the dictionaries.
+///
+/// - Windows: `%LOCALAPPDATA%\ejaan\dictionaries`
+/// - macOS: `~/Library/Caches/ejaan/dictionaries`
+/// - Others: `$XDG_CACHE_HOME/ejaan/dictionaries`, or `~/.cache/ejaan/dictionaries`
+pub fn default_directory() -> EjaanError<PathBuf> {
+    let env = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+    let cache = if cfg!(target_os = "windows") {
+        env("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env("HOME").map(|home| Path::new(&home).join("Library").join("Caches"))
+    } else {
+        env("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env("HOME").map(|home| Path::new(&home).join(".cache")))
+    };
+
+    cache
+        .map(|cache| cache.join("ejaan").join("dictionaries"))
+        .ok_or_else(|| {
+            Error::new("Failed to find the cache directory, set the cacheLocation option")
+        })
+}
+
+/// Get the lowercase hexadecimal SHA-256 checksum of the content.
+pub fn sha256(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Get the path of a dictionary in the cache, keeping the extension of the URL for `load()`.
+fn cached_path(directory: &Path, url: &str, checksum: &str) -> PathBuf {
+    let name = url
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| extension.bytes().all(|b| b.is_ascii_alphanumeric()));
+    match extension {
+        Some(extension) => directory.join(format!("{}.{}", checksum, extension)),
+        None => directory.join(checksum),
+    }
+}
+
+/// Download a dictionary into the cache directory, and get its path.
+///
+/// The download is skipped when the file is already cached with the same checksum.
+pub fn download(url: &str, checksum: &str, directory: &Path) -> EjaanError<PathBuf> {
+    let checksum = checksum.trim().to_ascii_lowercase();
+    if checksum.len() != 64 || !checksum.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::new(format!(
+            "Invalid SHA-256 checksum: {}",
+            checksum
+        )));
+    }
+
+    let path = cached_path(directory, url, &checksum);
+    if fs::read(&path).is_ok_and(|content| sha256(&content) == checksum) {
+        return Ok(path);
+    }
+
+    let content = ureq::get(url)
+        .call()
+        .and_then(|mut response| {
+            response
+                .body_mut()
+                .with_config()
+                .limit(MAX_SIZE)
+                .read_to_vec()
+        })
+        .map_err(|e| Error::new(format!("Failed to download {}: {}", url, e)))?;
+    let actual = sha256(&content);
+    if actual != checksum {
+        return Err(Error::new(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            url, checksum, actual
+        )));
+    }
+
+    fs::create_dir_all(directory)
+        .map_err(|e| Error::new(format!("Failed to create {}: {}", directory.display(), e)))?;
+    // Write to a temporary file first, a concurrent reader never sees a partial dictionary
+    let partial = path.with_extension(format!("{}.part", std::process::id()));
+    fs::write(&partial, &content)
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&partial);
+            Error::new(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    /// Serve a single response on a local port, and get the URL of the file.
+    fn serve(body: &'static [u8], name: &str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+        });
+        format!("http://{}/files/{}?download=1", address, name)
+    }
+
+    fn directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("ejaan-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            sha256(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_cached_path() {
+        let directory = Path::new("cache");
+        assert_eq!(
+            cached_path(directory, "https://example.com/id_ID.oxt?raw=true", "abc"),
+            directory.join("abc.oxt")
+        );
+        assert_eq!(
+            cached_path(directory, "https://example.com/words", "abc"),
+            directory.join("abc")
+        );
+    }
+
+    #[test]
+    fn test_download() {
+        let body = b"2\nejaan\nnapi\n";
+        let directory = directory("download");
+        let checksum = sha256(body);
+
+        let path = download(
+            &serve(body, "words.dic"),
+            &checksum.to_uppercase(),
+            &directory,
+        )
+        .unwrap();
+        assert_eq!(path, directory.join(format!("{}.dic", checksum)));
+        assert_eq!(fs::read(&path).unwrap(), body);
+
+        // Cached, nothing is listening anymore
+        let cached = download("http://127.0.0.1:9/words.dic", &checksum, &directory).unwrap();
+        assert_eq!(cached, path);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let directory = directory("mismatch");
+        let url = serve(b"tampered\n", "words.dic");
+        let error = download(&url, &sha256(b"expected\n"), &directory).unwrap_err();
+        assert!(error.message().starts_with("Checksum mismatch"));
+        assert!(!directory.exists());
+
+        assert!(download(&url, "not a checksum", &directory).is_err());
+    }
+}
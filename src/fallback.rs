@@ -0,0 +1,300 @@
+//! Fallback languages, a word is only flagged when no language of the chain accepts it.
+//!
+//! The first language is checked by the native spell checker, each fallback language by a fork
+//! of it, see the `languages` option and `fromSystemPreferences()`.
+
+use std::hash::{Hash, Hasher};
+
+use crate::{
+    SpellCheckerImpl,
+    cache::CacheStats,
+    dictionaries::CustomDictionary,
+    locales,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, TokenWithSuggestions},
+};
+
+/// A spell checker accepting the words of any of its fallback languages.
+pub struct FallbackSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    fallbacks: Vec<Fallback>,
+}
+
+/// A fallback language, checked by a fork of the native spell checker.
+struct Fallback {
+    language: String,
+    checker: Box<dyn SpellCheckerImpl>,
+}
+
+impl FallbackSpellChecker {
+    fn new(inner: Box<dyn SpellCheckerImpl>, fallbacks: Vec<Fallback>) -> Self {
+        FallbackSpellChecker { inner, fallbacks }
+    }
+
+    /// Set the language of the spell checker to the first available of the preferred languages,
+    /// with the other available ones as fallbacks.
+    ///
+    /// The spell checker is unchanged when none of the languages is available.
+    pub fn with_languages(
+        mut inner: Box<dyn SpellCheckerImpl>,
+        preferred: &[String],
+    ) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        let available = inner.get_available_languages()?;
+        let chain = locales::resolve(preferred, &available);
+        let Some((language, others)) = chain.split_first() else {
+            return Ok(inner);
+        };
+
+        inner.set_language(language)?;
+        if others.is_empty() {
+            return Ok(inner);
+        }
+        let mut fallbacks = Vec::with_capacity(others.len());
+        for language in others {
+            let mut checker = inner.fork()?;
+            if checker.set_language(language)? {
+                fallbacks.push(Fallback {
+                    language: language.clone(),
+                    checker,
+                });
+            }
+        }
+        Ok(Box::new(Self::new(inner, fallbacks)))
+    }
+
+    fn accepted(&self, word: &str) -> bool {
+        self.fallbacks
+            .iter()
+            .any(|fallback| fallback.checker.check_word(word).unwrap_or(false))
+    }
+
+    /// Drop the tokens accepted by a fallback language, the others are also rejected by every
+    /// fallback language, up to `max_results` tokens.
+    fn filter(
+        &self,
+        tokens: EjaanError<Vec<TokenWithSuggestions>>,
+        max_results: Option<usize>,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let tokens = tokens?;
+        if self.fallbacks.is_empty() {
+            return Ok(tokens);
+        }
+        Ok(tokens
+            .into_iter()
+            .filter(|token| !self.accepted(token.word()))
+            .map(|token| {
+                self.fallbacks.iter().fold(token, |token, fallback| {
+                    token.also_rejected_by(fallback.language.clone())
+                })
+            })
+            .take(max_results.unwrap_or(usize::MAX))
+            .collect())
+    }
+}
+
+impl SpellCheckerImpl for FallbackSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        Ok(self.inner.check_word(word)? || self.accepted(word))
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.filter(self.inner.check_sentences(sentence), None)
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // The fallbacks may accept some of the first results, only stop after filtering them
+        let uncapped = options.clone().with_max_results(None);
+        self.filter(
+            self.inner.check_sentences_with(sentence, &uncapped),
+            options.max_results(),
+        )
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.filter(self.inner.check_sentences_utf16(sentence), None)
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.add_words(words)
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.add_words_chunked(words, on_chunk)
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.remove_words_chunked(words, on_chunk)
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        let fallbacks = self
+            .fallbacks
+            .iter()
+            .map(|fallback| {
+                Ok(Fallback {
+                    language: fallback.language.clone(),
+                    checker: fallback.checker.fork()?,
+                })
+            })
+            .collect::<EjaanError<Vec<_>>>()?;
+        Ok(Box::new(Self::new(self.inner.fork()?, fallbacks)))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        for fallback in &mut self.fallbacks {
+            fallback.checker.reset_session()?;
+        }
+        self.inner.reset_session()
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.inner.custom_dictionaries()
+    }
+
+    fn fingerprint(&self, mut state: &mut dyn Hasher) {
+        self.inner.fingerprint(state);
+        for fallback in &self.fallbacks {
+            fallback.language.hash(&mut state);
+        }
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        self.inner.set_dictionary_enabled(id, enabled)
+    }
+
+    fn clear_cache(&self) {
+        self.inner.clear_cache()
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        self.inner.cache_stats()
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    /// Only the first language is changed, the fallbacks are kept.
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        self.inner.set_language(language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::{
+        dictionaries::{DictionariesSpellChecker, WordList},
+        utils::{Diagnostic, Token},
+    };
+
+    fn checker(words: &str) -> Box<dyn SpellCheckerImpl> {
+        let lists = Arc::new(RwLock::new(vec![WordList::parse(words)]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        Box::new(DictionariesSpellChecker::new(inner, lists))
+    }
+
+    fn fallback(language: &str, words: &str) -> Fallback {
+        Fallback {
+            language: language.to_string(),
+            checker: checker(words),
+        }
+    }
+
+    fn token(start: usize, word: &str) -> TokenWithSuggestions {
+        TokenWithSuggestions::new(
+            Token::new(start, start + word.len(), word.to_string()),
+            Vec::new(),
+        )
+        .with_language(Some("en-US".to_string()))
+    }
+
+    #[test]
+    fn test_fallbacks() {
+        let checker =
+            FallbackSpellChecker::new(checker("walk\n"), vec![fallback("id-ID", "jalan\n")]);
+        assert!(checker.check_word("walk").unwrap());
+        // The stub backend knows no words, ask the fallbacks directly
+        assert!(checker.accepted("jalan"));
+        assert!(!checker.accepted("walk"));
+    }
+
+    #[test]
+    fn test_filter() {
+        let checker = FallbackSpellChecker::new(
+            checker(""),
+            vec![fallback("id-ID", "jalan\n"), fallback("ms-MY", "jalan\n")],
+        );
+        let tokens = vec![token(0, "jalan"), token(6, "wlak"), token(11, "tset")];
+
+        let filtered = checker.filter(Ok(tokens.clone()), None).unwrap();
+        assert_eq!(filtered.len(), 2);
+        let diagnostic = Diagnostic::spelling(filtered[0].clone());
+        assert_eq!(diagnostic.rejected_by(), ["en-US", "id-ID", "ms-MY"]);
+
+        // The accepted words do not count towards the results
+        let filtered = checker.filter(Ok(tokens), Some(2)).unwrap();
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[1].word(), "tset");
+    }
+
+    #[test]
+    fn test_unavailable_languages() {
+        // The stub backend has no languages to resolve
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        assert!(FallbackSpellChecker::with_languages(inner, &["en-US".to_string()]).is_err());
+    }
+}
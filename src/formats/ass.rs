@@ -0,0 +1,142 @@
+//! Advanced SubStation Alpha (`.ass`/`.ssa`) subtitles, checking only the text of the dialogues.
+//!
+//! The override blocks (e.g. `{\an8}` or the karaoke `{\k20}`) are removed, so the words
+//! split by them are checked whole, and the offsets are mapped back into the original line.
+//! The drawing commands and the line breaks (`\N`, `\n`, `\h`) are skipped.
+
+use super::{Masker, OffsetMap};
+
+/// The number of fields of a dialogue in the default `Format` line, the text is always the last.
+const DEFAULT_FIELDS: usize = 10;
+
+/// Mask everything but the text of the dialogues, removing the override blocks.
+pub fn mask(text: &str) -> (String, OffsetMap) {
+    let mut masker = Masker::new(text.len());
+    let mut in_events = false;
+    let mut fields = DEFAULT_FIELDS;
+
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let trimmed = body.trim_start();
+
+        if trimmed.starts_with('[') {
+            in_events = trimmed.eq_ignore_ascii_case("[events]");
+        } else if let Some(format) = trimmed.strip_prefix("Format:").filter(|_| in_events) {
+            fields = format.split(',').count();
+        } else if let Some(dialogue) = trimmed.strip_prefix("Dialogue:").filter(|_| in_events) {
+            let start = body.len() - dialogue.len();
+            let text_start = match fields.checked_sub(2) {
+                Some(commas) => dialogue
+                    .match_indices(',')
+                    .nth(commas)
+                    .map_or(body.len(), |(index, _)| start + index + 1),
+                None => start,
+            };
+            masker.blank(&body[..text_start]);
+            mask_dialogue(&mut masker, &body[text_start..]);
+            masker.keep(&line[body.len()..]);
+            continue;
+        }
+
+        masker.blank(body);
+        masker.keep(&line[body.len()..]);
+    }
+
+    masker.finish_mapped()
+}
+
+/// Remove the override blocks and mask the drawings of the dialogue text.
+fn mask_dialogue(masker: &mut Masker, text: &str) {
+    let mut drawing = false;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (plain, block) = match rest.find('{') {
+            Some(open) => rest.split_at(open),
+            None => (rest, ""),
+        };
+
+        if drawing {
+            masker.blank(plain);
+        } else {
+            masker.keep_except(plain, line_break_length);
+        }
+
+        if block.is_empty() {
+            break;
+        }
+        let end = block.find('}').map_or(block.len(), |close| close + 1);
+        let overrides = &block[..end];
+        if let Some(scale) = drawing_scale(overrides) {
+            drawing = scale > 0;
+        }
+        masker.remove(overrides);
+        rest = &block[end..];
+    }
+}
+
+/// Get the length of the line break at the start of the text, e.g. `\N`.
+fn line_break_length(text: &str) -> Option<usize> {
+    ["\\N", "\\n", "\\h"]
+        .iter()
+        .any(|line_break| text.starts_with(line_break))
+        .then_some(2)
+}
+
+/// Get the last drawing mode `\pN` set by the override block, if any.
+fn drawing_scale(overrides: &str) -> Option<u32> {
+    overrides
+        .split('\\')
+        .skip(1)
+        .filter_map(|tag| tag.strip_prefix('p'))
+        .filter_map(|scale| {
+            let digits = scale.trim_end_matches('}');
+            digits.parse().ok()
+        })
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let text = "\
+[Script Info]
+Title: A snetences
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize
+Style: Default,Arial,20
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Ignored snetences
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,Roger,0,0,0,,{\\an8}This is, a {\\i1}snetences{\\i0}\\Nnext
+Dialogue: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,{\\k20}Ka{\\k30}ra{\\p1}m 0 0 l 100 0{\\p0} done
+";
+        let (masked, offsets) = mask(text);
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec!["This", "is,", "a", "snetences", "next", "Kara", "done"]
+        );
+
+        // The offsets are mapped back into the original text
+        let original = |word: &str| {
+            let masked_offset = masked[..masked.rfind(word).unwrap()].encode_utf16().count();
+            offsets.original(masked_offset)
+        };
+        assert_eq!(original("snetences"), text.rfind("snetences").unwrap());
+        assert_eq!(original("next"), text.find("next").unwrap());
+        assert_eq!(original("Kara"), text.find("Ka{").unwrap());
+        assert_eq!(original("done"), text.find("done").unwrap());
+    }
+
+    #[test]
+    fn test_drawing_scale() {
+        assert_eq!(drawing_scale("{\\an8\\p1}"), Some(1));
+        assert_eq!(drawing_scale("{\\p0}"), Some(0));
+        assert_eq!(drawing_scale("{\\pos(1,2)}"), None);
+        assert_eq!(drawing_scale("{\\i1}"), None);
+    }
+}
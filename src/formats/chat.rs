@@ -0,0 +1,122 @@
+//! Chat messages with BBCode or Discord-flavored markdown.
+//!
+//! The code blocks, inline code, BBCode tags (e.g. `[spoiler]`), and the Discord syntax
+//! for custom emoji, mentions, and timestamps (e.g. `<:name:id>` or `<@id>`) are skipped.
+
+use super::Masker;
+
+/// Mask the markup of the chat message.
+pub fn mask(text: &str) -> String {
+    let mut masker = Masker::new(text.len());
+    masker.keep_except(text, markup_length);
+    masker.finish()
+}
+
+/// Get the length of the markup at the start of the text, if any.
+fn markup_length(text: &str) -> Option<usize> {
+    match text.as_bytes().first()? {
+        b'`' => code_length(text),
+        b'[' => bbcode_length(text),
+        b'<' => discord_length(text),
+        _ => None,
+    }
+}
+
+/// Get the length of a code block or inline code, e.g. ```` ```rust ... ``` ```` or `` `code` ``.
+fn code_length(text: &str) -> Option<usize> {
+    let fence = if text.starts_with("```") { "```" } else { "`" };
+    // An unterminated code block runs until the end, like in Discord
+    match text[fence.len()..].find(fence) {
+        Some(end) => Some(fence.len() + end + fence.len()),
+        None if fence == "```" => Some(text.len()),
+        None => None,
+    }
+}
+
+/// Get the length of a BBCode tag, or of a whole `[code]` block.
+fn bbcode_length(text: &str) -> Option<usize> {
+    let end = text.find(']')?;
+    let tag = &text[1..end];
+    let name = tag.strip_prefix('/').unwrap_or(tag);
+    let name = name.split_once('=').map_or(name, |(name, _)| name);
+    let valid = name == "*"
+        || (!name.is_empty() && name.len() <= 10 && name.chars().all(|c| c.is_ascii_alphabetic()));
+    if !valid {
+        return None;
+    }
+
+    if tag.eq_ignore_ascii_case("code") || tag.to_ascii_lowercase().starts_with("code=") {
+        let closing = text.to_ascii_lowercase().find("[/code]");
+        return Some(closing.map_or(text.len(), |closing| closing + "[/code]".len()));
+    }
+    Some(end + 1)
+}
+
+/// Get the length of the Discord syntax, e.g. `<:emoji:123>`, `<@123>`, `<#123>`, or `<t:123:R>`.
+fn discord_length(text: &str) -> Option<usize> {
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+    let is_id = |id: &str| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit());
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == ' ')
+    };
+
+    let valid = if let Some(mention) = inner.strip_prefix('@') {
+        // User and role mentions, e.g. `<@123>`, `<@!123>`, or `<@&123>`
+        is_id(mention.trim_start_matches(['!', '&']))
+    } else if let Some(channel) = inner.strip_prefix('#') {
+        is_id(channel)
+    } else if let Some(timestamp) = inner.strip_prefix("t:") {
+        let (time, style) = timestamp.split_once(':').unwrap_or((timestamp, "R"));
+        is_id(time) && style.len() == 1
+    } else {
+        // Custom emoji `<:name:123>` and `<a:name:123>`, or slash commands `</name:123>`
+        let emoji = inner
+            .strip_prefix("a:")
+            .or_else(|| inner.strip_prefix(':'))
+            .or_else(|| inner.strip_prefix('/'));
+        emoji
+            .and_then(|emoji| emoji.rsplit_once(':'))
+            .is_some_and(|(name, id)| is_name(name) && is_id(id))
+    };
+    valid.then_some(end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        let masked = mask(text);
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        masked.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_discord() {
+        assert_eq!(
+            words("Hi <@123> and <@&456>, run `cargo tset` in <#789> <:pepega:123> <a:wiggle:456>"),
+            vec!["Hi", "and", ",", "run", "in"]
+        );
+        assert_eq!(
+            words("||A snetences|| at <t:1700000000:R>\n```rust\nlet snetences = 1;\n```\ndone"),
+            vec!["||A", "snetences||", "at", "done"]
+        );
+        assert_eq!(words("a < b > c"), vec!["a", "<", "b", ">", "c"]);
+    }
+
+    #[test]
+    fn test_bbcode() {
+        assert_eq!(
+            words("[b]Bold[/b] [spoiler]snetences[/spoiler] [url=https://example.com]link[/url]"),
+            vec!["Bold", "snetences", "link"]
+        );
+        assert_eq!(
+            words("[CODE]fn tset()[/CODE] after [list][*]item[/list] [not a tag]"),
+            vec!["after", "item", "[not", "a", "tag]"]
+        );
+    }
+}
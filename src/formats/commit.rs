@@ -0,0 +1,225 @@
+//! Git commit messages, checking the subject and the body.
+//!
+//! The comment lines, everything below the scissors line, the trailers (e.g. `Signed-off-by:`),
+//! the conventional commit prefix (e.g. `feat(parser):`), the inline code spans,
+//! and the issue references (e.g. `#123`, `owner/repo#123`, or `JIRA-123`) are skipped.
+
+use super::Masker;
+
+/// The line below which everything is removed by `git commit --cleanup=scissors`.
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+/// Mask everything but the prose of the commit message.
+pub fn mask(text: &str) -> String {
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let end = lines
+        .iter()
+        .position(|line| line.starts_with('#') && line.contains(SCISSORS))
+        .unwrap_or(lines.len());
+    let is_comment = |line: &str| line.starts_with('#');
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    // The trailers are the last paragraph, if it only has trailers and is not the subject
+    let subject = lines[..end]
+        .iter()
+        .position(|line| !is_comment(line) && !is_blank(line));
+    let last = lines[..end]
+        .iter()
+        .rposition(|line| !is_comment(line) && !is_blank(line));
+    let trailers_start = last
+        .and_then(|last| {
+            let start = lines[..last].iter().rposition(|line| is_blank(line))? + 1;
+            let only_trailers = lines[start..=last]
+                .iter()
+                .filter(|line| !is_comment(line))
+                .all(|line| is_trailer(line.trim_end_matches(['\r', '\n'])));
+            only_trailers.then_some(start)
+        })
+        .unwrap_or(end);
+
+    let mut masker = Masker::new(text.len());
+    for (index, line) in lines.iter().enumerate() {
+        let body = line.trim_end_matches(['\r', '\n']);
+        if index >= end || index >= trailers_start || is_comment(line) {
+            masker.blank(body);
+        } else if Some(index) == subject {
+            let prefix = conventional_prefix(body);
+            masker.blank(&body[..prefix]);
+            mask_prose(&mut masker, &body[prefix..]);
+        } else {
+            mask_prose(&mut masker, body);
+        }
+        masker.keep(&line[body.len()..]);
+    }
+
+    masker.finish()
+}
+
+/// Mask the code spans and issue references of a line.
+fn mask_prose(masker: &mut Masker, line: &str) {
+    let mut rest = line;
+    while let Some(open) = rest.find('`') {
+        let Some(close) = rest[open + 1..].find('`') else {
+            break;
+        };
+        mask_references(masker, &rest[..open]);
+        let end = open + 1 + close + 1;
+        masker.blank(&rest[open..end]);
+        rest = &rest[end..];
+    }
+    mask_references(masker, rest);
+}
+
+/// Mask the issue references and links, split by whitespace.
+fn mask_references(masker: &mut Masker, text: &str) {
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let word = chunk.trim_end();
+        let core = word.trim_matches(['(', ')', '[', ']', ',', '.', ':', ';']);
+        if is_reference(core) {
+            let start = word.find(core).unwrap_or(0);
+            masker.keep(&word[..start]);
+            masker.blank(core);
+            masker.keep(&chunk[start + core.len()..]);
+        } else {
+            masker.keep(chunk);
+        }
+    }
+}
+
+/// Check if the word is an issue reference or a link.
+fn is_reference(word: &str) -> bool {
+    let is_number = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+    if word.starts_with("http://") || word.starts_with("https://") {
+        return true;
+    }
+
+    // `#123`, `repo#123`, or `owner/repo#123`
+    if let Some((repository, number)) = word.rsplit_once('#') {
+        let valid_repository = repository
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"/._-".contains(&b));
+        return is_number(number) && valid_repository;
+    }
+
+    // `JIRA-123` or `GH-123`
+    word.rsplit_once('-').is_some_and(|(project, number)| {
+        is_number(number)
+            && project.starts_with(|c: char| c.is_ascii_uppercase())
+            && project
+                .bytes()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+    })
+}
+
+/// Check if the line is a trailer, e.g. `Signed-off-by: Name <email>` or a continuation of it.
+fn is_trailer(line: &str) -> bool {
+    if line.starts_with([' ', '\t']) {
+        return true;
+    }
+    line.split_once(':').is_some_and(|(token, _)| {
+        !token.is_empty()
+            && token
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    })
+}
+
+/// Get the length of the conventional commit prefix of the subject, e.g. `feat(parser)!: `.
+fn conventional_prefix(subject: &str) -> usize {
+    let Some((prefix, _)) = subject.split_once(": ") else {
+        return 0;
+    };
+
+    let kind = prefix.trim_end_matches('!');
+    let kind = match kind.split_once('(') {
+        Some((kind, scope)) if scope.ends_with(')') => kind,
+        Some(_) => return 0,
+        None => kind,
+    };
+    let valid = !kind.is_empty() && kind.bytes().all(|b| b.is_ascii_lowercase());
+    if valid { prefix.len() + 2 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        let masked = mask(text);
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        masked.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_mask() {
+        let text = "\
+feat(tokenizr): Add a snetences check
+
+Fixes #12 and owner/repo#34 (see JIRA-56), calls `chek_word` now.
+Key: not a trailer in the body.
+
+Signed-off-by: Some Persn <persn@example.com>
+Co-authored-by: Anothr <anothr@example.com>
+# Please enter the commit message for your changes.
+# ------------------------ >8 ------------------------
+diff --git a/src b/src
+";
+        assert_eq!(
+            words(text),
+            vec![
+                "Add",
+                "a",
+                "snetences",
+                "check",
+                "Fixes",
+                "and",
+                "(see",
+                "),",
+                "calls",
+                "now.",
+                "Key:",
+                "not",
+                "a",
+                "trailer",
+                "in",
+                "the",
+                "body."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conventional_prefix() {
+        assert_eq!(conventional_prefix("fix: typo"), 5);
+        assert_eq!(conventional_prefix("feat(api)!: breaking"), 12);
+        assert_eq!(conventional_prefix("Note: not a prefix"), 0);
+        assert_eq!(conventional_prefix("no prefix"), 0);
+    }
+
+    #[test]
+    fn test_last_paragraph() {
+        assert_eq!(
+            words("Fix tset\n\nThe last paragraph is prose.\nNote: kept too.\n"),
+            vec![
+                "Fix",
+                "tset",
+                "The",
+                "last",
+                "paragraph",
+                "is",
+                "prose.",
+                "Note:",
+                "kept",
+                "too."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subject_only() {
+        assert_eq!(
+            words("Fix snetences: Signed-off-by"),
+            vec!["Fix", "snetences:", "Signed-off-by"]
+        );
+    }
+}
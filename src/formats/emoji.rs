@@ -0,0 +1,130 @@
+//! Emoji shortcodes (e.g. `:thumbsup:`) and raw emoji sequences, skipped in every format.
+
+use super::Masker;
+
+/// The maximum length of a shortcode name, without the colons.
+const MAX_SHORTCODE: usize = 64;
+
+/// Mask the emoji shortcodes and sequences, or `None` if the text has none.
+pub fn mask(text: &str) -> Option<String> {
+    let mut chars = text.char_indices();
+    let found = chars.any(|(index, c)| {
+        is_emoji(c) || is_presentation(c) || shortcode_length(text, index).is_some()
+    });
+    if !found {
+        return None;
+    }
+
+    let mut masker = Masker::new(text.len());
+    let mut index = 0;
+    let mut kept = 0;
+    let mut previous_emoji = false;
+    while let Some(c) = text[index..].chars().next() {
+        let joined = previous_emoji && c == '\u{200D}';
+        let length = if is_emoji(c) || is_presentation(c) || joined {
+            previous_emoji = true;
+            Some(c.len_utf8())
+        } else {
+            previous_emoji = false;
+            shortcode_length(text, index)
+        };
+
+        match length {
+            Some(length) => {
+                masker.keep(&text[kept..index]);
+                masker.blank(&text[index..index + length]);
+                index += length;
+                kept = index;
+            }
+            None => index += c.len_utf8(),
+        }
+    }
+    masker.keep(&text[kept..]);
+
+    Some(masker.finish())
+}
+
+/// Get the length of the shortcode starting at the index, e.g. `:thumbsup:` or `:+1:`.
+fn shortcode_length(text: &str, index: usize) -> Option<usize> {
+    let rest = text[index..].strip_prefix(':')?;
+    // Avoid matching inside words or times, e.g. `a:b:c` or `10:30:00`
+    if text[..index]
+        .chars()
+        .next_back()
+        .is_some_and(char::is_alphanumeric)
+    {
+        return None;
+    }
+
+    let end = rest.find(':')?;
+    let name = &rest[..end];
+    let valid = (1..=MAX_SHORTCODE).contains(&name.len())
+        && !name.bytes().all(|b| b.is_ascii_digit())
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"_+-".contains(&b));
+    let followed_by_word = rest[end + 1..]
+        .chars()
+        .next()
+        .is_some_and(char::is_alphanumeric);
+    (valid && !followed_by_word).then_some(end + 2)
+}
+
+/// Check if the char is an emoji or a pictograph.
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF
+            | 0x2600..=0x27BF
+            | 0x2B00..=0x2BFF
+            | 0x2190..=0x21FF
+            | 0x2300..=0x23FF
+            | 0xE0020..=0xE007F
+            | 0x00A9
+            | 0x00AE
+            | 0x203C
+            | 0x2049
+            | 0x2122
+            | 0x2139
+            | 0x3030
+            | 0x303D
+            | 0x3297
+            | 0x3299
+    )
+}
+
+/// Check if the char selects the emoji presentation of the previous char, e.g. for the keycaps.
+fn is_presentation(c: char) -> bool {
+    matches!(c, '\u{FE0F}' | '\u{FE0E}' | '\u{20E3}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcodes() {
+        let text = "Nice :thumbsup: and :+1: but not 10:30:00 or a:b:c, :not a code:";
+        let masked = mask(text).unwrap();
+        assert_eq!(masked.len(), text.len());
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec![
+                "Nice", "and", "but", "not", "10:30:00", "or", "a:b:c,", ":not", "a", "code:"
+            ]
+        );
+        assert!(mask("No emoji here: really").is_none());
+    }
+
+    #[test]
+    fn test_sequences() {
+        // Family, flag, and keycap sequences
+        let text = "Hi 👨‍👩‍👧 🇮🇩 1️⃣ ✨ done";
+        let masked = mask(text).unwrap();
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec!["Hi", "1", "done"]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Gettext catalogs (`.po`/`.pot`), checking only the translated `msgstr` entries.
+//!
+//! The source `msgid` entries, the comments, and the format placeholders are skipped.
+
+use super::{Masker, escape_length, placeholder::Placeholders};
+
+/// Mask everything but the translations, returning the language declared in the header.
+pub fn mask(text: &str) -> (String, Option<String>) {
+    let mut masker = Masker::new(text.len());
+    let mut keyword = Keyword::Other;
+    // The `msgid` of the current entry, an empty one marks the header entry
+    let mut msgid = String::new();
+    let mut header = String::new();
+
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let rest = body.trim_start();
+        masker.blank(&body[..body.len() - rest.len()]);
+
+        let string = if rest.starts_with('"') {
+            rest
+        } else if rest.starts_with("msg") {
+            let start = rest.find('"').unwrap_or(rest.len());
+            keyword = Keyword::parse(&rest[..start]);
+            if keyword == Keyword::Id {
+                msgid.clear();
+            }
+            masker.blank(&rest[..start]);
+            &rest[start..]
+        } else {
+            // Comments, including the previous `#|` and obsolete `#~` entries
+            masker.blank(rest);
+            masker.keep(&line[body.len()..]);
+            continue;
+        };
+
+        let (open, content, close) = split_quoted(string);
+        masker.blank(open);
+        match keyword {
+            Keyword::Str if msgid.is_empty() => {
+                header.push_str(content);
+                masker.blank(content);
+            }
+            Keyword::Str => masker.keep_except(content, |rest| {
+                escape_length(rest).or_else(|| Placeholders::builtin().length(rest))
+            }),
+            Keyword::Id => {
+                msgid.push_str(content);
+                masker.blank(content);
+            }
+            Keyword::Other => masker.blank(content),
+        }
+        masker.blank(close);
+        masker.keep(&line[body.len()..]);
+    }
+
+    (masker.finish(), header_language(&header))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    /// The `msgid` source string.
+    Id,
+    /// The `msgstr` translation, including the plural forms `msgstr[N]`.
+    Str,
+    /// The other keywords, e.g. `msgctxt` and `msgid_plural`.
+    Other,
+}
+
+impl Keyword {
+    fn parse(keyword: &str) -> Self {
+        match keyword.trim() {
+            "msgid" => Keyword::Id,
+            "msgstr" => Keyword::Str,
+            keyword if keyword.starts_with("msgstr[") => Keyword::Str,
+            _ => Keyword::Other,
+        }
+    }
+}
+
+/// Split a quoted string into the opening quote, the content, and the closing quote with the rest.
+fn split_quoted(string: &str) -> (&str, &str, &str) {
+    let Some(content) = string.strip_prefix('"') else {
+        return ("", "", string);
+    };
+
+    let mut index = 0;
+    while index < content.len() {
+        match content.as_bytes()[index] {
+            b'"' => break,
+            b'\\' => index += 2,
+            _ => index += 1,
+        }
+    }
+    let index = index.min(content.len());
+    (&string[..1], &content[..index], &content[index..])
+}
+
+/// Get the language declared in the header, e.g. `Language: id\n`.
+fn header_language(header: &str) -> Option<String> {
+    header
+        .split("\\n")
+        .find_map(|field| field.trim().strip_prefix("Language:"))
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let text = r#"# Translator comment snetences
+msgid ""
+msgstr ""
+"Project-Id-Version: ejaan\n"
+"Language: id\n"
+
+#: src/main.rs:12
+#, c-format
+msgid "Hello %s, you have mistkae"
+msgstr "Halo %s, kamu punya %1$d kesalahaan"
+
+msgctxt "menu"
+msgid "One file"
+msgid_plural "%d files"
+msgstr[0] "Satu berkas {name}"
+msgstr[1] "%d "
+"berkas\n"
+"#;
+        let (masked, language) = mask(text);
+        assert_eq!(language.as_deref(), Some("id"));
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec![
+                "Halo",
+                ",",
+                "kamu",
+                "punya",
+                "kesalahaan",
+                "Satu",
+                "berkas",
+                "berkas"
+            ]
+        );
+        assert_eq!(masked.find("kesalahaan"), text.find("kesalahaan"));
+    }
+}
@@ -0,0 +1,120 @@
+//! JSON documents, checking only the string values and optionally the keys.
+
+use super::Masker;
+use crate::utils::{EjaanError, Error};
+
+/// Mask everything but the strings of the JSON document.
+///
+/// The document is only validated as far as needed to find the strings.
+pub fn mask(text: &str, check_keys: bool) -> EjaanError<String> {
+    let bytes = text.as_bytes();
+    let mut masker = Masker::new(text.len());
+    // Are the opened containers objects or arrays
+    let mut containers: Vec<bool> = Vec::new();
+    let mut expect_key = false;
+    // Everything before this index is already masked
+    let mut masked = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => {
+                let end = string_end(text, index)?;
+                masker.blank(&text[masked..=index]);
+                let content = &text[index + 1..end];
+                if expect_key && !check_keys {
+                    masker.blank(content);
+                } else {
+                    masker.keep_escaped(content);
+                }
+                expect_key = false;
+                masked = end;
+                index = end;
+            }
+            b'{' => {
+                containers.push(true);
+                expect_key = true;
+            }
+            b'[' => containers.push(false),
+            close @ (b'}' | b']') => {
+                if containers.pop() != Some(close == b'}') {
+                    return Err(Error::new(format!(
+                        "Invalid JSON: unexpected `{}` at byte {}",
+                        close as char, index
+                    )));
+                }
+                expect_key = false;
+            }
+            b',' => expect_key = containers.last() == Some(&true),
+            b':' => expect_key = false,
+            _ => {}
+        }
+        index += 1;
+    }
+
+    if !containers.is_empty() {
+        return Err(Error::new("Invalid JSON: unexpected end of the document"));
+    }
+
+    masker.blank(&text[masked..]);
+    Ok(masker.finish())
+}
+
+/// Find the closing quote of the string starting at `start`.
+fn string_end(text: &str, start: usize) -> EjaanError<usize> {
+    let bytes = text.as_bytes();
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => return Ok(index),
+            b'\\' => index += 2,
+            b'\n' => break,
+            _ => index += 1,
+        }
+    }
+
+    Err(Error::new(format!(
+        "Invalid JSON: unterminated string at byte {}",
+        start
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let text = r#"{"title": "A snetences", "tags": ["mistkae", 1, true], "nested": {"key": "Yes\nno"}}"#;
+        let masked = mask(text, false).unwrap();
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec!["A", "snetences", "mistkae", "Yes", "no"]
+        );
+        assert_eq!(masked.find("snetences"), text.find("snetences"));
+
+        let masked = mask(text, true).unwrap();
+        assert_eq!(
+            masked.split_whitespace().collect::<Vec<_>>(),
+            vec![
+                "title",
+                "A",
+                "snetences",
+                "tags",
+                "mistkae",
+                "nested",
+                "key",
+                "Yes",
+                "no"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(mask(r#"{"title": "A snetences}"#, false).is_err());
+        assert!(mask(r#"{"title": ["A"}"#, false).is_err());
+        assert!(mask(r#"{"title": "A""#, false).is_err());
+    }
+}
@@ -0,0 +1,358 @@
+//! Structured document formats, checking only the prose inside of them.
+//!
+//! Each format masks everything which is not prose (syntax, keys, numbers, ...) with spaces
+//! of the same UTF-16 length, so the offsets from the native spell checkers still point
+//! into the original source.
+
+use std::{borrow::Cow, path::Path};
+
+use crate::{
+    SpellCheckerImpl,
+    utils::{CheckOptions, EjaanError, Error, TokenWithSuggestions},
+};
+
+mod ass;
+mod chat;
+mod commit;
+mod emoji;
+mod gettext;
+mod json;
+pub mod placeholder;
+mod subtitle;
+mod yaml;
+
+/// The format of the checked text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Plain text, everything is checked.
+    #[default]
+    Plain,
+    /// A JSON document, only the string values are checked.
+    Json,
+    /// A YAML document, only the string values are checked.
+    Yaml,
+    /// A gettext catalog (`.po`/`.pot`), only the translations are checked.
+    Gettext,
+    /// SubRip or WebVTT subtitles (`.srt`/`.vtt`), only the caption text is checked.
+    Subtitle,
+    /// Advanced SubStation Alpha subtitles (`.ass`/`.ssa`), only the text of the dialogues is checked.
+    Ass,
+    /// Chat messages with BBCode or Discord-flavored markdown, the markup and code are skipped.
+    Chat,
+    /// A git commit message, the trailers, issue references, and code spans are skipped.
+    Commit,
+}
+
+impl Format {
+    /// Parse the format from its name, e.g. `json`.
+    pub fn parse(name: &str) -> EjaanError<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "plain" | "text" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "gettext" | "po" | "pot" => Ok(Format::Gettext),
+            "subtitle" | "srt" | "vtt" => Ok(Format::Subtitle),
+            "ass" | "ssa" => Ok(Format::Ass),
+            "chat" | "bbcode" | "discord" => Ok(Format::Chat),
+            "commit" | "git-commit" => Ok(Format::Commit),
+            _ => Err(Error::new(format!("Unknown format: {}", name))),
+        }
+    }
+
+    /// Guess the format from the name of the file, e.g. `messages.po` or `COMMIT_EDITMSG`.
+    pub fn from_path(path: &Path) -> Format {
+        if path
+            .file_name()
+            .is_some_and(|name| name == "COMMIT_EDITMSG")
+        {
+            return Format::Commit;
+        }
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| Format::parse(extension).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// A text prepared for checking.
+pub struct Prepared<'a> {
+    /// The text with everything which should not be checked masked.
+    text: Cow<'a, str>,
+    /// The language declared by the document, if any.
+    language: Option<String>,
+    /// The offsets of the text removed from the document, instead of being masked.
+    offsets: OffsetMap,
+}
+
+impl Prepared<'_> {
+    /// Is the text checked as-is, nothing was masked or removed and the language is unchanged.
+    pub fn is_unchanged(&self) -> bool {
+        matches!(self.text, Cow::Borrowed(_)) && self.language.is_none() && self.offsets.is_empty()
+    }
+
+    /// Check the text, switching to the language of the document for this call.
+    pub fn check(
+        &self,
+        backend: &mut dyn SpellCheckerImpl,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let Some(language) = &self.language else {
+            return self.check_text(backend, options);
+        };
+
+        // Unavailable languages are ignored, the text is checked with the current language
+        let previous = backend.get_language()?;
+        let mut switched = false;
+        if previous
+            .as_ref()
+            .is_some_and(|previous| previous != language)
+        {
+            // Documents may use either `pt_BR` or `pt-BR`, try both spellings
+            for candidate in [language.clone(), language.replace('_', "-")] {
+                switched = backend.set_language(&candidate)?;
+                if switched {
+                    break;
+                }
+            }
+        }
+
+        let result = self.check_text(backend, options);
+        if let (true, Some(previous)) = (switched, previous) {
+            backend.set_language(&previous)?;
+        }
+        result
+    }
+
+    fn check_text(
+        &self,
+        backend: &mut dyn SpellCheckerImpl,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let tokens = backend.check_sentences_with(&self.text, options)?;
+        if self.offsets.is_empty() {
+            return Ok(tokens);
+        }
+
+        Ok(tokens
+            .into_iter()
+            .map(|token| token.map_offsets(|offset| self.offsets.original(offset)))
+            .collect())
+    }
+}
+
+/// Prepare the text for checking, masking everything which should not be checked.
+pub fn prepare<'a>(text: &'a str, options: &CheckOptions) -> EjaanError<Prepared<'a>> {
+    let mut offsets = OffsetMap::default();
+    let (text, language) = match options.format() {
+        Format::Plain => (Cow::Borrowed(text), None),
+        Format::Json => (Cow::Owned(json::mask(text, options.check_keys())?), None),
+        Format::Yaml => (Cow::Owned(yaml::mask(text, options.check_keys())), None),
+        Format::Gettext => {
+            let (masked, language) = gettext::mask(text);
+            (Cow::Owned(masked), language)
+        }
+        Format::Subtitle => (Cow::Owned(subtitle::mask(text)), None),
+        Format::Ass => {
+            let (masked, removed) = ass::mask(text);
+            offsets = removed;
+            (Cow::Owned(masked), None)
+        }
+        Format::Chat => (Cow::Owned(chat::mask(text)), None),
+        Format::Commit => (Cow::Owned(commit::mask(text)), None),
+    };
+    // The masking keeps the length, the offsets are still valid
+    let text = match options.skip_emoji().then(|| emoji::mask(&text)).flatten() {
+        Some(masked) => Cow::Owned(masked),
+        None => text,
+    };
+    let text = match options.placeholders().mask(&text) {
+        Some(masked) => Cow::Owned(masked),
+        None => text,
+    };
+    Ok(Prepared {
+        text,
+        language,
+        offsets,
+    })
+}
+
+/// Maps the UTF-16 offsets of a text with some parts removed back into the original text.
+#[derive(Debug, Clone, Default)]
+pub struct OffsetMap {
+    /// The offsets where some text was removed, with the total length removed so far.
+    removed: Vec<(usize, usize)>,
+}
+
+impl OffsetMap {
+    fn is_empty(&self) -> bool {
+        self.removed.is_empty()
+    }
+
+    /// Get the offset in the original text.
+    fn original(&self, offset: usize) -> usize {
+        let index = self.removed.partition_point(|(at, _)| *at <= offset);
+        match index {
+            0 => offset,
+            _ => offset + self.removed[index - 1].1,
+        }
+    }
+}
+
+/// A copy of the text where the unchecked parts are replaced by spaces.
+struct Masker {
+    masked: String,
+    offsets: OffsetMap,
+    /// The UTF-16 length of the masked text up to `counted`, only computed when removing text.
+    length: usize,
+    counted: usize,
+}
+
+impl Masker {
+    fn new(capacity: usize) -> Self {
+        Masker {
+            masked: String::with_capacity(capacity),
+            offsets: OffsetMap::default(),
+            length: 0,
+            counted: 0,
+        }
+    }
+
+    /// Keep the text as-is, to be checked.
+    fn keep(&mut self, text: &str) {
+        self.masked.push_str(text);
+    }
+
+    /// Replace the text with spaces, keeping the line breaks and the UTF-16 length.
+    fn blank(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' | '\r' => self.masked.push(c),
+                _ => self.masked.extend(std::iter::repeat_n(' ', c.len_utf16())),
+            }
+        }
+    }
+
+    /// Keep the text, replacing the parts matched by `skip` with spaces.
+    ///
+    /// `skip` is called with the rest of the text at each char, returning the length in bytes to replace.
+    fn keep_except(&mut self, text: &str, skip: impl Fn(&str) -> Option<usize>) {
+        let mut kept = 0;
+        let mut index = 0;
+        while let Some(c) = text[index..].chars().next() {
+            match skip(&text[index..]).filter(|length| *length > 0) {
+                Some(length) => {
+                    self.keep(&text[kept..index]);
+                    self.blank(&text[index..index + length]);
+                    index += length;
+                    kept = index;
+                }
+                None => index += c.len_utf8(),
+            }
+        }
+        self.keep(&text[kept..]);
+    }
+
+    /// Keep the content of a quoted string, replacing the backslash escapes with spaces.
+    fn keep_escaped(&mut self, text: &str) {
+        self.keep_except(text, escape_length);
+    }
+
+    /// Remove the text entirely, e.g. the tags inside of a word.
+    ///
+    /// The offsets are mapped back with the `OffsetMap` from `finish_mapped()`.
+    fn remove(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        self.length += self.masked[self.counted..].encode_utf16().count();
+        self.counted = self.masked.len();
+        let removed = self.offsets.removed.last().map_or(0, |(_, total)| *total);
+        self.offsets
+            .removed
+            .push((self.length, removed + text.encode_utf16().count()));
+    }
+
+    fn finish(self) -> String {
+        self.masked
+    }
+
+    fn finish_mapped(self) -> (String, OffsetMap) {
+        (self.masked, self.offsets)
+    }
+}
+
+/// Get the length of the backslash escape at the start of the text, e.g. `\n` or `\u00e9`.
+fn escape_length(text: &str) -> Option<usize> {
+    let mut chars = text.chars();
+    if chars.next()? != '\\' {
+        return None;
+    }
+    let Some(escaped) = chars.next() else {
+        return Some(1);
+    };
+
+    let digits = match escaped {
+        'x' => 2,
+        'u' => 4,
+        'U' => 8,
+        _ => 0,
+    };
+    let hex = chars
+        .take(digits)
+        .take_while(char::is_ascii_hexdigit)
+        .count();
+    Some(1 + escaped.len_utf8() + hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Format::parse("JSON").unwrap(), Format::Json);
+        assert_eq!(Format::parse("yml").unwrap(), Format::Yaml);
+        assert!(Format::parse("toml").is_err());
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            Format::from_path(Path::new("locales/id.po")),
+            Format::Gettext
+        );
+        assert_eq!(
+            Format::from_path(Path::new(".git/COMMIT_EDITMSG")),
+            Format::Commit
+        );
+        assert_eq!(Format::from_path(Path::new("README.md")), Format::Plain);
+        assert_eq!(Format::from_path(Path::new("LICENSE")), Format::Plain);
+    }
+
+    #[test]
+    fn test_masker() {
+        let mut masker = Masker::new(0);
+        masker.blank("😀 a\n");
+        masker.keep_escaped(r"Don't\nstop");
+        assert_eq!(masker.finish(), "    \nDon't  stop");
+    }
+
+    #[test]
+    fn test_offset_map() {
+        let mut masker = Masker::new(0);
+        masker.keep("😀 Ka");
+        masker.remove("{\\k20}");
+        masker.keep("ra");
+        masker.remove("{a}");
+        masker.remove("{b}");
+        masker.keep("!");
+
+        let (masked, offsets) = masker.finish_mapped();
+        assert_eq!(masked, "😀 Kara!");
+        assert_eq!(offsets.original(4), 4);
+        assert_eq!(offsets.original(5), 11);
+        assert_eq!(offsets.original(6), 12);
+        assert_eq!(offsets.original(7), 19);
+    }
+}
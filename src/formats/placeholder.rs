@@ -0,0 +1,166 @@
+//! Template placeholders, e.g. `{name}`, `{{var}}`, `${env}`, `%s`, or `%(key)s`.
+
+use super::Masker;
+
+/// The maximum length of a placeholder name inside single braces, e.g. `{name}`.
+const MAX_BRACE_NAME: usize = 32;
+
+/// The placeholder syntaxes to skip when checking.
+#[derive(Debug, Clone, Default, Hash)]
+pub struct Placeholders {
+    /// Skip the built-in syntaxes.
+    builtin: bool,
+    /// The extra opening and closing delimiters, e.g. `[[` and `]]`.
+    delimiters: Vec<(String, String)>,
+}
+
+impl Placeholders {
+    /// Skip the built-in syntaxes: `{name}`, `{{var}}`, `${env}`, and the printf-style `%s` or `%(key)s`.
+    pub fn builtin() -> Self {
+        Placeholders {
+            builtin: true,
+            delimiters: Vec::new(),
+        }
+    }
+
+    /// Also skip the placeholders between the delimiters, e.g. `[[` and `]]`.
+    pub fn with_delimiters(mut self, open: String, close: String) -> Self {
+        self.delimiters.push((open, close));
+        self
+    }
+
+    /// Check if there is no placeholder syntax to skip.
+    pub fn is_empty(&self) -> bool {
+        !self.builtin && self.delimiters.is_empty()
+    }
+
+    /// Mask the placeholders, or `None` if there is nothing to skip.
+    pub fn mask(&self, text: &str) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut masker = Masker::new(text.len());
+        masker.keep_except(text, |rest| self.length(rest));
+        Some(masker.finish())
+    }
+
+    /// Get the length of the placeholder at the start of the text, if any.
+    pub fn length(&self, text: &str) -> Option<usize> {
+        let custom = self.delimiters.iter().find_map(|(open, close)| {
+            let rest = text.strip_prefix(open.as_str())?;
+            let end = rest.find(close.as_str())?;
+            (!rest[..end].contains('\n')).then_some(open.len() + end + close.len())
+        });
+        if custom.is_some() || !self.builtin {
+            return custom;
+        }
+
+        let bytes = text.as_bytes();
+        match bytes.first()? {
+            b'%' => printf_length(bytes),
+            b'$' if text[1..].starts_with('{') => delimited_length(text, "${", "}"),
+            b'{' if text[1..].starts_with('{') => delimited_length(text, "{{", "}}"),
+            b'{' => {
+                // Python and C# style, e.g. `{0}` or `{name}`
+                let end = text.find('}')?;
+                let name = &text[1..end];
+                let valid = name.len() <= MAX_BRACE_NAME
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "_.:!,[]".contains(c));
+                valid.then_some(end + 1)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Get the length of a placeholder on a single line between the delimiters.
+fn delimited_length(text: &str, open: &str, close: &str) -> Option<usize> {
+    let end = text[open.len()..].find(close)?;
+    let inner = &text[open.len()..open.len() + end];
+    (!inner.contains('\n')).then_some(open.len() + end + close.len())
+}
+
+/// Get the length of a printf-style placeholder, e.g. `%-5.2f`, `%(name)s`, or `%{name}`.
+fn printf_length(bytes: &[u8]) -> Option<usize> {
+    let mut index = 1;
+    match bytes.get(index)? {
+        b'%' => return Some(2),
+        b'(' | b'{' => {
+            let close = if bytes[index] == b'(' { b')' } else { b'}' };
+            let end = bytes.iter().position(|byte| *byte == close)?;
+            if bytes[index + 1..end].iter().any(u8::is_ascii_whitespace) {
+                return None;
+            }
+            index = end + 1;
+            if close == b'}' {
+                return Some(index);
+            }
+        }
+        _ => {
+            // Positional argument, e.g. `%1$s`
+            let digits = bytes[index..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            if digits > 0 && bytes.get(index + digits) == Some(&b'$') {
+                index += digits + 1;
+            }
+        }
+    }
+
+    while bytes
+        .get(index)
+        .is_some_and(|b| b"-+#0123456789.*hlLqjzt".contains(b))
+    {
+        index += 1;
+    }
+    bytes
+        .get(index)
+        .filter(|b| b"diouxXeEfFgGaAcspn@".contains(b))
+        .map(|_| index + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_length() {
+        let placeholders = Placeholders::builtin();
+        assert_eq!(placeholders.length("%s files"), Some(2));
+        assert_eq!(placeholders.length("%1$d"), Some(4));
+        assert_eq!(placeholders.length("%-5.2f"), Some(6));
+        assert_eq!(placeholders.length("%(name)s"), Some(8));
+        assert_eq!(placeholders.length("%{name}"), Some(7));
+        assert_eq!(placeholders.length("%%"), Some(2));
+        assert_eq!(placeholders.length("{0}"), Some(3));
+        assert_eq!(placeholders.length("{{ user.name }}!"), Some(15));
+        assert_eq!(placeholders.length("${HOME}/x"), Some(7));
+        assert_eq!(placeholders.length("% of"), None);
+        assert_eq!(placeholders.length("{ not a placeholder }"), None);
+    }
+
+    #[test]
+    fn test_mask() {
+        let placeholders = Placeholders::default();
+        assert!(placeholders.is_empty());
+        assert!(placeholders.mask("Hello {nmae}").is_none());
+
+        let placeholders = Placeholders::default().with_delimiters("[[".into(), "]]".into());
+        assert_eq!(
+            placeholders.mask("Hello [[nmae]], {nmae}").unwrap(),
+            "Hello         , {nmae}"
+        );
+
+        let placeholders = Placeholders::builtin();
+        assert_eq!(
+            placeholders
+                .mask("Dear {{fisrtName}}, %(cnt)d new at ${enviroment}")
+                .unwrap(),
+            "Dear              ,         new at              "
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! SubRip (`.srt`) and WebVTT (`.vtt`) subtitles, checking only the caption text.
+//!
+//! The sequence numbers, cue identifiers, timestamps and cue settings are skipped,
+//! as well as the `WEBVTT` header, the `NOTE`, `STYLE`, and `REGION` blocks, and the inline tags.
+
+use super::Masker;
+
+/// Mask everything but the caption text.
+pub fn mask(text: &str) -> String {
+    let mut masker = Masker::new(text.len());
+    // Have we seen the timing line of the current block, the rest of the block is the caption
+    let mut in_caption = false;
+
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        if body.trim().is_empty() {
+            in_caption = false;
+            masker.keep(line);
+        } else if in_caption {
+            masker.keep_except(body, tag_length);
+            masker.keep(&line[body.len()..]);
+        } else {
+            // The sequence number, the cue identifier, the timing line, or a block without any cue
+            in_caption = body.contains("-->");
+            masker.blank(line);
+        }
+    }
+
+    masker.finish()
+}
+
+/// Get the length of the inline tag at the start of the text, e.g. `<i>`, `{\an8}`, or `&amp;`.
+fn tag_length(text: &str) -> Option<usize> {
+    let (close, max_length) = match text.as_bytes().first()? {
+        b'<' => ('>', usize::MAX),
+        b'{' if text[1..].starts_with('\\') => ('}', usize::MAX),
+        b'&' => (';', 10),
+        _ => return None,
+    };
+
+    let end = text.find(close)?;
+    let inner = &text[1..end];
+    let valid = end <= max_length && !inner.is_empty() && !inner.contains('\n');
+    let entity = close != ';' || inner.chars().all(|c| c.is_ascii_alphanumeric() || c == '#');
+    (valid && entity).then_some(end + close.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        let masked = mask(text);
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        assert_eq!(masked.lines().count(), text.lines().count());
+        masked.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_srt() {
+        let text = "\
+1
+00:00:01,000 --> 00:00:02,500
+This is a <i>snetences</i>
+
+2
+00:00:03,000 --> 00:00:04,000 X1:100 X2:200
+{\\an8}Second line &amp; more
+still caption
+";
+        assert_eq!(
+            words(text),
+            vec![
+                "This",
+                "is",
+                "a",
+                "snetences",
+                "Second",
+                "line",
+                "more",
+                "still",
+                "caption"
+            ]
+        );
+        let masked = mask(text);
+        assert_eq!(masked.find("snetences"), text.find("snetences"));
+    }
+
+    #[test]
+    fn test_vtt() {
+        let text = "\
+WEBVTT - Some title
+
+NOTE a comment snetences
+over two lines
+
+STYLE
+::cue { color: yellow }
+
+intro
+00:01.000 --> 00:04.000 line:0 position:20%
+<v Roger>Hello <c.yellow>wrold</c> <00:02.000>again
+";
+        assert_eq!(words(text), vec!["Hello", "wrold", "again"]);
+    }
+}
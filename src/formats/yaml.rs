@@ -0,0 +1,306 @@
+//! YAML documents, checking only the string values and optionally the keys.
+//!
+//! This is a line-based scanner covering the common block style, flow collections,
+//! quoted and block scalars, not a complete YAML parser.
+
+use super::Masker;
+
+/// Mask everything but the strings of the YAML document.
+pub fn mask(text: &str, check_keys: bool) -> String {
+    let mut scanner = Scanner {
+        masker: Masker::new(text.len()),
+        check_keys,
+        block: None,
+    };
+
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        scanner.line(content);
+        scanner.masker.keep(&line[content.len()..]);
+    }
+
+    scanner.masker.finish()
+}
+
+struct Scanner {
+    masker: Masker,
+    check_keys: bool,
+    /// The indentation of the line starting the current block scalar, if any.
+    block: Option<usize>,
+}
+
+impl Scanner {
+    fn line(&mut self, content: &str) {
+        let rest = content.trim_start_matches(' ');
+        let indent = content.len() - rest.len();
+
+        if let Some(parent) = self.block {
+            if rest.is_empty() || indent > parent {
+                self.masker.keep(content);
+                return;
+            }
+            self.block = None;
+        }
+
+        self.masker.blank(&content[..indent]);
+        if rest.starts_with(['#', '%']) || is_document_marker(rest) {
+            self.masker.blank(rest);
+            return;
+        }
+
+        // Skip the list item markers, e.g. `- - value`
+        let mut rest = rest;
+        while rest == "-" || rest.starts_with("- ") {
+            let item = rest[1..].trim_start_matches(' ');
+            self.masker.blank(&rest[..rest.len() - item.len()]);
+            rest = item;
+        }
+
+        if let Some(colon) = key_separator(rest) {
+            self.key(&rest[..colon]);
+            let value = rest[colon + 1..].trim_start_matches(' ');
+            self.masker.blank(&rest[colon..rest.len() - value.len()]);
+            rest = value;
+        }
+
+        self.value(rest, indent);
+    }
+
+    fn key(&mut self, key: &str) {
+        if self.check_keys {
+            self.scalar(key);
+        } else {
+            self.masker.blank(key);
+        }
+    }
+
+    fn value(&mut self, value: &str, indent: usize) {
+        // Skip the anchors, aliases, and tags
+        let mut value = value;
+        while value.starts_with(['&', '*', '!']) {
+            let end = value.find(' ').unwrap_or(value.len());
+            let rest = value[end..].trim_start_matches(' ');
+            self.masker.blank(&value[..value.len() - rest.len()]);
+            value = rest;
+        }
+
+        if value.starts_with(['|', '>']) {
+            self.masker.blank(value);
+            self.block = Some(indent);
+        } else if value.starts_with(['[', '{']) {
+            self.flow(value);
+        } else {
+            self.scalar(value);
+        }
+    }
+
+    /// Mask a flow collection, e.g. `[one, two]` or `{ key: value }`.
+    fn flow(&mut self, flow: &str) {
+        let mut start = 0;
+        let mut quote = None;
+        for (index, c) in flow.char_indices() {
+            match (quote, c) {
+                (Some(open), _) if c == open => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') if flow[start..index].trim().is_empty() => quote = Some(c),
+                (None, '[' | ']' | '{' | '}' | ',') => {
+                    self.flow_item(&flow[start..index]);
+                    self.masker.blank(&flow[index..index + 1]);
+                    start = index + 1;
+                }
+                (None, '#') if flow[..index].ends_with(' ') => {
+                    self.flow_item(&flow[start..index]);
+                    self.masker.blank(&flow[index..]);
+                    return;
+                }
+                _ => {}
+            }
+        }
+        self.flow_item(&flow[start..]);
+    }
+
+    fn flow_item(&mut self, item: &str) {
+        match key_separator(item) {
+            Some(colon) => {
+                self.key(&item[..colon]);
+                self.masker.blank(":");
+                self.scalar(&item[colon + 1..]);
+            }
+            None => self.scalar(item),
+        }
+    }
+
+    /// Mask a single scalar, keeping it if it is a string.
+    fn scalar(&mut self, scalar: &str) {
+        let value = scalar.trim_start_matches(' ');
+        self.masker.blank(&scalar[..scalar.len() - value.len()]);
+
+        if let Some(quote @ ('"' | '\'')) = value.chars().next() {
+            let end = quoted_end(value, quote).unwrap_or(value.len());
+            self.masker.blank(&value[..1]);
+            let content = &value[1..end.max(1)];
+            if quote == '"' {
+                self.masker.keep_escaped(content);
+            } else {
+                self.masker.keep(content);
+            }
+            self.masker.blank(&value[end.max(1)..]);
+            return;
+        }
+
+        let end = comment_start(value).unwrap_or(value.len());
+        let plain = value[..end].trim_end();
+        if is_string(plain) {
+            self.masker.keep(plain);
+        } else {
+            self.masker.blank(plain);
+        }
+        self.masker.blank(&value[plain.len()..]);
+    }
+}
+
+fn is_document_marker(line: &str) -> bool {
+    ["---", "..."].iter().any(|marker| {
+        line.strip_prefix(marker)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    })
+}
+
+/// Find the `:` separating a key from its value, outside of any quoted key.
+fn key_separator(text: &str) -> Option<usize> {
+    let text_start = text.len() - text.trim_start_matches(' ').len();
+    let mut start = text_start;
+    if let Some(quote @ ('"' | '\'')) = text[text_start..].chars().next() {
+        start += quoted_end(&text[text_start..], quote)?;
+    } else if text[text_start..].starts_with(['[', '{']) {
+        return None;
+    }
+
+    let mut chars = text[start..].char_indices().peekable();
+    let mut previous = ' ';
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '#' if previous == ' ' => return None,
+            ':' if chars.peek().is_none_or(|(_, next)| *next == ' ') => return Some(start + index),
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// Find the index of the closing quote of a quoted scalar.
+fn quoted_end(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        if c == '\\' && quote == '"' {
+            chars.next();
+        } else if c == quote {
+            return Some(index);
+        }
+    }
+    None
+}
+
+fn comment_start(text: &str) -> Option<usize> {
+    if text.starts_with('#') {
+        return Some(0);
+    }
+    text.find(" #")
+}
+
+/// Check if a plain scalar is a string, not a number, boolean, null, or date.
+fn is_string(scalar: &str) -> bool {
+    if scalar.is_empty() || scalar.parse::<f64>().is_ok() {
+        return false;
+    }
+
+    let keyword = matches!(
+        scalar.to_ascii_lowercase().as_str(),
+        "~" | "null" | "true" | "false" | "yes" | "no" | "on" | "off" | ".inf" | "-.inf" | ".nan"
+    );
+    let date_or_number = scalar.starts_with(|c: char| c.is_ascii_digit())
+        && scalar
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || "-+:._ TZxo".contains(c));
+    !keyword && !date_or_number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str, check_keys: bool) -> Vec<String> {
+        let masked = mask(text, check_keys);
+        assert_eq!(masked.encode_utf16().count(), text.encode_utf16().count());
+        masked.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_mask() {
+        let text = "\
+# A comment snetences
+---
+title: A snetences # trailing comment
+count: 12
+enabled: true
+date: 2024-01-01
+quoted: \"Don't\\nstop\"
+tags: [mistkae, 'one two', 3]
+items:
+  - first item
+  - name: &anchor nested
+    alias: *anchor
+description: |
+  Multi line
+  block text
+other: done
+";
+        assert_eq!(
+            words(text, false),
+            vec![
+                "A",
+                "snetences",
+                "Don't",
+                "stop",
+                "mistkae",
+                "one",
+                "two",
+                "first",
+                "item",
+                "nested",
+                "Multi",
+                "line",
+                "block",
+                "text",
+                "done"
+            ]
+        );
+
+        assert_eq!(
+            words("title: A snetences\n\"quoted key\": value\n", true),
+            vec!["title", "A", "snetences", "quoted", "key", "value"]
+        );
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = "title: “A snetences”\r\n";
+        let masked = mask(text, false);
+        let offset = |text: &str| {
+            let index = text.find("snetences").unwrap();
+            text[..index].encode_utf16().count()
+        };
+        assert_eq!(offset(&masked), offset(text));
+        assert!(masked.ends_with("\r\n"));
+    }
+
+    #[test]
+    fn test_key_separator() {
+        assert_eq!(key_separator("key: value"), Some(3));
+        assert_eq!(key_separator("key:"), Some(3));
+        assert_eq!(key_separator("http://example.com"), None);
+        assert_eq!(key_separator("\"a: b\": c"), Some(6));
+        assert_eq!(key_separator("value # a: b"), None);
+    }
+}
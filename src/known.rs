@@ -0,0 +1,374 @@
+//! A filter of the words already confirmed correct by the native spell checker.
+//!
+//! Re-checking unchanged text is very common in editors, remembering the correct words
+//! in a compact bloom filter avoids calling the native spell checker again for them.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{
+    SpellCheckerImpl,
+    dictionaries::CustomDictionary,
+    tokenizer::Tokenizer,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, TokenWithSuggestions},
+};
+
+/// The number of bits in each filter, 128 KiB.
+const FILTER_BITS: usize = 1 << 20;
+/// The number of hash functions used for each word.
+const FILTER_HASHES: u64 = 7;
+/// The number of words in a filter before it is cleared.
+///
+/// This keeps the false positive rate at around 0.015%.
+const FILTER_CAPACITY: usize = 50_000;
+
+/// A bloom filter of words.
+///
+/// A word which was never inserted may be reported as present, with a very low probability.
+#[derive(Debug, Clone)]
+pub struct WordFilter {
+    bits: Vec<u64>,
+    count: usize,
+}
+
+impl WordFilter {
+    /// Create a new empty filter.
+    pub fn new() -> Self {
+        WordFilter {
+            bits: vec![0; FILTER_BITS / 64],
+            count: 0,
+        }
+    }
+
+    fn positions(word: &str) -> impl Iterator<Item = usize> {
+        let mut hasher = DefaultHasher::new();
+        word.hash(&mut hasher);
+        let hash = hasher.finish();
+        // Double hashing, derive all the positions from the two halves of a single hash
+        let (first, second) = (hash & 0xffff_ffff, (hash >> 32) | 1);
+        (0..FILTER_HASHES).map(move |index| {
+            (first.wrapping_add(index.wrapping_mul(second)) as usize) % FILTER_BITS
+        })
+    }
+
+    /// Insert a word into the filter.
+    pub fn insert(&mut self, word: &str) {
+        if self.count >= FILTER_CAPACITY {
+            // Start over instead of letting the false positive rate grow
+            self.clear();
+        }
+
+        for position in Self::positions(word) {
+            self.bits[position / 64] |= 1 << (position % 64);
+        }
+        self.count += 1;
+    }
+
+    /// Check if the word may have been inserted.
+    pub fn contains(&self, word: &str) -> bool {
+        Self::positions(word).all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    /// Remove all the words from the filter.
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+        self.count = 0;
+    }
+}
+
+impl Default for WordFilter {
+    fn default() -> Self {
+        WordFilter::new()
+    }
+}
+
+/// A spell checker remembering the words confirmed correct by the native spell checker.
+pub struct KnownWordsSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    /// The current language, the filters are kept per language.
+    language: String,
+    filters: RefCell<HashMap<String, WordFilter>>,
+    /// The tokenizer used to split the text, matching the rest of the checks.
+    tokenizer: Arc<Tokenizer>,
+}
+
+impl KnownWordsSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>, tokenizer: Arc<Tokenizer>) -> Self {
+        let language = inner.get_language().ok().flatten().unwrap_or_default();
+        KnownWordsSpellChecker {
+            inner,
+            language,
+            filters: RefCell::default(),
+            tokenizer,
+        }
+    }
+
+    fn is_known(&self, word: &str) -> bool {
+        self.filters
+            .borrow()
+            .get(&self.language)
+            .is_some_and(|filter| filter.contains(word))
+    }
+
+    fn remember<'a>(&self, words: impl Iterator<Item = &'a str>) {
+        let mut filters = self.filters.borrow_mut();
+        let filter = filters.entry(self.language.clone()).or_default();
+        for word in words {
+            filter.insert(word);
+        }
+    }
+
+    /// Check the sentence with the native spell checker and remember the correct words.
+    fn check_and_remember(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+        check: impl FnOnce() -> EjaanError<Vec<TokenWithSuggestions>>,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        if self
+            .tokenizer
+            .words(sentence)
+            .all(|word| self.is_known(word.text))
+        {
+            return Ok(Vec::new());
+        }
+
+        let tokens = check()?;
+        if options.is_full(tokens.len()) {
+            // The checking may have stopped early, the rest of the words are unknown
+            return Ok(tokens);
+        }
+        // The native spell checker splits the text on its own and skips some of it (e.g. the URLs),
+        // a word it did not flag is only remembered once it is confirmed correct on its own
+        let misspelled: HashSet<&str> = tokens.iter().map(|token| token.word()).collect();
+        let unknown: HashSet<&str> = self
+            .tokenizer
+            .words(sentence)
+            .map(|word| word.text)
+            .filter(|word| !misspelled.contains(word) && !self.is_known(word))
+            .collect();
+        let correct: Vec<&str> = unknown
+            .into_iter()
+            .filter(|word| self.inner.check_word(word).unwrap_or(false))
+            .collect();
+        self.remember(correct.into_iter());
+
+        Ok(tokens)
+    }
+
+    /// Forget all the known words, e.g. when a word may no longer be correct.
+    fn forget(&self) {
+        self.filters.borrow_mut().clear();
+    }
+}
+
+impl SpellCheckerImpl for KnownWordsSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        if self.is_known(word) {
+            return Ok(true);
+        }
+
+        let correct = self.inner.check_word(word)?;
+        if correct {
+            self.remember(std::iter::once(word));
+        }
+        Ok(correct)
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_and_remember(sentence, &CheckOptions::new(), || {
+            self.inner.check_sentences(sentence)
+        })
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_and_remember(sentence, options, || {
+            self.inner.check_sentences_with(sentence, options)
+        })
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        match String::from_utf16(sentence) {
+            Ok(text) => self.check_and_remember(&text, &CheckOptions::new(), || {
+                self.inner.check_sentences_utf16(sentence)
+            }),
+            // Unpaired surrogates cannot be split into words, always ask the native spell checker
+            Err(_) => self.inner.check_sentences_utf16(sentence),
+        }
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)?;
+        // The filter cannot remove a single word
+        self.forget();
+        Ok(())
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.add_words(words)
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.add_words_chunked(words, on_chunk)
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)?;
+        self.forget();
+        Ok(())
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        // Forget even on failure, some words may have been removed already
+        let result = self.inner.remove_words_chunked(words, on_chunk);
+        self.forget();
+        result
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        Ok(Box::new(Self::new(
+            self.inner.fork()?,
+            self.tokenizer.clone(),
+        )))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.inner.reset_session()?;
+        // The added words are no longer correct
+        self.forget();
+        Ok(())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.inner.custom_dictionaries()
+    }
+
+    fn fingerprint(&self, mut state: &mut dyn Hasher) {
+        self.inner.fingerprint(state);
+        "known-words".hash(&mut state);
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        let changed = self.inner.set_dictionary_enabled(id, enabled);
+        if changed && !enabled {
+            // The words of the dictionary may no longer be correct
+            self.forget();
+        }
+        changed
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        let changed = self.inner.set_language(language)?;
+        if changed {
+            self.language = language.to_string();
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::RwLock;
+
+    use super::*;
+    use crate::dictionaries::{DictionariesSpellChecker, WordList};
+
+    #[test]
+    fn test_remember_confirmed_words() {
+        let lists = Arc::new(RwLock::new(vec![WordList::parse("visit\nthe\n")]));
+        let inner = Box::new(crate::stubs::StubSpellChecker::new());
+        let inner = Box::new(DictionariesSpellChecker::new(inner, lists));
+        let checker = KnownWordsSpellChecker::new(inner, Arc::default());
+
+        // The native spell checker skipped the misspelled `exmaple` inside of a URL
+        let tokens = checker
+            .check_and_remember("visit the exmaple", &CheckOptions::new(), || Ok(Vec::new()))
+            .unwrap();
+        assert!(tokens.is_empty());
+        assert!(checker.is_known("visit"));
+        assert!(checker.is_known("the"));
+        assert!(!checker.is_known("exmaple"));
+    }
+
+    #[test]
+    fn test_word_filter() {
+        let mut filter = WordFilter::new();
+        filter.insert("ejaan");
+        filter.insert("sentences");
+
+        assert!(filter.contains("ejaan"));
+        assert!(filter.contains("sentences"));
+        assert!(!filter.contains("snetences"));
+
+        filter.clear();
+        assert!(!filter.contains("ejaan"));
+    }
+
+    #[test]
+    fn test_word_filter_capacity() {
+        let mut filter = WordFilter::new();
+        for index in 0..FILTER_CAPACITY {
+            filter.insert(&format!("word{}", index));
+        }
+        assert!(filter.contains("word0"));
+
+        filter.insert("overflow");
+        assert!(filter.contains("overflow"));
+        assert!(
+            !filter.contains("word0"),
+            "Expected the filter to start over when full"
+        );
+    }
+}
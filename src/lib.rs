@@ -1,16 +1,84 @@
+use std::{
+    collections::HashMap,
+    hash::Hasher,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, RwLock},
+    time::Instant,
+};
+
+use napi::{
+    Env, JsString, JsUnknown, NapiValue,
+    bindgen_prelude::{AsyncTask, Either},
+    threadsafe_function::{ErrorStrategy, ThreadsafeFunction},
+};
 use napi_derive::*;
 
-use crate::utils::{EjaanError, TokenWithSuggestions};
+use crate::{
+    analysis::UnknownWords,
+    cache::CacheStats,
+    decorations::PositionMap,
+    dictionaries::CustomDictionary,
+    formats::placeholder::Placeholders,
+    queue::{CallQueue, QueuePermit},
+    tasks::{CheckTask, DownloadTask, FailOn, LintTask, Threshold, WordsOperation, WordsTask},
+    timings::{Phase, Recorder},
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError},
+};
+
+pub use crate::utils::{
+    Diagnostic, DiagnosticCategory, ErrorKind, Severity, Token, TokenWithSuggestions,
+};
 
+mod analysis;
 #[cfg(target_os = "macos")]
 mod apple;
+mod buildinfo;
+mod cache;
+mod casing;
+mod common;
+mod compounds;
+mod correction;
+mod decorations;
+mod dictionaries;
+mod diskcache;
+mod distance;
+mod download;
+mod fallback;
+mod formats;
+mod known;
+mod locales;
+mod ndjson;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod overlay;
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+mod owner;
+mod proposals;
+mod queue;
+mod selftest;
+mod stemming;
 mod stubs;
+mod tasks;
+mod timings;
+mod tokenizer;
 mod utils;
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+mod wide;
 #[cfg(target_os = "windows")]
 mod winrt;
 
+/// The number of words processed at once in batch operations.
+const WORDS_CHUNK_SIZE: usize = 1000;
+
+/// The thread owning all the Windows spell checkers, with COM initialized on it.
+#[cfg(target_os = "windows")]
+static NATIVE_OWNER: owner::NativeOwner =
+    owner::NativeOwner::new(|| Ok(Box::new(winrt::init_thread()?) as owner::ThreadGuard));
+
 /// The main trait for spell checking functionality.
-pub trait SpellCheckerImpl {
+///
+/// The implementation must be movable to another thread, access to it is always serialized.
+pub trait SpellCheckerImpl: Send {
     /// Check if a word is spelled correctly.
     ///
     /// # Arguments
@@ -27,6 +95,35 @@ pub trait SpellCheckerImpl {
     /// # Returns
     /// A list of index positions where the words are misspelled.
     fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>>;
+    /// Check if a sentence is spelled correctly, with the given options.
+    ///
+    /// # Arguments
+    /// * `sentence` - The sentence to check.
+    /// * `options` - The options for checking, e.g. the maximum number of results.
+    ///
+    /// # Returns
+    /// A list of index positions where the words are misspelled.
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let mut tokens = self.check_sentences(sentence)?;
+        if let Some(max_results) = options.max_results() {
+            tokens.truncate(max_results);
+        }
+        Ok(tokens)
+    }
+    /// Check if a sentence is spelled correctly, using the UTF-16 code units directly.
+    ///
+    /// # Arguments
+    /// * `sentence` - The UTF-16 code units of the sentence to check.
+    ///
+    /// # Returns
+    /// A list of index positions where the words are misspelled.
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_sentences(&String::from_utf16_lossy(sentence))
+    }
 
     /// Add a word to the spell checker.
     fn add_word(&self, word: &str) -> EjaanError<()>;
@@ -40,8 +137,24 @@ pub trait SpellCheckerImpl {
     /// # Arguments
     /// * `words` - A list of words to add.
     fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
-        for word in words {
-            self.add_word(&word)?;
+        self.add_words_chunked(&words, &mut |_| {})
+    }
+
+    /// Batch add words to the spell checker in chunks.
+    ///
+    /// # Arguments
+    /// * `words` - A list of words to add.
+    /// * `on_chunk` - Called with the number of processed words after each chunk.
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        for chunk in words.chunks(WORDS_CHUNK_SIZE) {
+            for word in chunk {
+                self.add_word(word)?;
+            }
+            on_chunk(chunk.len());
         }
         Ok(())
     }
@@ -51,12 +164,94 @@ pub trait SpellCheckerImpl {
     /// # Arguments
     /// * `words` - A list of words to remove.
     fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
-        for word in words {
-            self.remove_word(&word)?;
+        self.remove_words_chunked(&words, &mut |_| {})
+    }
+
+    /// Batch remove words from the spell checker in chunks.
+    ///
+    /// # Arguments
+    /// * `words` - A list of words to remove.
+    /// * `on_chunk` - Called with the number of processed words after each chunk.
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        for chunk in words.chunks(WORDS_CHUNK_SIZE) {
+            for word in chunk {
+                self.remove_word(word)?;
+            }
+            on_chunk(chunk.len());
         }
         Ok(())
     }
 
+    /// Get the raw pointer of the underlying native spell checker object, if any.
+    ///
+    /// The pointer is only valid as long as the spell checker is alive and unchanged.
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void>;
+
+    /// Get the native spell checker below the layers of this library, e.g. the word lists
+    /// and the caches, to tell its results apart from the ones of this library.
+    fn native(&self) -> &dyn SpellCheckerImpl;
+
+    /// Create a new spell checker sharing the backend resources of this one.
+    ///
+    /// The new spell checker has its own language and session state.
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>>;
+
+    /// Clear all the session state of the spell checker.
+    ///
+    /// This includes the ignored/added words and any per-document state.
+    fn reset_session(&mut self) -> EjaanError<()>;
+
+    /// Get information about where the added words are being stored.
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo>;
+
+    /// Get the optional features supported by the backend, e.g. removing words.
+    fn capabilities(&self) -> EjaanError<Capabilities>;
+
+    /// Get the words flagged often in this session with their counts, the most frequent first.
+    ///
+    /// This is empty unless the spell checker is created with the `proposeAdditions` option.
+    fn proposed_additions(&self) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+    /// Stop proposing the word for the rest of the session.
+    fn dismiss_proposal(&self, _word: &str) {}
+
+    /// Remove all the cached paragraph results.
+    fn clear_cache(&self) {}
+    /// Get the statistics of the paragraph cache.
+    fn cache_stats(&self) -> CacheStats {
+        CacheStats::default()
+    }
+
+    /// Get the custom dictionaries accepted on top of the native spell checker.
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        Vec::new()
+    }
+    /// Enable or disable the custom dictionaries with this id for this spell checker.
+    ///
+    /// Returns `false` if there is no such dictionary.
+    fn set_dictionary_enabled(&mut self, _id: &str, _enabled: bool) -> bool {
+        false
+    }
+    /// Find the words of the custom dictionaries within `max_distance` edits of the word,
+    /// with their distance. The same word may be returned more than once.
+    fn similar_words(&self, _word: &str, _max_distance: usize) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+    /// Get the stems of the word from the custom dictionaries of the language,
+    /// empty if no dictionary has the word.
+    fn dictionary_stems(&self, _word: &str, _language: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Hash the configuration which changes the results, e.g. the custom dictionaries,
+    /// the added words, and the optional layers, for the persistent cache of `checkFiles()`.
+    fn fingerprint(&self, _state: &mut dyn Hasher) {}
+
     /// Get a list of available languages for the spell checker.
     fn get_available_languages(&self) -> EjaanError<Vec<String>>;
 
@@ -75,7 +270,253 @@ pub trait SpellCheckerImpl {
 /// @class SpellChecker
 #[napi]
 pub struct SpellChecker {
-    inner: Box<dyn SpellCheckerImpl>,
+    inner: SharedSpellChecker,
+    allow_native_handle: bool,
+    tokenizer: Arc<tokenizer::Tokenizer>,
+    dictionaries: dictionaries::SharedWordLists,
+    hyphenation: Arc<Vec<dictionaries::hyphenation::Patterns>>,
+}
+
+/// The spell checker implementation shared with the background tasks.
+pub(crate) type SharedSpellChecker = Arc<Mutex<Box<dyn SpellCheckerImpl>>>;
+
+/// The queue for all the calls into the native spell checkers.
+///
+/// By default, only a single call is running at once across all instances.
+static CALL_QUEUE: CallQueue = CallQueue::new(1);
+
+/// A locked spell checker implementation, holding a slot in the call queue.
+pub(crate) struct BackendGuard<'a> {
+    // Release the lock before giving up our slot in the queue
+    backend: MutexGuard<'a, Box<dyn SpellCheckerImpl>>,
+    _permit: QueuePermit<'static>,
+}
+
+impl Deref for BackendGuard<'_> {
+    type Target = Box<dyn SpellCheckerImpl>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.backend
+    }
+}
+
+impl DerefMut for BackendGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.backend
+    }
+}
+
+/// Wait for our turn in the call queue, for a spell checker implementation owned by the caller.
+pub(crate) fn call_permit() -> EjaanError<QueuePermit<'static>> {
+    CALL_QUEUE.acquire()
+}
+
+/// Wait for our turn in the call queue and lock the shared spell checker implementation.
+///
+/// This must never be nested, otherwise it could deadlock with a concurrency of 1.
+pub(crate) fn lock_backend(inner: &SharedSpellChecker) -> EjaanError<BackendGuard<'_>> {
+    let permit = call_permit()?;
+    let backend = inner
+        .lock()
+        .map_err(|_| utils::Error::new("Spell checker is unusable after a previous panic"))?;
+
+    Ok(BackendGuard {
+        backend,
+        _permit: permit,
+    })
+}
+
+/// Options for creating the spell checker.
+///
+/// @typedef {Object} SpellCheckerOptions
+/// @property {boolean} [sandboxed] Never write added words to the system dictionary
+/// @property {boolean} [allowNativeHandle] Allow access to the native spell checker object
+/// @property {boolean} [commonWords] Check the most common words without the native spell checker
+/// @property {boolean} [knownWords] Remember the correct words to skip the native spell checker
+/// @property {WordBreakOptions} [wordBreak] Customize which characters are part of a word
+/// @property {number} [proposeAdditions] Propose adding the words flagged more than this many times
+/// @property {boolean} [userDictionaries] Accept the words of the Office and LibreOffice personal dictionaries
+/// @property {string[]} [dictionaries] Accept the words of these word lists
+/// @property {boolean} [compounds] Accept the compounds of valid words in German, Dutch, and the Nordic languages
+/// @property {string[]} [hyphenation] Load the hyphenation patterns of these files for `hyphenate()`
+/// @property {string[]} [languages] Check with the first installed language, falling back to the others
+/// @property {boolean} [dictionaryFallback] Check with the loaded dictionaries only when the native spell checker is unavailable
+#[napi(object)]
+#[derive(Default)]
+pub struct SpellCheckerOptions {
+    /// Sandbox-safe mode, added words will never be written to the system dictionary.
+    ///
+    /// On macOS, words are ignored for this instance only instead of being learned
+    /// into the user dictionary, which is required for sandboxed/App Store apps.
+    ///
+    /// On Windows, this has no effect since added words are always ignored per instance.
+    pub sandboxed: Option<bool>,
+    /// Allow access to the underlying native spell checker object with `unsafeNativeHandle()`.
+    pub allow_native_handle: Option<bool>,
+    /// Check the most common words of the language with a bundled list first,
+    /// only the other words are checked by the native spell checker.
+    ///
+    /// This speeds up as-you-type checking, the native spell checker is skipped entirely when every
+    /// word is common. The lists of `en` and `id` are bundled, the words are only accepted
+    /// as listed, capitalized, or in all caps.
+    pub common_words: Option<bool>,
+    /// Remember the words confirmed correct by the native spell checker in this session,
+    /// so re-checking unchanged text skips the native spell checker.
+    ///
+    /// The words are kept in a bloom filter per language, a misspelled word
+    /// may be reported as correct with a very low probability (around 0.015%).
+    /// Each new word is confirmed on its own first, so the first check of a text is slower.
+    pub known_words: Option<bool>,
+    /// Customize which characters are part of a word.
+    ///
+    /// When set, the text is split into words by this library before being checked,
+    /// instead of relying on the word breaking of the native spell checker.
+    pub word_break: Option<JsWordBreakOptions>,
+    /// Propose adding the words flagged more than this many times in the session
+    /// to the dictionary, see `proposedAdditions()`.
+    ///
+    /// Each occurrence in the checked text is counted once, re-checking an unchanged paragraph
+    /// does not count its words again. The words of `checkWord()` are not counted.
+    ///
+    /// The words are not counted unless this is set.
+    pub propose_additions: Option<u32>,
+    /// Accept the words of the existing personal dictionaries of other applications.
+    ///
+    /// On Windows, the Office custom dictionaries (`%APPDATA%\Microsoft\UProof\*.dic`) and the
+    /// LibreOffice user wordbooks are loaded, so the words added years ago in Word are still accepted.
+    /// The dictionaries are only read, the words added with this library are not written to them.
+    pub user_dictionaries: Option<bool>,
+    /// Accept the words of these word lists, e.g. an exported `CUSTOM.DIC` or a Hunspell dictionary.
+    ///
+    /// The files have one word per line. A Hunspell `.dic` next to its `.aff` file is expanded with the
+    /// prefixes and suffixes of the affix rules, and a LibreOffice `.oxt` extension loads the Hunspell
+    /// dictionaries it declares, each one only for the languages of the extension.
+    ///
+    /// A capitalized or uppercase word matches its lowercase entry with the case rules of the current
+    /// language, e.g. the Turkish `Isparta` matches `ısparta` and the German `STRASSE` matches `straße`.
+    pub dictionaries: Option<Vec<String>>,
+    /// Accept a flagged word when it splits into valid words, e.g. the German `Haustür`
+    /// or the Dutch `fietsenwinkel`, with the linking elements (`-s-`, `-en-`) between them.
+    ///
+    /// This applies to German, Dutch, Danish, Norwegian, and Swedish. The native spell checkers
+    /// only know some of the compounds, and the words of the `dictionaries` option none of them.
+    /// Each flagged word costs a few more calls to the native spell checker.
+    pub compounds: Option<bool>,
+    /// Load the hyphenation patterns of these files for `hyphenate()`.
+    ///
+    /// The files are the Hunspell and LibreOffice patterns, e.g. `hyph_de_DE.dic` (for the language
+    /// in its name), or a LibreOffice `.oxt` extension with the patterns of its languages.
+    pub hyphenation: Option<Vec<String>>,
+    /// Check with the first installed of these languages, e.g. `["en-US", "id-ID"]`,
+    /// and fall back to the other installed ones.
+    ///
+    /// A word is only flagged when none of the languages accepts it, the suggestions are from the
+    /// first language. A language matches an installed one of another region, e.g. `en-AU` uses
+    /// `en-GB` when only that one is installed. The default language is kept when none is installed.
+    pub languages: Option<Vec<String>>,
+    /// Check with the loaded dictionaries only, instead of throwing `BackendUnavailable`,
+    /// when the native spell checker is unavailable, e.g. on Windows Server Core.
+    ///
+    /// Every word is flagged unless it is in the `dictionaries`, e.g. a Hunspell dictionary,
+    /// or added with `addWord()`. The flagged words have no suggestions, see `lookupSimilar()`.
+    pub dictionary_fallback: Option<bool>,
+}
+
+/// Options for splitting the text into words.
+///
+/// @typedef {Object} WordBreakOptions
+/// @property {string} [wordCharacters] Extra characters always part of a word
+/// @property {string} [joinCharacters] Characters part of a word only inside of it
+/// @property {string} [breakCharacters] Characters always breaking a word
+#[napi(object, js_name = "WordBreakOptions")]
+#[derive(Default)]
+pub struct JsWordBreakOptions {
+    /// Extra characters which are always part of a word, e.g. `’` to treat it as a letter.
+    pub word_characters: Option<String>,
+    /// Characters which are part of a word only between two word characters,
+    /// e.g. `_` or `.` to keep identifiers together.
+    ///
+    /// This replaces the default, which is the apostrophes `'` and `’`.
+    pub join_characters: Option<String>,
+    /// Characters which always break a word, even letters.
+    pub break_characters: Option<String>,
+}
+
+/// Options for checking the sentences.
+///
+/// @typedef {Object} CheckOptions
+/// @property {number} [maxResults] Stop checking after this many misspelled words
+/// @property {string} [format] The format of the text: `plain`, `json`, `yaml`, `gettext`, `srt`, `vtt`, `ass`, `chat`, or `commit`
+/// @property {boolean} [checkKeys] Also check the keys of JSON and YAML documents
+/// @property {boolean} [skipEmoji] Skip the emoji shortcodes and sequences, enabled by default
+/// @property {boolean} [skipPlaceholders] Skip the common template placeholders, e.g. `{name}` or `%s`
+/// @property {PlaceholderDelimiters[]} [placeholders] Extra placeholder syntaxes to skip
+/// @property {boolean} [cache] Reuse the results of the unchanged paragraphs
+/// @property {boolean} [debug] Return the timings of the check with its results
+#[napi(object, js_name = "CheckOptions")]
+#[derive(Default)]
+pub struct JsCheckOptions {
+    /// Stop checking once this many misspelled words are found.
+    ///
+    /// This is useful for "document has 100+ issues" summaries,
+    /// especially on Windows where each misspelled word costs an extra call for the suggestions.
+    pub max_results: Option<u32>,
+    /// The format of the text, only the prose inside of it is checked.
+    ///
+    /// - `plain`: Plain text, everything is checked. This is the default.
+    /// - `json`: A JSON document, only the string values are checked.
+    /// - `yaml`: A YAML document, only the string values are checked.
+    /// - `gettext`: A gettext catalog (`.po`/`.pot`), only the `msgstr` translations are checked,
+    ///   skipping the format placeholders. The catalog is checked in the language of its header.
+    /// - `srt`/`vtt`: SubRip or WebVTT subtitles, only the caption text is checked,
+    ///   skipping the sequence numbers, timestamps, cue settings, and inline tags.
+    /// - `ass`/`ssa`: Advanced SubStation Alpha subtitles, only the text of the `Dialogue` lines is checked,
+    ///   without the override tags (e.g. `{\an8}` or karaoke `{\k20}`) and drawing commands.
+    /// - `chat`: Chat messages with BBCode or Discord-flavored markdown, skipping the code blocks,
+    ///   inline code, BBCode tags (e.g. `[spoiler]`), custom emoji (`<:name:id>`), and mentions.
+    /// - `commit`: A git commit message, skipping the comments, trailers (e.g. `Signed-off-by:`),
+    ///   issue references (e.g. `#123`), and inline code spans.
+    ///
+    /// The offsets are still based on the original text.
+    pub format: Option<String>,
+    /// Also check the keys of JSON and YAML documents.
+    pub check_keys: Option<bool>,
+    /// Skip the emoji shortcodes (e.g. `:thumbsup:`) and the raw emoji sequences, in every format.
+    ///
+    /// This is enabled by default.
+    pub skip_emoji: Option<bool>,
+    /// Skip the common template placeholders, for i18n messages or email templates:
+    /// `{name}`, `{{var}}`, `${env}`, and the printf-style `%s` or `%(key)s`.
+    pub skip_placeholders: Option<bool>,
+    /// Extra placeholder syntaxes to skip, e.g. `{ open: '[[', close: ']]' }`.
+    ///
+    /// These are skipped even without `skipPlaceholders`.
+    pub placeholders: Option<Vec<JsPlaceholderDelimiters>>,
+    /// Reuse the results of the unchanged paragraphs from the previous checks,
+    /// so re-checking an edited document only checks the changed paragraphs.
+    ///
+    /// The paragraphs are separated by blank lines. This is enabled by default
+    /// for `checkParagraphs()` and `checkFile()`, and disabled for the other checks.
+    pub cache: Option<bool>,
+    /// Record how long each phase of the check took, the check then returns
+    /// `{ results, timings }` instead of the results alone, e.g. `TimedDiagnostics`.
+    /// `checkFiles()` returns the timings in the `timings` of its report instead.
+    ///
+    /// This adds a little overhead to every word, keep it for diagnosing slow checks.
+    pub debug: Option<bool>,
+}
+
+/// The delimiters of a template placeholder syntax.
+///
+/// @typedef {Object} PlaceholderDelimiters
+/// @property {string} open The opening delimiter, e.g. `[[`
+/// @property {string} close The closing delimiter, e.g. `]]`
+#[napi(object, js_name = "PlaceholderDelimiters")]
+pub struct JsPlaceholderDelimiters {
+    /// The opening delimiter, e.g. `[[`.
+    pub open: String,
+    /// The closing delimiter, e.g. `]]`.
+    pub close: String,
 }
 
 /// A suggestion for a misspelled word.
@@ -99,6 +540,589 @@ pub struct JsSuggestion {
     pub suggestions: Vec<String>,
 }
 
+/// A word in the text.
+///
+/// @typedef {Object} Token
+/// @property {number} start The start index of the word
+/// @property {number} end The end index of the word
+/// @property {string} word The word
+#[napi(object, js_name = "Token")]
+pub struct JsToken {
+    /// The start index of the word in the original text.
+    pub start: u32,
+    /// The end index of the word in the original text.
+    ///
+    /// This is inclusive, meaning the end index is the last character of the word.
+    pub end: u32,
+    /// The word.
+    pub word: String,
+}
+
+/// A word from the caller's own tokenizer.
+///
+/// @typedef {Object} TextToken
+/// @property {string} word The word to check
+/// @property {number} start The start index of the word in the original text
+#[napi(object, js_name = "TextToken")]
+pub struct JsTextToken {
+    /// The word to check.
+    pub word: String,
+    /// The start index of the word in the original text, in UTF-16 code units.
+    pub start: u32,
+}
+
+/// An issue found in the text.
+///
+/// @typedef {Object} Diagnostic
+/// @property {number} start The start index of the string
+/// @property {number} end The end index of the string
+/// @property {string} word The text with the issue
+/// @property {string[]} suggestions The list of suggested replacements
+/// @property {string} category The kind of check: `spelling`, `grammar`, `style`, or `custom-rule`
+/// @property {string} severity The severity: `error`, `warning`, `info`, or `hint`
+/// @property {string} [language] The language which produced the issue
+/// @property {string[]} rejectedBy The languages which rejected the word
+#[napi(object, js_name = "Diagnostic")]
+pub struct JsDiagnostic {
+    /// The start index of the text in the original text.
+    pub start: u32,
+    /// The end index of the text in the original text.
+    ///
+    /// This is inclusive, meaning the end index is the last character of the text.
+    pub end: u32,
+    /// The text with the issue, e.g. the misspelled word.
+    pub word: String,
+    /// A list of suggested replacements for the text.
+    pub suggestions: Vec<String>,
+    /// The kind of check which found the issue.
+    ///
+    /// - `spelling`: A misspelled word.
+    /// - `grammar`: A grammar issue.
+    /// - `style`: A style issue.
+    /// - `custom-rule`: A custom rule defined by the user.
+    pub category: String,
+    /// How important the issue is: `error`, `warning`, `info`, or `hint`.
+    ///
+    /// Misspelled words are reported as `warning`.
+    pub severity: String,
+    /// The language or dictionary which produced the issue, if known.
+    pub language: Option<String>,
+    /// All the languages which rejected the word, to help adjusting the language configuration.
+    ///
+    /// This includes the fallback languages of the `languages` option.
+    pub rejected_by: Vec<String>,
+}
+
+/// A block of text from a rich-text editor document, e.g. a ProseMirror or TipTap paragraph.
+///
+/// @typedef {Object} TextBlock
+/// @property {string} text The text of the block
+/// @property {number} pos The document position where the text starts
+/// @property {TextSegment[]} [segments] Where the text continues after the inline nodes
+#[napi(object, js_name = "TextBlock")]
+pub struct JsTextBlock {
+    /// The text of the block, e.g. from `node.textContent`.
+    pub text: String,
+    /// The document position where the text starts, e.g. `pos + 1` from `doc.descendants()`.
+    pub pos: u32,
+    /// Where the text continues in the document, when the block has inline nodes or
+    /// a flat text of the whole document is used.
+    ///
+    /// Each segment maps an offset in the text to a document position,
+    /// the offsets between two segments are counted from the previous one.
+    pub segments: Option<Vec<JsTextSegment>>,
+}
+
+/// The start of a text segment in the document.
+///
+/// @typedef {Object} TextSegment
+/// @property {number} offset The offset in the text of the block
+/// @property {number} pos The document position of the offset
+#[napi(object, js_name = "TextSegment")]
+pub struct JsTextSegment {
+    /// The offset in the text of the block, in UTF-16 code units.
+    pub offset: u32,
+    /// The document position of the offset.
+    pub pos: u32,
+}
+
+/// An issue found in a block, with the document positions for an editor decoration.
+///
+/// @typedef {Object} Decoration
+/// @property {number} from The document position where the issue starts
+/// @property {number} to The document position where the issue ends, exclusive
+/// @property {number} block The index of the block with the issue
+/// @property {string} word The text with the issue
+/// @property {string[]} suggestions The list of suggested replacements
+/// @property {string} category The kind of check: `spelling`, `grammar`, `style`, or `custom-rule`
+/// @property {string} severity The severity: `error`, `warning`, `info`, or `hint`
+#[napi(object, js_name = "Decoration")]
+pub struct JsDecoration {
+    /// The document position where the issue starts.
+    pub from: u32,
+    /// The document position where the issue ends.
+    ///
+    /// This is exclusive, the same as `Decoration.inline(from, to)` in ProseMirror.
+    pub to: u32,
+    /// The index of the block with the issue.
+    pub block: u32,
+    /// The text with the issue, e.g. the misspelled word.
+    pub word: String,
+    /// A list of suggested replacements for the text.
+    pub suggestions: Vec<String>,
+    /// The kind of check which found the issue, the same as `Diagnostic.category`.
+    pub category: String,
+    /// How important the issue is, the same as `Diagnostic.severity`.
+    pub severity: String,
+}
+
+/// A misspelled word found across the documents.
+///
+/// @typedef {Object} UnknownWord
+/// @property {string} word The misspelled word
+/// @property {number} count How many times the word was found
+/// @property {number} documents How many documents have the word
+/// @property {string[]} contexts Some of the lines where the word was found
+#[napi(object, js_name = "UnknownWord")]
+pub struct JsUnknownWord {
+    /// The misspelled word.
+    pub word: String,
+    /// How many times the word was found in all the documents.
+    pub count: u32,
+    /// How many documents have the word.
+    pub documents: u32,
+    /// Up to 3 sample lines where the word was found, trimmed around the word.
+    pub contexts: Vec<String>,
+}
+
+/// A word proposed to be added to the dictionary.
+///
+/// @typedef {Object} ProposedAddition
+/// @property {string} word The flagged word
+/// @property {number} count How many distinct occurrences of the word were flagged
+#[napi(object, js_name = "ProposedAddition")]
+pub struct JsProposedAddition {
+    /// The word flagged as misspelled.
+    pub word: String,
+    /// How many distinct occurrences of the word were flagged in the session.
+    pub count: u32,
+}
+
+/// Options for checking many files at once, like a linter.
+///
+/// @typedef {Object} LintOptions
+/// @property {CheckOptions} [check] The options for checking each file
+/// @property {string} [cacheLocation] The file of the persistent cache, disabled by default
+/// @property {number} [concurrency] The number of files checked at once, 1 by default
+/// @property {number} [maxErrors] Stop once more than this number of issues are found
+/// @property {string} [failOn] Which issues are counted towards `maxErrors`: `any` or `new`
+#[napi(object, js_name = "LintOptions")]
+#[derive(Default)]
+pub struct JsLintOptions {
+    /// The options for checking each file.
+    ///
+    /// The format of each file is guessed from its extension unless `format` is set.
+    pub check: Option<JsCheckOptions>,
+    /// The file of the persistent cache, e.g. `.ejaancache`, like ESLint's `--cache-location`.
+    ///
+    /// Each file is keyed by a hash of its content, the language, the check options, and
+    /// the configuration of the spell checker, so repeated runs only check the modified files.
+    /// The configuration includes the custom dictionaries and whether they are enabled,
+    /// the words added or removed in this session, and the options such as `compounds`.
+    pub cache_location: Option<String>,
+    /// The number of files checked at once, 1 by default.
+    ///
+    /// Each worker checks the files with its own spell checker forked from this one,
+    /// so the words added in this session are not known by the workers.
+    /// The workers do not go through the call queue of `setMaxConcurrency()`, so the other calls
+    /// are not blocked while checking. On macOS, the calls into the single native spell checker
+    /// still run one at a time.
+    pub concurrency: Option<u32>,
+    /// Stop checking the remaining files once more than this number of issues are found.
+    ///
+    /// The report tells whether the threshold was exceeded with `thresholdExceeded`,
+    /// e.g. `0` to fail a quality gate on the first issue. There is no threshold by default.
+    pub max_errors: Option<u32>,
+    /// Which issues are counted towards `maxErrors`, `any` by default.
+    ///
+    /// - `any`: Every issue.
+    /// - `new`: Only the issues not reported in the previous run, from the persistent cache.
+    ///   Without `cacheLocation`, every issue is new.
+    pub fail_on: Option<String>,
+}
+
+/// Options for downloading a dictionary.
+///
+/// @typedef {Object} DownloadOptions
+/// @property {string} sha256 The expected SHA-256 checksum of the file, in hexadecimal
+/// @property {string} [language] The language of the words, instead of the one of the dictionary
+/// @property {string} [cacheLocation] The directory of the downloaded dictionaries
+#[napi(object, js_name = "DownloadOptions")]
+pub struct JsDownloadOptions {
+    /// The expected SHA-256 checksum of the file, in hexadecimal.
+    ///
+    /// The dictionary is rejected when the downloaded file does not match.
+    pub sha256: String,
+    /// The language of the words, e.g. `id-ID`.
+    ///
+    /// By default, the words of a LibreOffice extension are used for the languages it declares,
+    /// and the words of the other dictionaries for every language.
+    pub language: Option<String>,
+    /// The directory of the downloaded dictionaries.
+    ///
+    /// By default, `%LOCALAPPDATA%\ejaan\dictionaries` on Windows,
+    /// `~/Library/Caches/ejaan/dictionaries` on macOS, and `~/.cache/ejaan/dictionaries` elsewhere.
+    pub cache_location: Option<String>,
+}
+
+/// The results of a checked file.
+///
+/// @typedef {Object} FileResult
+/// @property {string} path The path of the file
+/// @property {Diagnostic[]} diagnostics The list of issues found in the file
+/// @property {boolean} cached Are the results from the persistent cache
+/// @property {string} [error] Why the file could not be checked
+#[napi(object, js_name = "FileResult")]
+pub struct JsFileResult {
+    /// The path of the file, as given.
+    pub path: String,
+    /// The list of issues found in the file.
+    pub diagnostics: Vec<JsDiagnostic>,
+    /// Are the results from the persistent cache, without checking the file again.
+    pub cached: bool,
+    /// Why the file could not be checked, e.g. it could not be read as UTF-8.
+    pub error: Option<String>,
+}
+
+/// The results of checking many files.
+///
+/// @typedef {Object} LintReport
+/// @property {FileResult[]} files The results of each file
+/// @property {number} errorCount The number of issues found in all the files
+/// @property {boolean} thresholdExceeded Did the issues exceed the `maxErrors` option
+/// @property {Timings} [timings] How long each phase took, with the `debug` check option
+#[napi(object, js_name = "LintReport")]
+pub struct JsLintReport {
+    /// The results of each file, in the same order as the paths.
+    ///
+    /// The files after the `maxErrors` threshold was exceeded are not checked, nor included.
+    pub files: Vec<JsFileResult>,
+    /// The number of issues found in all the files.
+    pub error_count: u32,
+    /// Did the issues counted by `failOn` exceed the `maxErrors` option.
+    pub threshold_exceeded: bool,
+    /// How long each phase of checking all the files took, with the `debug` check option.
+    ///
+    /// With a `concurrency` above 1, the phases are summed over the workers
+    /// and may add up to more than the total.
+    pub timings: Option<JsTimings>,
+}
+
+/// The statistics of the paragraph cache.
+///
+/// @typedef {Object} CacheStats
+/// @property {number} entries The number of cached paragraphs
+/// @property {number} hits The number of paragraphs found in the cache
+/// @property {number} misses The number of paragraphs checked by the native spell checker
+#[napi(object, js_name = "CacheStats")]
+pub struct JsCacheStats {
+    /// The number of cached paragraphs.
+    pub entries: u32,
+    /// The number of paragraphs found in the cache since the spell checker was created.
+    pub hits: u32,
+    /// The number of paragraphs checked by the native spell checker with the cache enabled.
+    pub misses: u32,
+}
+
+/// How long each phase of a check took, recorded with the `debug` check option.
+///
+/// @typedef {Object} Timings
+/// @property {number} tokenizationMs Masking the format and splitting the words
+/// @property {number} checkMs Checking the words with the native spell checker and the word lists
+/// @property {number} suggestionsMs Getting the suggestions of the misspelled words
+/// @property {number} marshallingMs Converting the results for JavaScript
+/// @property {number} totalMs The whole check, in milliseconds
+#[napi(object, js_name = "Timings")]
+pub struct JsTimings {
+    /// Masking the format of the text and splitting the words with the `wordBreak` option.
+    pub tokenization_ms: f64,
+    /// Checking the words, mostly the native spell checker. This is everything else of the check.
+    pub check_ms: f64,
+    /// Getting the suggestions of the misspelled words from the native spell checker.
+    pub suggestions_ms: f64,
+    /// Converting the results for JavaScript.
+    pub marshalling_ms: f64,
+    /// The whole check, in milliseconds.
+    pub total_ms: f64,
+}
+
+/// The issues found by a check with the `debug` option, with the timings of the check.
+///
+/// @typedef {Object} TimedDiagnostics
+/// @property {Diagnostic[]} results The list of issues found
+/// @property {Timings} timings How long each phase of the check took
+#[napi(object, js_name = "TimedDiagnostics")]
+pub struct JsTimedDiagnostics {
+    pub results: Vec<JsDiagnostic>,
+    pub timings: JsTimings,
+}
+
+/// The decorations found by `checkBlocks()` with the `debug` option, with the timings of the check.
+///
+/// @typedef {Object} TimedDecorations
+/// @property {Decoration[]} results The list of issues found
+/// @property {Timings} timings How long each phase of the check took
+#[napi(object, js_name = "TimedDecorations")]
+pub struct JsTimedDecorations {
+    pub results: Vec<JsDecoration>,
+    pub timings: JsTimings,
+}
+
+/// The suggestions found by a check with the `debug` option, with the timings of the check.
+///
+/// @typedef {Object} TimedSuggestions
+/// @property {Suggestion[]} results The list of suggested spellings
+/// @property {Timings} timings How long each phase of the check took
+#[napi(object, js_name = "TimedSuggestions")]
+pub struct JsTimedSuggestions {
+    pub results: Vec<JsSuggestion>,
+    pub timings: JsTimings,
+}
+
+/// The results of a check, returned with their timings with the `debug` check option.
+pub(crate) trait Timed {
+    type Results;
+
+    fn new(results: Self::Results, timings: JsTimings) -> Self;
+
+    /// Return the results alone, or with the timings of the check when recorded.
+    fn wrap(
+        results: Self::Results,
+        timings: Option<timings::Breakdown>,
+    ) -> Either<Self::Results, Self>
+    where
+        Self: Sized,
+    {
+        match timings {
+            Some(timings) => Either::B(Self::new(results, timings.into())),
+            None => Either::A(results),
+        }
+    }
+}
+
+impl Timed for JsTimedDiagnostics {
+    type Results = Vec<JsDiagnostic>;
+
+    fn new(results: Self::Results, timings: JsTimings) -> Self {
+        JsTimedDiagnostics { results, timings }
+    }
+}
+
+impl Timed for JsTimedDecorations {
+    type Results = Vec<JsDecoration>;
+
+    fn new(results: Self::Results, timings: JsTimings) -> Self {
+        JsTimedDecorations { results, timings }
+    }
+}
+
+impl Timed for JsTimedSuggestions {
+    type Results = Vec<JsSuggestion>;
+
+    fn new(results: Self::Results, timings: JsTimings) -> Self {
+        JsTimedSuggestions { results, timings }
+    }
+}
+
+/// Information about where the custom words are being stored.
+///
+/// @typedef {Object} DictionaryInfo
+/// @property {string} backend The native spell checker backend
+/// @property {string} storage The storage kind: `system`, `session`, or `overlay`
+/// @property {string} [location] The location of the storage, if any
+/// @property {boolean} persistent Are the words kept after the process exits
+#[napi(object, js_name = "DictionaryInfo")]
+pub struct JsDictionaryInfo {
+    /// The native spell checker backend in use.
+    pub backend: String,
+    /// Where the added words are stored.
+    ///
+    /// - `system`: The system-level user dictionary, shared with other applications.
+    /// - `session`: The session ignore list of this spell checker instance.
+    /// - `overlay`: The overlay dictionary maintained by this library.
+    pub storage: String,
+    /// The location of the storage on disk, if any.
+    pub location: Option<String>,
+    /// Are the added words kept after the process exits.
+    pub persistent: bool,
+}
+
+/// The optional features supported by the native spell checker.
+///
+/// @typedef {Object} Capabilities
+/// @property {boolean} removeWords Can the added words be removed again
+/// @property {boolean} languageDetection Is the language identified automatically when not set
+#[napi(object, js_name = "Capabilities")]
+pub struct JsCapabilities {
+    /// Can the added words be removed again with `removeWord()`.
+    ///
+    /// This is `false` on Windows when the provider does not implement `ISpellChecker2`.
+    pub remove_words: bool,
+    /// Is the language of the text identified automatically when no language is set.
+    pub language_detection: bool,
+}
+
+/// A single check of the self-test.
+///
+/// @typedef {Object} SelfTestProbe
+/// @property {string} input The checked word or sentence
+/// @property {boolean} expected Is the input expected to be spelled correctly
+/// @property {boolean} passed Did the native spell checker give the expected result
+/// @property {string} [error] The error of the native spell checker, if the check failed
+/// @property {number} durationMs How long the check took, in milliseconds
+#[napi(object, js_name = "SelfTestProbe")]
+pub struct JsSelfTestProbe {
+    /// The checked word or sentence.
+    ///
+    /// The sentence joins the correct words with a gibberish word, only the gibberish word
+    /// is expected to be flagged.
+    pub input: String,
+    /// Is the input expected to be spelled correctly.
+    pub expected: bool,
+    /// Did the native spell checker give the expected result.
+    pub passed: bool,
+    /// The error of the native spell checker, if the check failed.
+    pub error: Option<String>,
+    /// How long the check took, in milliseconds.
+    pub duration_ms: f64,
+}
+
+/// The results of `selfTest()`.
+///
+/// @typedef {Object} SelfTestReport
+/// @property {boolean} passed Did every probe pass
+/// @property {string} [backend] The native spell checker backend, if it can be queried
+/// @property {string} [language] The language of the native spell checker
+/// @property {SelfTestProbe[]} probes The result of each probe
+/// @property {number} durationMs How long the self-test took, in milliseconds
+#[napi(object, js_name = "SelfTestReport")]
+pub struct JsSelfTestReport {
+    /// Did every probe pass.
+    pub passed: bool,
+    /// The native spell checker backend, if it can be queried, e.g. `NSSpellChecker`.
+    pub backend: Option<String>,
+    /// The language of the native spell checker.
+    pub language: Option<String>,
+    /// The result of each probe.
+    pub probes: Vec<JsSelfTestProbe>,
+    /// How long the self-test took, in milliseconds.
+    pub duration_ms: f64,
+}
+
+/// A component of the platform, e.g. the spell checking provider on Windows.
+///
+/// @typedef {Object} BuildComponent
+/// @property {string} name The name of the component
+/// @property {string} value The version or state of the component
+#[napi(object, js_name = "BuildComponent")]
+pub struct JsBuildComponent {
+    /// The name of the component, e.g. `provider`.
+    pub name: String,
+    /// The version or state of the component, e.g. `MsSpell`.
+    pub value: String,
+}
+
+/// The results of `buildInfo()`.
+///
+/// @typedef {Object} BuildInfo
+/// @property {string} version The version of this library
+/// @property {string[]} features The enabled cargo features
+/// @property {boolean} debug Is this a debug build
+/// @property {string} os The operating system of the build
+/// @property {string} arch The CPU architecture of the build
+/// @property {string} backend The native spell checker backend of the build
+/// @property {boolean} backendAvailable Can the native spell checker be created
+/// @property {string} [error] Why the native spell checker cannot be created
+/// @property {BuildComponent[]} components The platform components of the native spell checker
+#[napi(object, js_name = "BuildInfo")]
+pub struct JsBuildInfo {
+    /// The version of this library.
+    pub version: String,
+    /// The enabled cargo features, e.g. `serde`.
+    pub features: Vec<String>,
+    /// Is this a debug build.
+    pub debug: bool,
+    /// The operating system of the build, e.g. `windows`, `macos`, or `linux`.
+    pub os: String,
+    /// The CPU architecture of the build, e.g. `x86_64` or `aarch64`.
+    pub arch: String,
+    /// The native spell checker backend of the build: `windows`, `macos`, or `unsupported`.
+    pub backend: String,
+    /// Can the native spell checker be created on this machine.
+    pub backend_available: bool,
+    /// Why the native spell checker cannot be created.
+    pub error: Option<String>,
+    /// The platform components of the native spell checker, e.g. the Windows spell checking
+    /// provider or the macOS version.
+    pub components: Vec<JsBuildComponent>,
+}
+
+/// Options for looking up the similar words of the dictionaries.
+///
+/// @typedef {Object} LookupOptions
+/// @property {number} [maxDistance] The maximum number of edits, 2 by default
+/// @property {number} [limit] The maximum number of words returned, 10 by default
+#[napi(object, js_name = "LookupOptions")]
+#[derive(Default)]
+pub struct JsLookupOptions {
+    /// The maximum number of edits from the word: inserting, removing, or replacing a character,
+    /// or swapping two adjacent characters. This is 2 by default.
+    pub max_distance: Option<u32>,
+    /// The maximum number of words returned, the closest first. This is 10 by default.
+    pub limit: Option<u32>,
+}
+
+/// A dictionary word similar to the looked up word.
+///
+/// @typedef {Object} SimilarWord
+/// @property {string} word The word of the dictionary
+/// @property {number} distance The number of edits from the looked up word
+#[napi(object, js_name = "SimilarWord")]
+pub struct JsSimilarWord {
+    /// The word, as written in the dictionary.
+    pub word: String,
+    /// The number of edits from the looked up word, ignoring the case.
+    pub distance: u32,
+}
+
+/// A custom dictionary accepted on top of the native spell checker.
+///
+/// @typedef {Object} CustomDictionary
+/// @property {string} id The path of the file, followed by the dictionary inside a `.oxt` extension
+/// @property {string} format The format of the file: `wordlist` or `hunspell`
+/// @property {string[]} languages The languages of the words, empty for every language
+/// @property {number} words The number of words
+/// @property {boolean} enabled Are the words accepted by this spell checker
+#[napi(object, js_name = "CustomDictionary")]
+pub struct JsCustomDictionary {
+    /// The id of the dictionary, the path of the file.
+    ///
+    /// The dictionaries of a `.oxt` extension are suffixed with their path inside the extension,
+    /// e.g. `dict-id.oxt#id_ID.dic`.
+    pub id: String,
+    /// The format of the file.
+    ///
+    /// - `wordlist`: One word per line, e.g. the Office and LibreOffice personal dictionaries.
+    /// - `hunspell`: A Hunspell dictionary expanded with its affix rules.
+    pub format: String,
+    /// The languages of the words, empty when the words are accepted for every language.
+    pub languages: Vec<String>,
+    /// The number of words, including the prefixed and suffixed forms of a Hunspell dictionary.
+    pub words: u32,
+    /// Are the words accepted by this spell checker, see `setDictionaryEnabled()`.
+    pub enabled: bool,
+}
+
 #[napi]
 impl SpellChecker {
     /// The main Spell checker class.
@@ -107,32 +1131,125 @@ impl SpellChecker {
     ///
     /// As a sidenote, all API returned can throw an error, especially on Windows.
     ///
+    /// @param {SpellCheckerOptions} [options] The options for the spell checker.
     /// @returns {void}
     #[napi(constructor)]
-    pub fn new() -> napi::Result<Self> {
+    pub fn new(options: Option<SpellCheckerOptions>) -> napi::Result<Self, ErrorKind> {
+        let options = options.unwrap_or_default();
+        let dictionary_fallback = options.dictionary_fallback.unwrap_or(false);
         #[cfg(target_os = "macos")]
-        let inner = apple::AppleSpellChecker::new();
+        let mut inner: Box<dyn SpellCheckerImpl> = Box::new(apple::AppleSpellChecker::new(
+            options.sandboxed.unwrap_or(false),
+        ));
         #[cfg(target_os = "windows")]
-        let inner = winrt::WindowsSpellChecker::new().map_err(|e| {
-            napi::Error::from_reason(format!(
-                "Failed to create Windows spell checker: {}",
-                e.message()
-            ))
-        })?;
+        let mut inner: Box<dyn SpellCheckerImpl> = match NATIVE_OWNER
+            .spawn(|| Ok(Box::new(winrt::WindowsSpellChecker::new()?) as Box<dyn SpellCheckerImpl>))
+        {
+            Ok(inner) => Box::new(inner),
+            Err(e) if dictionary_fallback && e.kind() == ErrorKind::BackendUnavailable => {
+                Box::new(stubs::WordListSpellChecker::new())
+            }
+            Err(e) => {
+                return Err(utils::Error::with_kind(
+                    e.kind(),
+                    format!("Failed to create Windows spell checker: {}", e.message()),
+                )
+                .into());
+            }
+        };
         #[cfg(not(any(target_os = "macos", target_os = "windows")))]
-        let inner = stubs::StubSpellChecker::new();
+        let mut inner: Box<dyn SpellCheckerImpl> = match dictionary_fallback {
+            true => Box::new(stubs::WordListSpellChecker::new()),
+            false => Box::new(stubs::StubSpellChecker::new()),
+        };
+
+        if let Some(languages) = &options.languages {
+            inner = fallback::FallbackSpellChecker::with_languages(inner, languages)?;
+        }
+        let tokenizer = Arc::new(
+            options
+                .word_break
+                .as_ref()
+                .map(tokenizer::Tokenizer::from)
+                .unwrap_or_default(),
+        );
+        if options.word_break.is_some() {
+            inner = Box::new(tokenizer::TokenizedSpellChecker::new(
+                inner,
+                tokenizer.clone(),
+            ));
+        }
+        let mut lists = match options.user_dictionaries.unwrap_or(false) {
+            true => dictionaries::user_dictionaries(),
+            false => Vec::new(),
+        };
+        for path in options.dictionaries.iter().flatten() {
+            lists.extend(dictionaries::load(Path::new(path))?);
+        }
+        let mut hyphenation = Vec::new();
+        for path in options.hyphenation.iter().flatten() {
+            hyphenation.extend(dictionaries::load_hyphenation(Path::new(path))?);
+        }
+        // Always added, the dictionaries can be downloaded later
+        let lists = Arc::new(RwLock::new(lists));
+        inner = Box::new(dictionaries::DictionariesSpellChecker::new(
+            inner,
+            lists.clone(),
+        ));
+        if options.compounds.unwrap_or(false) {
+            inner = Box::new(compounds::CompoundsSpellChecker::new(inner));
+        }
+        if options.known_words.unwrap_or(false) {
+            inner = Box::new(known::KnownWordsSpellChecker::new(inner, tokenizer.clone()));
+        }
+        if options.common_words.unwrap_or(false) {
+            inner = Box::new(common::CommonWordsSpellChecker::new(
+                inner,
+                tokenizer.clone(),
+            ));
+        }
+        inner = Box::new(cache::CachedSpellChecker::new(inner));
+        if let Some(threshold) = options.propose_additions {
+            inner = Box::new(proposals::ProposalsSpellChecker::new(
+                inner,
+                threshold as usize,
+            ));
+        }
 
         Ok(Self {
-            inner: Box::new(inner),
+            inner: Arc::new(Mutex::new(inner)),
+            allow_native_handle: options.allow_native_handle.unwrap_or(false),
+            tokenizer,
+            dictionaries: lists,
+            hyphenation: Arc::new(hyphenation),
         })
     }
 
+    /// Create a spell checker for the preferred languages of the user in the operating system.
+    ///
+    /// The languages are the preferred languages of the user profile on Windows and `AppleLanguages`
+    /// on macOS, the first installed one is used and the others are fallbacks, see the `languages` option.
+    /// The languages of the options are tried after the preferred ones.
+    ///
+    /// @param {SpellCheckerOptions} [options] The options for the spell checker.
+    /// @returns {SpellChecker}
+    #[napi(factory)]
+    pub fn from_system_preferences(
+        options: Option<SpellCheckerOptions>,
+    ) -> napi::Result<Self, ErrorKind> {
+        let mut options = options.unwrap_or_default();
+        let mut languages = locales::preferred_languages();
+        languages.extend(options.languages.take().unwrap_or_default());
+        options.languages = Some(languages);
+        Self::new(Some(options))
+    }
+
     /// Get the current language of the spell checker.
     ///
     /// @returns {string | null}
     #[napi]
-    pub fn language(&self) -> napi::Result<Option<String>> {
-        Ok(self.inner.get_language()?)
+    pub fn language(&self) -> napi::Result<Option<String>, ErrorKind> {
+        Ok(self.backend()?.get_language()?)
     }
 
     /// Set the language for the spell checker.
@@ -140,12 +1257,9 @@ impl SpellChecker {
     /// @param {string} language The preferred spell checker language.
     /// @returns {void}
     #[napi]
-    pub fn set_language(&mut self, language: String) -> napi::Result<()> {
-        if !self.inner.set_language(&language)? {
-            return Err(napi::Error::from_reason(format!(
-                "Failed to set language: {}",
-                language
-            )));
+    pub fn set_language(&mut self, language: String) -> napi::Result<(), ErrorKind> {
+        if !self.backend()?.set_language(&language)? {
+            return Err(utils::Error::new(format!("Failed to set language: {}", language)).into());
         }
         Ok(())
     }
@@ -154,8 +1268,8 @@ impl SpellChecker {
     ///
     /// @returns {string[]} A list of available languages.
     #[napi]
-    pub fn available_languages(&self) -> napi::Result<Vec<String>> {
-        Ok(self.inner.get_available_languages()?)
+    pub fn available_languages(&self) -> napi::Result<Vec<String>, ErrorKind> {
+        Ok(self.backend()?.get_available_languages()?)
     }
 
     /// Check if a word is spelled correctly.
@@ -163,21 +1277,469 @@ impl SpellChecker {
     /// @param {string} word The word to check
     /// @returns {boolean} Is the word spelled correctly or not.
     #[napi]
-    pub fn check_word(&self, word: String) -> napi::Result<bool> {
-        Ok(self.inner.check_word(&word)?)
+    pub fn check_word(&self, word: String) -> napi::Result<bool, ErrorKind> {
+        Ok(self.backend()?.check_word(&word)?)
+    }
+
+    /// Check the sentences for any issues.
+    ///
+    /// This returns all the issues found, each with its category and severity.
+    /// Currently only the spelling is checked.
+    ///
+    /// @param {string} sentences The sentences to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+    #[napi]
+    pub fn check(
+        &self,
+        sentences: String,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsDiagnostic>, JsTimedDiagnostics>, ErrorKind> {
+        let options = CheckOptions::try_from(options.unwrap_or_default())?;
+
+        Ok(self.timed(&options, || {
+            let diagnostics = self.diagnostics(&sentences, options.clone())?;
+            Ok(options.timings().time(Phase::Marshalling, || {
+                diagnostics.into_iter().map(JsDiagnostic::from).collect()
+            }))
+        })?)
+    }
+
+    /// Check the text paragraph by paragraph, reusing the results of the unchanged paragraphs.
+    ///
+    /// This is the same as `check()` with the `cache` option enabled by default,
+    /// meant for re-checking a document after each edit.
+    /// The cache is cleared when the words of the spell checker are changed.
+    ///
+    /// @param {string} text The text to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+    #[napi]
+    pub fn check_paragraphs(
+        &self,
+        text: String,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsDiagnostic>, JsTimedDiagnostics>, ErrorKind> {
+        let mut options = options.unwrap_or_default();
+        options.cache = options.cache.or(Some(true));
+        let options = CheckOptions::try_from(options)?;
+
+        Ok(self.timed(&options, || {
+            let diagnostics = self.diagnostics(&text, options.clone())?;
+            Ok(options.timings().time(Phase::Marshalling, || {
+                diagnostics.into_iter().map(JsDiagnostic::from).collect()
+            }))
+        })?)
+    }
+
+    /// Check a UTF-8 text file, paragraph by paragraph like `checkParagraphs()`.
+    ///
+    /// The format is guessed from the file extension (e.g. `.json`, `.po`, or `.srt`)
+    /// unless the `format` option is set, unknown extensions are checked as plain text.
+    ///
+    /// @param {string} path The path of the file to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+    #[napi]
+    pub fn check_file(
+        &self,
+        path: String,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsDiagnostic>, JsTimedDiagnostics>, ErrorKind> {
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| utils::Error::new(format!("Failed to read {}: {}", path, e)))?;
+        let mut options = options.unwrap_or_default();
+        options.cache = options.cache.or(Some(true));
+        let guess = options.format.is_none();
+        let mut options = CheckOptions::try_from(options)?;
+        if guess {
+            options = options.with_format(formats::Format::from_path(Path::new(&path)));
+        }
+
+        Ok(self.timed(&options, || {
+            let diagnostics = self.diagnostics(&text, options.clone())?;
+            Ok(options.timings().time(Phase::Marshalling, || {
+                diagnostics.into_iter().map(JsDiagnostic::from).collect()
+            }))
+        })?)
+    }
+
+    /// Check many files in a background task, like a linter.
+    ///
+    /// Each file is checked like `checkFile()`, a file which cannot be read is reported
+    /// with its `error` instead of failing the whole run.
+    /// With the `cacheLocation` option, the unchanged files are not checked again in the next runs.
+    ///
+    /// With `onRecord`, each finding is sent as an NDJSON record as soon as its file is checked,
+    /// e.g. `{"path":"README.md","start":2,"end":5,"word":"tset",...}`, and a file which cannot
+    /// be read as `{"path":"missing.md","error":"..."}`. The findings are then not kept in memory,
+    /// the `diagnostics` of the report are empty but the `errorCount` is still counted.
+    ///
+    /// @param {string[]} paths The paths of the files to check
+    /// @param {LintOptions} [options] The options for checking the files
+    /// @param {Function} [onRecord] Called with a single-line JSON record for each finding
+    /// @returns {Promise<LintReport>} The results of each file.
+    #[napi(
+        ts_args_type = "paths: Array<string>, options?: LintOptions | undefined | null, onRecord?: (record: string) => void",
+        ts_return_type = "Promise<LintReport>"
+    )]
+    pub fn check_files(
+        &self,
+        paths: Vec<String>,
+        options: Option<JsLintOptions>,
+        on_record: Option<ThreadsafeFunction<String, ErrorStrategy::Fatal>>,
+    ) -> napi::Result<AsyncTask<LintTask>, ErrorKind> {
+        let options = options.unwrap_or_default();
+        let mut check = options.check.unwrap_or_default();
+        check.cache = check.cache.or(Some(true));
+        let guess_format = check.format.is_none();
+        let fail_on = match options.fail_on.as_deref() {
+            Some(name) => FailOn::parse(name)?,
+            None => FailOn::Any,
+        };
+        let threshold = options.max_errors.map(|max_errors| Threshold {
+            max_errors: max_errors.try_into().unwrap_or(usize::MAX),
+            fail_on,
+        });
+
+        Ok(AsyncTask::new(
+            LintTask::new(
+                self.inner.clone(),
+                paths,
+                check.try_into()?,
+                guess_format,
+                options.cache_location.map(PathBuf::from),
+                options.concurrency.map_or(1, |concurrency| {
+                    concurrency.try_into().unwrap_or(usize::MAX)
+                }),
+                on_record,
+            )
+            .with_threshold(threshold),
+        ))
+    }
+
+    /// Download a dictionary in a background task, and accept its words from then on.
+    ///
+    /// The file can be a word list, a Hunspell `.dic` without affix rules, or a LibreOffice `.oxt`
+    /// extension, see the `dictionaries` option. It is kept in a cache directory and named after
+    /// its checksum, so it is only downloaded once and loaded from the cache afterwards.
+    ///
+    /// The words are added on top of the native spell checker for this instance and its forks,
+    /// the system dictionaries are not modified.
+    ///
+    /// @param {string} url The URL of the dictionary
+    /// @param {DownloadOptions} options The checksum and the language of the dictionary
+    /// @returns {Promise<string>} The path of the downloaded dictionary.
+    #[napi(ts_return_type = "Promise<string>")]
+    pub fn download_dictionary(
+        &self,
+        url: String,
+        options: JsDownloadOptions,
+    ) -> AsyncTask<DownloadTask> {
+        AsyncTask::new(DownloadTask::new(
+            self.inner.clone(),
+            self.dictionaries.clone(),
+            url,
+            options.sha256,
+            options.language.into_iter().collect(),
+            options.cache_location.map(PathBuf::from),
+        ))
+    }
+
+    /// Remove all the cached paragraph results of this spell checker.
+    ///
+    /// @returns {void}
+    #[napi]
+    pub fn clear_cache(&self) -> napi::Result<(), ErrorKind> {
+        self.backend()?.clear_cache();
+        Ok(())
+    }
+
+    /// Get the statistics of the paragraph cache, e.g. to measure the hit rate.
+    ///
+    /// @returns {CacheStats} The cache statistics.
+    #[napi]
+    pub fn cache_stats(&self) -> napi::Result<JsCacheStats, ErrorKind> {
+        let stats = self.backend()?.cache_stats();
+
+        Ok(JsCacheStats {
+            entries: stats.entries.try_into().unwrap_or(u32::MAX),
+            hits: stats.hits.try_into().unwrap_or(u32::MAX),
+            misses: stats.misses.try_into().unwrap_or(u32::MAX),
+        })
+    }
+
+    /// Split the text into words, the same way as the checks done by this library.
+    ///
+    /// This is useful to build word counts, highlighting, or custom filters
+    /// that agree with the words being checked.
+    ///
+    /// A word is a run of letters, apostrophes are only kept inside a word.
+    /// This can be customized with the `wordBreak` option.
+    ///
+    /// The words only match the checked words exactly with the `wordBreak` option, the text is then
+    /// split by this library before being checked. Otherwise, the native spell checker splits the text
+    /// on its own and may disagree, e.g. on `123abc` or the URLs.
+    ///
+    /// @param {string} text The text to split
+    /// @returns {Token[]} The list of words with their offsets.
+    #[napi]
+    pub fn tokenize(&self, text: String) -> Vec<JsToken> {
+        self.tokenizer
+            .words(&text)
+            .map(|word| JsToken::from(word.to_token()))
+            .collect()
+    }
+
+    /// Check the words from an existing token stream, e.g. from a syntax highlighter.
+    ///
+    /// Each token is checked as a single word, with at most one result spanning the whole token,
+    /// e.g. `my_var` is reported as a whole instead of being split by the native spell checker.
+    /// The offsets of the results are based on the `start` of the token.
+    /// The `format` option is ignored, since the tokens are already extracted.
+    ///
+    /// @param {TextToken[]} tokens The tokens to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Diagnostic[] | TimedDiagnostics} The list of issues found, with the timings with the `debug` option.
+    #[napi]
+    pub fn check_tokens(
+        &self,
+        tokens: Vec<JsTextToken>,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsDiagnostic>, JsTimedDiagnostics>, ErrorKind> {
+        let options = CheckOptions::try_from(options.unwrap_or_default())?;
+
+        Ok(self.timed(&options, || {
+            let backend = self.backend()?;
+            let language = backend.get_language()?;
+            let mut diagnostics = Vec::new();
+            for token in tokens {
+                if options.is_full(diagnostics.len()) {
+                    break;
+                }
+                if token.word.is_empty() || backend.check_word(&token.word)? {
+                    continue;
+                }
+
+                // The suggestions are only kept when the native spell checker flags the whole token
+                let length = token.word.encode_utf16().count();
+                let whole = backend
+                    .check_sentences_with(&token.word, &options.clone().with_max_results(None))?
+                    .into_iter()
+                    .find(|result| result.start() == 0 && result.end() + 1 == length);
+                let suggestions = whole
+                    .as_ref()
+                    .map(|result| result.suggestions().to_vec())
+                    .unwrap_or_default();
+                let rejected_by = whole
+                    .and_then(|result| result.language().map(str::to_string))
+                    .or_else(|| language.clone());
+
+                let start = token.start.try_into().unwrap_or(usize::MAX);
+                let result = TokenWithSuggestions::new(
+                    Token::new(start, start + length - 1, token.word),
+                    suggestions,
+                )
+                .with_language(rejected_by);
+                options.timings().time(Phase::Marshalling, || {
+                    diagnostics.push(JsDiagnostic::from(Diagnostic::spelling(result)))
+                });
+            }
+            Ok(diagnostics)
+        })?)
+    }
+
+    /// Check the blocks of a rich-text editor document, e.g. ProseMirror or TipTap.
+    ///
+    /// Each block is checked on its own, and the results are returned with the document positions,
+    /// ready to be used as decorations without mapping the offsets again.
+    ///
+    /// @param {TextBlock[]} blocks The blocks to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Decoration[] | TimedDecorations} The list of issues found, with the timings with the `debug` option.
+    #[napi]
+    pub fn check_blocks(
+        &self,
+        blocks: Vec<JsTextBlock>,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsDecoration>, JsTimedDecorations>, ErrorKind> {
+        let options = CheckOptions::try_from(options.unwrap_or_default())?;
+
+        Ok(self.timed(&options, || {
+            let mut decorations = Vec::new();
+            for (index, block) in blocks.into_iter().enumerate() {
+                if options.is_full(decorations.len()) {
+                    break;
+                }
+
+                let map = PositionMap::from(&block);
+                let remaining = options
+                    .max_results()
+                    .map(|max_results| max_results - decorations.len());
+                let block_options = options.clone().with_max_results(remaining);
+                let diagnostics = self.diagnostics(&block.text, block_options)?;
+                options.timings().time(Phase::Marshalling, || {
+                    decorations.extend(diagnostics.into_iter().map(|diagnostic| {
+                        let (from, to) = map.range(diagnostic.start(), diagnostic.end());
+                        let diagnostic = JsDiagnostic::from(diagnostic);
+                        JsDecoration {
+                            from: from.try_into().unwrap_or(u32::MAX),
+                            to: to.try_into().unwrap_or(u32::MAX),
+                            block: index.try_into().unwrap_or(u32::MAX),
+                            word: diagnostic.word,
+                            suggestions: diagnostic.suggestions,
+                            category: diagnostic.category,
+                            severity: diagnostic.severity,
+                        }
+                    }))
+                });
+            }
+            Ok(decorations)
+        })?)
+    }
+
+    /// Find the misspelled words across many documents, the most frequent first.
+    ///
+    /// This is useful to seed a project dictionary from an existing corpus,
+    /// since the project-specific words are usually the most frequent ones.
+    /// The `maxResults` option applies to each document.
+    ///
+    /// @param {string[]} texts The documents to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {UnknownWord[]} The misspelled words, ranked by frequency.
+    #[napi]
+    pub fn analyze_unknown_words(
+        &self,
+        texts: Vec<String>,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Vec<JsUnknownWord>, ErrorKind> {
+        let options = CheckOptions::try_from(options.unwrap_or_default())?;
+
+        let mut analysis = UnknownWords::new(analysis::MAX_CONTEXTS);
+        for text in &texts {
+            let diagnostics = self.diagnostics(text, options.clone())?;
+            let tokens = diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.category() == DiagnosticCategory::Spelling)
+                .map(|diagnostic| &**diagnostic.token());
+            analysis.add_document(text, tokens);
+        }
+
+        Ok(analysis
+            .into_ranked()
+            .into_iter()
+            .map(|word| JsUnknownWord {
+                word: word.word,
+                count: word.count.try_into().unwrap_or(u32::MAX),
+                documents: word.documents.try_into().unwrap_or(u32::MAX),
+                contexts: word.contexts,
+            })
+            .collect())
     }
 
     /// Check if a word is spelled correctly.
     ///
     /// This will also return a list of suggestions if the word is misspelled.
     ///
+    /// This only returns the spelling issues of `check()`, in the older `Suggestion` shape.
+    ///
     /// @param {string} sentences The sentences to check
-    /// @returns {Suggestion[]} The list of suggested spellings.
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Suggestion[] | TimedSuggestions} The list of suggested spellings, with the timings with the `debug` option.
     #[napi]
-    pub fn check_and_suggest(&self, sentences: String) -> napi::Result<Vec<JsSuggestion>> {
-        let tokens = self.inner.check_sentences(&sentences)?;
+    pub fn check_and_suggest(
+        &self,
+        sentences: String,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsSuggestion>, JsTimedSuggestions>, ErrorKind> {
+        let options = CheckOptions::try_from(options.unwrap_or_default())?;
+
+        Ok(self.timed(&options, || {
+            let diagnostics = self.diagnostics(&sentences, options.clone())?;
+            Ok(options.timings().time(Phase::Marshalling, || {
+                diagnostics
+                    .into_iter()
+                    .filter(|diagnostic| diagnostic.category() == DiagnosticCategory::Spelling)
+                    .map(|diagnostic| JsSuggestion::from(diagnostic.into_token()))
+                    .collect()
+            }))
+        })?)
+    }
+
+    /// Check if a word is spelled correctly, using the UTF-16 string from JavaScript directly.
+    ///
+    /// This is the same as `checkAndSuggest()`, but skips the conversion to UTF-8 and back,
+    /// since both native spell checkers work on UTF-16 strings.
+    ///
+    /// The string is only passed as-is when there is nothing to skip with the options, e.g. the emoji,
+    /// the placeholders, or a `format`, and without the `cache` option. Otherwise, it is checked exactly
+    /// like `checkAndSuggest()`. Unpaired surrogates are always passed to the spell checker as-is,
+    /// only `maxResults` is applied to them.
+    ///
+    /// @param {string} sentences The sentences to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Suggestion[] | TimedSuggestions} The list of suggested spellings, with the timings with the `debug` option.
+    #[napi(ts_args_type = "sentences: string, options?: CheckOptions | undefined | null")]
+    pub fn check_and_suggest_utf16(
+        &self,
+        sentences: JsString,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<Either<Vec<JsSuggestion>, JsTimedSuggestions>, ErrorKind> {
+        let options = CheckOptions::try_from(options.unwrap_or_default())?;
+        let sentences = sentences
+            .into_utf16()
+            .map_err(|e| utils::Error::new(format!("Failed to read UTF-16 string: {}", e)))?;
+        // The buffer always ends with a null terminator
+        let wide = sentences
+            .as_slice()
+            .split_last()
+            .map_or(&[][..], |(_, wide)| wide);
+
+        Ok(self.timed(&options, || {
+            if let Ok(text) = String::from_utf16(wide) {
+                let unchanged = !options.cache()
+                    && options.timings().time(Phase::Tokenization, || {
+                        formats::prepare(&text, &options).map(|prepared| prepared.is_unchanged())
+                    })?;
+                if !unchanged {
+                    let tokens = self.diagnostics(&text, options.clone())?;
+                    return Ok(options.timings().time(Phase::Marshalling, || {
+                        tokens
+                            .into_iter()
+                            .map(|diagnostic| JsSuggestion::from(diagnostic.into_token()))
+                            .collect()
+                    }));
+                }
+            }
 
-        Ok(tokens.into_iter().map(JsSuggestion::from).collect())
+            let mut tokens = self.backend()?.check_sentences_utf16(wide)?;
+            if let Some(max_results) = options.max_results() {
+                tokens.truncate(max_results);
+            }
+            Ok(options.timings().time(Phase::Marshalling, || {
+                tokens.into_iter().map(JsSuggestion::from).collect()
+            }))
+        })?)
+    }
+
+    /// Check the sentences in a background task.
+    ///
+    /// This is the same as `checkAndSuggest()`, but will not block the event loop.
+    ///
+    /// @param {string} sentences The sentences to check
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {Promise<Suggestion[] | TimedSuggestions>} The list of suggested spellings, with the timings with the `debug` option.
+    #[napi(ts_return_type = "Promise<Array<Suggestion> | TimedSuggestions>")]
+    pub fn check_and_suggest_async(
+        &self,
+        sentences: String,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<AsyncTask<CheckTask>, ErrorKind> {
+        Ok(AsyncTask::new(CheckTask::new(
+            self.inner.clone(),
+            sentences,
+            options.unwrap_or_default().try_into()?,
+        )))
     }
 
     /// Add a single word to the spell checker.
@@ -186,11 +1748,14 @@ impl SpellChecker {
     /// On Windows, this will add the word to the IGNORE list instead of the dictionary.
     /// This is done to avoid adding the word permanently to the dictionary,
     ///
+    /// On macOS, this will learn the word into the user dictionary,
+    /// unless the spell checker is created with the `sandboxed` option.
+    ///
     /// @param {string} word The word to add
     /// @returns {void}
     #[napi]
-    pub fn add_word(&self, word: String) -> napi::Result<()> {
-        self.inner.add_word(&word)?;
+    pub fn add_word(&self, word: String) -> napi::Result<(), ErrorKind> {
+        self.backend()?.add_word(&word)?;
         Ok(())
     }
 
@@ -200,21 +1765,338 @@ impl SpellChecker {
     /// On Windows, this will add the word to the IGNORE list instead of the dictionary.
     /// This is done to avoid adding the word permanently to the dictionary,
     ///
+    /// On macOS, this will learn the word into the user dictionary,
+    /// unless the spell checker is created with the `sandboxed` option.
+    ///
     /// @param {string[]} words The words to add
     /// @returns {void}
     #[napi]
-    pub fn add_words(&self, words: Vec<String>) -> napi::Result<()> {
-        self.inner.add_words(words)?;
+    pub fn add_words(&self, words: Vec<String>) -> napi::Result<(), ErrorKind> {
+        self.backend()?.add_words(words)?;
         Ok(())
     }
 
+    /// Start an interactive correction session over the text, like a "Spelling…" dialog.
+    ///
+    /// The misspelled words are visited one at a time, the session keeps the corrected text
+    /// and the offsets of the remaining words up to date.
+    ///
+    /// @param {string} text The text to correct
+    /// @param {CheckOptions} [options] The options for checking
+    /// @returns {CorrectionSession} The correction session.
+    #[napi]
+    pub fn correction_session(
+        &self,
+        text: String,
+        options: Option<JsCheckOptions>,
+    ) -> napi::Result<CorrectionSession, ErrorKind> {
+        let diagnostics = self.diagnostics(&text, options.unwrap_or_default().try_into()?)?;
+        let misspelled = diagnostics
+            .into_iter()
+            .filter(|diagnostic| diagnostic.category() == DiagnosticCategory::Spelling)
+            .map(Diagnostic::into_token)
+            .collect();
+
+        Ok(CorrectionSession {
+            inner: self.inner.clone(),
+            correction: correction::Correction::new(&text, misspelled),
+        })
+    }
+
+    /// Create a new spell checker that shares the expensive backend resources with this one.
+    ///
+    /// The forked spell checker starts with the same language and options,
+    /// but has its own language and session word list afterwards.
+    /// This is cheaper than creating a new instance, e.g. for per-tab spell checkers.
+    ///
+    /// ### Implementation Note
+    /// On macOS, each instance applies its own language and ignored words to the shared native
+    /// spell checker for every call, only the words learned into the user dictionary are shared.
+    ///
+    /// On Windows, all the spell checkers and their forks live on a single thread
+    /// (the native objects never leave it), every call waits for its turn on that thread.
+    ///
+    /// @returns {SpellChecker} The new spell checker.
+    #[napi]
+    pub fn fork(&self) -> napi::Result<SpellChecker, ErrorKind> {
+        Ok(SpellChecker {
+            inner: Arc::new(Mutex::new(self.backend()?.fork()?)),
+            allow_native_handle: self.allow_native_handle,
+            tokenizer: self.tokenizer.clone(),
+            dictionaries: self.dictionaries.clone(),
+            hyphenation: self.hyphenation.clone(),
+        })
+    }
+
+    /// Get the underlying native spell checker object as an external value.
+    ///
+    /// This is the `ISpellChecker2` COM pointer on Windows (`ISpellChecker` when it is unavailable)
+    /// and the `NSSpellChecker` object on macOS, meant for advanced interop from other native modules.
+    ///
+    /// This requires the `allowNativeHandle` option when creating the spell checker.
+    ///
+    /// ### Safety
+    /// The handle is borrowed, it is only valid as long as this spell checker is alive
+    /// and the language or session is not changed (which may recreate the native object on Windows).
+    ///
+    /// @returns {ExternalObject<unknown>} The native handle.
+    #[napi(ts_return_type = "ExternalObject<unknown>")]
+    pub fn unsafe_native_handle(&self, env: Env) -> napi::Result<JsUnknown, ErrorKind> {
+        if !self.allow_native_handle {
+            return Err(utils::Error::new(
+                "Native handle access is disabled, enable it with the `allowNativeHandle` option",
+            )
+            .into());
+        }
+
+        let handle = self
+            .backend()?
+            .native_handle()
+            .ok_or_else(utils::Error::unimplemented)?;
+
+        let mut raw = std::ptr::null_mut();
+        let status = unsafe {
+            napi::sys::napi_create_external(env.raw(), handle, None, std::ptr::null_mut(), &mut raw)
+        };
+        if status != napi::sys::Status::napi_ok {
+            return Err(utils::Error::new(format!(
+                "Failed to create external for the native handle (status: {})",
+                status
+            ))
+            .into());
+        }
+
+        Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), raw) })
+    }
+
+    /// Reset the session state of the spell checker.
+    ///
+    /// This clears all the ignored/added words and per-document state for this instance,
+    /// without needing to create a new spell checker.
+    ///
+    /// ### Implementation Note
+    /// On macOS, words learned into the user dictionary are persistent and will not be removed,
+    /// use the `sandboxed` option to keep added words in the session instead.
+    ///
+    /// @returns {void}
+    #[napi]
+    pub fn reset_session(&mut self) -> napi::Result<(), ErrorKind> {
+        self.backend()?.reset_session()?;
+        Ok(())
+    }
+
+    /// Get the words flagged more times than the `proposeAdditions` option in this session,
+    /// the most frequent first.
+    ///
+    /// This is useful to prompt "you've typed 'napi' 14 times, add it to the dictionary?".
+    /// The words are no longer proposed once added, or dismissed with `dismissProposal()`.
+    ///
+    /// @returns {ProposedAddition[]} The proposed words with their counts.
+    #[napi]
+    pub fn proposed_additions(&self) -> napi::Result<Vec<JsProposedAddition>, ErrorKind> {
+        let proposals = self.backend()?.proposed_additions();
+
+        Ok(proposals
+            .into_iter()
+            .map(|(word, count)| JsProposedAddition {
+                word,
+                count: count.try_into().unwrap_or(u32::MAX),
+            })
+            .collect())
+    }
+
+    /// Stop proposing the word for the rest of the session, e.g. when the user declined to add it.
+    ///
+    /// @param {string} word The word to stop proposing
+    /// @returns {void}
+    #[napi]
+    pub fn dismiss_proposal(&self, word: String) -> napi::Result<(), ErrorKind> {
+        self.backend()?.dismiss_proposal(&word);
+        Ok(())
+    }
+
+    /// Get information about where the added words are being stored.
+    ///
+    /// This is useful to answer "where did my added words go?".
+    ///
+    /// @returns {DictionaryInfo} The dictionary storage information.
+    #[napi]
+    pub fn dictionary_info(&self) -> napi::Result<JsDictionaryInfo, ErrorKind> {
+        Ok(self.backend()?.dictionary_info()?.into())
+    }
+
+    /// Get the optional features supported by the native spell checker on this system.
+    ///
+    /// On Windows, some older systems and third-party providers cannot remove words,
+    /// `removeWord()` then throws an error.
+    ///
+    /// @returns {Capabilities} The supported features.
+    #[napi]
+    pub fn capabilities(&self) -> napi::Result<JsCapabilities, ErrorKind> {
+        Ok(self.backend()?.capabilities()?.into())
+    }
+
+    /// Check a few known correct and misspelled words with the native spell checker.
+    ///
+    /// This tells a broken system dictionary apart from a bug in this library: the probes skip
+    /// the custom dictionaries and caches of this library, and do not change any state.
+    /// The probe words are bundled for `de`, `en`, `es`, `fr`, `id`, `it`, `nl`, and `pt`,
+    /// only a gibberish word is checked for the other languages.
+    ///
+    /// The failures are reported in the probes instead of being thrown.
+    ///
+    /// @returns {SelfTestReport} The results of the probes.
+    #[napi]
+    pub fn self_test(&self) -> napi::Result<JsSelfTestReport, ErrorKind> {
+        let backend = self.backend()?;
+        Ok(selftest::run(backend.as_ref()).into())
+    }
+
+    /// Get the custom dictionaries of this spell checker.
+    ///
+    /// This includes the personal dictionaries from the `userDictionaries` option, the files
+    /// from the `dictionaries` option, and the dictionaries from `downloadDictionary()`.
+    ///
+    /// @returns {CustomDictionary[]} The list of custom dictionaries.
+    #[napi]
+    pub fn list_dictionaries(&self) -> napi::Result<Vec<JsCustomDictionary>, ErrorKind> {
+        let dictionaries = self.backend()?.custom_dictionaries();
+
+        Ok(dictionaries
+            .into_iter()
+            .map(JsCustomDictionary::from)
+            .collect())
+    }
+
+    /// Enable or disable a custom dictionary for this spell checker only.
+    ///
+    /// This is useful to toggle domain dictionaries (e.g. medical or legal) per document type,
+    /// the other instances are not affected. A forked spell checker starts with the same state.
+    ///
+    /// @param {string} id The id of the dictionary, from `listDictionaries()`
+    /// @param {boolean} enabled Should the words of the dictionary be accepted
+    /// @returns {void}
+    #[napi]
+    pub fn set_dictionary_enabled(&self, id: String, enabled: bool) -> napi::Result<(), ErrorKind> {
+        if !self.backend()?.set_dictionary_enabled(&id, enabled) {
+            return Err(utils::Error::new(format!("Unknown dictionary: {}", id)).into());
+        }
+        Ok(())
+    }
+
+    /// Get the stems of a word, e.g. `walk` for `walked`, for search or highlight features.
+    ///
+    /// The stems come from the Hunspell dictionaries of the language (see the `dictionaries` option),
+    /// so they agree with the words accepted by the spell checker. A word may have more than one.
+    /// The words missing from the dictionaries are stemmed with the Snowball stemmer
+    /// of the language, the result is empty when there is none, e.g. for Indonesian.
+    ///
+    /// @param {string} word The word to stem
+    /// @param {string} [language] The language of the word, the current language by default
+    /// @returns {string[]} The stems of the word.
+    #[napi]
+    pub fn stem(
+        &self,
+        word: String,
+        language: Option<String>,
+    ) -> napi::Result<Vec<String>, ErrorKind> {
+        let backend = self.backend()?;
+        let language = match language {
+            Some(language) => language,
+            None => backend.get_language()?.unwrap_or_default(),
+        };
+
+        let stems = backend.dictionary_stems(&word, &language);
+        if !stems.is_empty() {
+            return Ok(stems);
+        }
+        Ok(stemming::stem(&word, &language).into_iter().collect())
+    }
+
+    /// Get the positions in a word where it can be hyphenated, e.g. `[2, 6]` for `hy-phen-ation`.
+    ///
+    /// The positions are the UTF-16 offsets before which a hyphen can be inserted, from the patterns
+    /// of the `hyphenation` option for the language. This throws when there are no patterns for it.
+    ///
+    /// @param {string} word The word to hyphenate
+    /// @param {string} [language] The language of the word, the current language by default
+    /// @returns {number[]} The break points of the word.
+    #[napi]
+    pub fn hyphenate(
+        &self,
+        word: String,
+        language: Option<String>,
+    ) -> napi::Result<Vec<u32>, ErrorKind> {
+        let language = match language {
+            Some(language) => language,
+            None => self.backend()?.get_language()?.unwrap_or_default(),
+        };
+        let Some(patterns) = dictionaries::find_hyphenation(&self.hyphenation, &language) else {
+            return Err(utils::Error::new(format!(
+                "No hyphenation patterns for {}, see the hyphenation option",
+                language
+            ))
+            .into());
+        };
+
+        Ok(patterns
+            .hyphenate(&word, casing::Casing::for_language(Some(&language)))
+            .into_iter()
+            .map(|offset| offset.try_into().unwrap_or(u32::MAX))
+            .collect())
+    }
+
+    /// Find the words of the custom dictionaries similar to a word, the closest first.
+    ///
+    /// This is useful for "did you mean" features of a search box, which want the words
+    /// of the dictionaries rather than the suggestions of the native spell checker.
+    /// The enabled dictionaries for the current language are searched, and on macOS the words
+    /// added with the `sandboxed` option. The native dictionaries cannot be listed.
+    ///
+    /// @param {string} word The word to look up
+    /// @param {LookupOptions} [options] The options for the lookup
+    /// @returns {SimilarWord[]} The similar words, sorted by distance.
+    #[napi]
+    pub fn lookup_similar(
+        &self,
+        word: String,
+        options: Option<JsLookupOptions>,
+    ) -> napi::Result<Vec<JsSimilarWord>, ErrorKind> {
+        let options = options.unwrap_or_default();
+        let max_distance = options.max_distance.map_or(2, |distance| distance as usize);
+        let limit = options.limit.map_or(10, |limit| limit as usize);
+        let similar = self.backend()?.similar_words(&word, max_distance);
+
+        // Keep the closest distance of the words found in many dictionaries
+        let mut closest: HashMap<String, usize> = HashMap::new();
+        for (word, distance) in similar {
+            closest
+                .entry(word)
+                .and_modify(|closest| *closest = (*closest).min(distance))
+                .or_insert(distance);
+        }
+        let mut closest: Vec<(String, usize)> = closest.into_iter().collect();
+        closest.sort_by(|(a, a_distance), (b, b_distance)| {
+            a_distance.cmp(b_distance).then_with(|| a.cmp(b))
+        });
+
+        Ok(closest
+            .into_iter()
+            .take(limit)
+            .map(|(word, distance)| JsSimilarWord {
+                word,
+                distance: distance.try_into().unwrap_or(u32::MAX),
+            })
+            .collect())
+    }
+
     /// Remove a single word from the spell checker.
     ///
     /// @param {string} word The word to remove
     /// @returns {void}
     #[napi]
-    pub fn remove_word(&self, word: String) -> napi::Result<()> {
-        self.inner.remove_word(&word)?;
+    pub fn remove_word(&self, word: String) -> napi::Result<(), ErrorKind> {
+        self.backend()?.remove_word(&word)?;
         Ok(())
     }
 
@@ -223,8 +2105,201 @@ impl SpellChecker {
     /// @param {string[]} words The words to remove
     /// @returns {void}
     #[napi]
-    pub fn remove_words(&self, words: Vec<String>) -> napi::Result<()> {
-        self.inner.remove_words(words)?;
+    pub fn remove_words(&self, words: Vec<String>) -> napi::Result<(), ErrorKind> {
+        self.backend()?.remove_words(words)?;
+        Ok(())
+    }
+
+    /// Set the maximum number of calls running at once into the native spell checkers.
+    ///
+    /// All calls from every spell checker instance go through a single fair queue,
+    /// by default only one call is running at once.
+    /// Increasing this allows independent per-language spell checkers to run in parallel.
+    ///
+    /// ### Implementation Note
+    /// On macOS, all instances share the same native spell checker,
+    /// so the calls into it still run one at a time.
+    ///
+    /// @param {number} concurrency The maximum number of concurrent calls, at least 1.
+    /// @returns {void}
+    #[napi]
+    pub fn set_max_concurrency(concurrency: u32) -> napi::Result<(), ErrorKind> {
+        CALL_QUEUE.set_concurrency(concurrency.try_into().unwrap_or(usize::MAX))?;
+        Ok(())
+    }
+
+    /// Get the version and the build of this library, with the state of the native spell checker.
+    ///
+    /// This is meant for bug reports, a native spell checker is created to probe the platform
+    /// components, e.g. the spell checking provider on Windows or the macOS version.
+    ///
+    /// @returns {BuildInfo} The build info.
+    #[napi]
+    pub fn build_info() -> JsBuildInfo {
+        buildinfo::collect().into()
+    }
+
+    /// Add words to the spell checker in a background task.
+    ///
+    /// This will not block the event loop, which is useful for a large project dictionary.
+    /// The spell checker is locked for each chunk of 1000 words, the other calls run between the chunks.
+    ///
+    /// @param {string[]} words The words to add
+    /// @param {Function} [onProgress] Called with the number of added words and the total
+    /// @returns {Promise<void>}
+    #[napi(
+        ts_args_type = "words: Array<string>, onProgress?: (done: number, total: number) => void",
+        ts_return_type = "Promise<void>"
+    )]
+    pub fn add_words_async(
+        &self,
+        words: Vec<String>,
+        on_progress: Option<ThreadsafeFunction<(u32, u32), ErrorStrategy::Fatal>>,
+    ) -> AsyncTask<WordsTask> {
+        AsyncTask::new(WordsTask::new(
+            self.inner.clone(),
+            words,
+            WordsOperation::Add,
+            on_progress,
+        ))
+    }
+
+    /// Remove words from the spell checker in a background task.
+    ///
+    /// This will not block the event loop, which is useful for a large project dictionary.
+    /// The spell checker is locked for each chunk of 1000 words, the other calls run between the chunks.
+    ///
+    /// @param {string[]} words The words to remove
+    /// @param {Function} [onProgress] Called with the number of removed words and the total
+    /// @returns {Promise<void>}
+    #[napi(
+        ts_args_type = "words: Array<string>, onProgress?: (done: number, total: number) => void",
+        ts_return_type = "Promise<void>"
+    )]
+    pub fn remove_words_async(
+        &self,
+        words: Vec<String>,
+        on_progress: Option<ThreadsafeFunction<(u32, u32), ErrorStrategy::Fatal>>,
+    ) -> AsyncTask<WordsTask> {
+        AsyncTask::new(WordsTask::new(
+            self.inner.clone(),
+            words,
+            WordsOperation::Remove,
+            on_progress,
+        ))
+    }
+}
+
+impl SpellChecker {
+    /// Check the sentences with the default options, for the Rust users of this crate.
+    ///
+    /// The results can be serialized with the `serde` feature, in the same shape as `check()`.
+    pub fn diagnose(&self, sentences: &str) -> napi::Result<Vec<Diagnostic>, ErrorKind> {
+        Ok(self.diagnostics(sentences, CheckOptions::default())?)
+    }
+
+    /// Get the spell checker implementation, waiting for our turn in the call queue.
+    fn backend(&self) -> EjaanError<BackendGuard<'_>> {
+        lock_backend(&self.inner)
+    }
+
+    /// Run all the checks on the sentences.
+    fn diagnostics(&self, sentences: &str, options: CheckOptions) -> EjaanError<Vec<Diagnostic>> {
+        let prepared = options.timings().time(Phase::Tokenization, || {
+            formats::prepare(sentences, &options)
+        })?;
+        let tokens = prepared.check(self.backend()?.as_mut(), &options)?;
+
+        Ok(tokens.into_iter().map(Diagnostic::spelling).collect())
+    }
+
+    /// Run a check, returning its timing breakdown with the results with the `debug` option.
+    fn timed<T: Timed>(
+        &self,
+        options: &CheckOptions,
+        check: impl FnOnce() -> EjaanError<T::Results>,
+    ) -> EjaanError<Either<T::Results, T>> {
+        let start = Instant::now();
+        let results = check()?;
+        Ok(T::wrap(
+            results,
+            options.timings().breakdown(start.elapsed()),
+        ))
+    }
+}
+
+/// An interactive correction session over a text, created with `SpellChecker.correctionSession()`.
+///
+/// @class CorrectionSession
+#[napi]
+pub struct CorrectionSession {
+    inner: SharedSpellChecker,
+    correction: correction::Correction,
+}
+
+#[napi]
+impl CorrectionSession {
+    /// Get the current misspelled word, with the offsets in the corrected text.
+    ///
+    /// @returns {Suggestion | null} The current misspelled word, or `null` once finished.
+    #[napi]
+    pub fn current(&self) -> Option<JsSuggestion> {
+        self.correction.current().cloned().map(JsSuggestion::from)
+    }
+
+    /// Get the number of the remaining misspelled words, including the current one.
+    ///
+    /// @returns {number} The number of the remaining words.
+    #[napi]
+    pub fn remaining(&self) -> u32 {
+        self.correction.remaining().try_into().unwrap_or(u32::MAX)
+    }
+
+    /// Get the text with all the corrections so far.
+    ///
+    /// @returns {string} The corrected text.
+    #[napi]
+    pub fn text(&self) -> String {
+        self.correction.text()
+    }
+
+    /// Replace the current word, e.g. with one of its suggestions, and move to the next one.
+    ///
+    /// @param {string} suggestion The replacement for the current word
+    /// @returns {void}
+    #[napi]
+    pub fn accept(&mut self, suggestion: String) -> napi::Result<(), ErrorKind> {
+        if !self.correction.replace(&suggestion) {
+            return Err(utils::Error::new("No misspelled word left to correct").into());
+        }
+        Ok(())
+    }
+
+    /// Keep the current word as-is and move to the next one.
+    ///
+    /// @returns {void}
+    #[napi]
+    pub fn ignore_once(&mut self) {
+        self.correction.skip();
+    }
+
+    /// Keep the current word and all its remaining occurrences in the text as-is.
+    ///
+    /// @returns {void}
+    #[napi]
+    pub fn ignore_all(&mut self) {
+        self.correction.skip_all();
+    }
+
+    /// Add the current word to the spell checker, keeping all its remaining occurrences.
+    ///
+    /// @returns {void}
+    #[napi]
+    pub fn add_to_dictionary(&mut self) -> napi::Result<(), ErrorKind> {
+        if let Some(current) = self.correction.current() {
+            lock_backend(&self.inner)?.add_word(current.word())?;
+            self.correction.skip_all();
+        }
         Ok(())
     }
 }
@@ -239,3 +2314,194 @@ impl From<TokenWithSuggestions> for JsSuggestion {
         }
     }
 }
+
+impl From<Token> for JsToken {
+    fn from(token: Token) -> Self {
+        JsToken {
+            start: token.start().try_into().unwrap_or(0),
+            end: token.end().try_into().unwrap_or(0),
+            word: token.word().to_string(),
+        }
+    }
+}
+
+impl From<Diagnostic> for JsDiagnostic {
+    fn from(diagnostic: Diagnostic) -> Self {
+        let category = diagnostic.category().as_str().to_string();
+        let severity = diagnostic.severity().as_str().to_string();
+        let language = diagnostic.language().map(|language| language.to_string());
+        let rejected_by = diagnostic.rejected_by().to_vec();
+        let suggestion = JsSuggestion::from(diagnostic.into_token());
+        JsDiagnostic {
+            start: suggestion.start,
+            end: suggestion.end,
+            word: suggestion.word,
+            suggestions: suggestion.suggestions,
+            category,
+            severity,
+            language,
+            rejected_by,
+        }
+    }
+}
+
+impl From<&JsTextBlock> for PositionMap {
+    fn from(block: &JsTextBlock) -> Self {
+        block
+            .segments
+            .iter()
+            .flatten()
+            .fold(PositionMap::new(block.pos as usize), |map, segment| {
+                map.with_segment(segment.offset as usize, segment.pos as usize)
+            })
+    }
+}
+
+impl TryFrom<JsCheckOptions> for CheckOptions {
+    type Error = utils::Error;
+
+    fn try_from(options: JsCheckOptions) -> Result<Self, Self::Error> {
+        let format = match &options.format {
+            Some(format) => formats::Format::parse(format)?,
+            None => formats::Format::default(),
+        };
+
+        let mut placeholders = match options.skip_placeholders {
+            Some(true) => Placeholders::builtin(),
+            _ => Placeholders::default(),
+        };
+        for delimiters in options.placeholders.unwrap_or_default() {
+            if delimiters.open.is_empty() || delimiters.close.is_empty() {
+                return Err(utils::Error::new(
+                    "Placeholder delimiters must not be empty",
+                ));
+            }
+            placeholders = placeholders.with_delimiters(delimiters.open, delimiters.close);
+        }
+
+        Ok(CheckOptions::new()
+            .with_max_results(
+                options
+                    .max_results
+                    .map(|max_results| max_results.try_into().unwrap_or(usize::MAX)),
+            )
+            .with_format(format)
+            .with_check_keys(options.check_keys.unwrap_or(false))
+            .with_skip_emoji(options.skip_emoji.unwrap_or(true))
+            .with_placeholders(placeholders)
+            .with_cache(options.cache.unwrap_or(false))
+            .with_timings(match options.debug {
+                Some(true) => Recorder::enabled(),
+                _ => Recorder::default(),
+            }))
+    }
+}
+
+impl From<timings::Breakdown> for JsTimings {
+    fn from(breakdown: timings::Breakdown) -> Self {
+        let ms = |duration: std::time::Duration| duration.as_secs_f64() * 1000.0;
+        JsTimings {
+            tokenization_ms: ms(breakdown.tokenization),
+            check_ms: ms(breakdown.check),
+            suggestions_ms: ms(breakdown.suggestions),
+            marshalling_ms: ms(breakdown.marshalling),
+            total_ms: ms(breakdown.total),
+        }
+    }
+}
+
+impl From<&JsWordBreakOptions> for tokenizer::Tokenizer {
+    fn from(options: &JsWordBreakOptions) -> Self {
+        let mut tokenizer = tokenizer::Tokenizer::new();
+        if let Some(chars) = &options.word_characters {
+            tokenizer = tokenizer.with_word_chars(chars);
+        }
+        if let Some(chars) = &options.join_characters {
+            tokenizer = tokenizer.with_join_chars(chars);
+        }
+        if let Some(chars) = &options.break_characters {
+            tokenizer = tokenizer.with_break_chars(chars);
+        }
+        tokenizer
+    }
+}
+
+impl From<Capabilities> for JsCapabilities {
+    fn from(capabilities: Capabilities) -> Self {
+        JsCapabilities {
+            remove_words: capabilities.remove_words(),
+            language_detection: capabilities.language_detection(),
+        }
+    }
+}
+
+impl From<selftest::Probe> for JsSelfTestProbe {
+    fn from(probe: selftest::Probe) -> Self {
+        JsSelfTestProbe {
+            input: probe.input,
+            expected: probe.expected,
+            passed: probe.passed,
+            error: probe.error,
+            duration_ms: probe.duration.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+impl From<selftest::Report> for JsSelfTestReport {
+    fn from(report: selftest::Report) -> Self {
+        JsSelfTestReport {
+            passed: report.passed(),
+            backend: report.backend,
+            language: report.language,
+            duration_ms: report.duration.as_secs_f64() * 1000.0,
+            probes: report
+                .probes
+                .into_iter()
+                .map(JsSelfTestProbe::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<buildinfo::BuildInfo> for JsBuildInfo {
+    fn from(info: buildinfo::BuildInfo) -> Self {
+        JsBuildInfo {
+            version: info.version.to_string(),
+            features: info.features.into_iter().map(String::from).collect(),
+            debug: info.debug,
+            os: info.os.to_string(),
+            arch: info.arch.to_string(),
+            backend: info.backend.to_string(),
+            backend_available: info.available,
+            error: info.error,
+            components: info
+                .components
+                .into_iter()
+                .map(|(name, value)| JsBuildComponent { name, value })
+                .collect(),
+        }
+    }
+}
+
+impl From<CustomDictionary> for JsCustomDictionary {
+    fn from(dictionary: CustomDictionary) -> Self {
+        JsCustomDictionary {
+            id: dictionary.id,
+            format: dictionary.format.as_str().to_string(),
+            languages: dictionary.languages,
+            words: dictionary.words.try_into().unwrap_or(u32::MAX),
+            enabled: dictionary.enabled,
+        }
+    }
+}
+
+impl From<DictionaryInfo> for JsDictionaryInfo {
+    fn from(info: DictionaryInfo) -> Self {
+        JsDictionaryInfo {
+            backend: info.backend().to_string(),
+            storage: info.storage().as_str().to_string(),
+            location: info.location().map(|location| location.to_string()),
+            persistent: info.persistent(),
+        }
+    }
+}
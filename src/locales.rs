@@ -0,0 +1,154 @@
+//! The preferred languages of the user from the operating system.
+//!
+//! - Windows: the preferred UI languages of the user profile.
+//! - macOS: the preferred languages of the user (`AppleLanguages`).
+//! - Others: the `LANGUAGE`, `LC_ALL`, `LC_MESSAGES`, and `LANG` environment variables.
+
+/// Get the preferred languages of the user, the most preferred first, e.g. `["en-US", "id-ID"]`.
+#[cfg(target_os = "windows")]
+pub fn preferred_languages() -> Vec<String> {
+    use windows::{
+        Win32::Globalization::{GetUserPreferredUILanguages, MUI_LANGUAGE_NAME},
+        core::PWSTR,
+    };
+
+    let mut count = 0;
+    let mut length = 0;
+    // Ask for the size first, the languages are a list of null-terminated strings
+    if unsafe { GetUserPreferredUILanguages(MUI_LANGUAGE_NAME, &mut count, None, &mut length) }
+        .is_err()
+    {
+        return Vec::new();
+    }
+    let mut buffer = vec![0u16; length as usize];
+    if unsafe {
+        GetUserPreferredUILanguages(
+            MUI_LANGUAGE_NAME,
+            &mut count,
+            Some(PWSTR(buffer.as_mut_ptr())),
+            &mut length,
+        )
+    }
+    .is_err()
+    {
+        return Vec::new();
+    }
+
+    buffer
+        .split(|unit| *unit == 0)
+        .filter(|language| !language.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect()
+}
+
+/// Get the preferred languages of the user, the most preferred first, e.g. `["en-US", "id-ID"]`.
+#[cfg(target_os = "macos")]
+pub fn preferred_languages() -> Vec<String> {
+    use objc2_foundation::NSLocale;
+
+    NSLocale::preferredLanguages()
+        .iter()
+        .map(|language| language.to_string())
+        .collect()
+}
+
+/// Get the preferred languages of the user, the most preferred first, e.g. `["en-US", "id-ID"]`.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn preferred_languages() -> Vec<String> {
+    let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+
+    // `LANGUAGE` is a list, but only used when a locale is set
+    let locale = env("LC_ALL")
+        .or_else(|| env("LC_MESSAGES"))
+        .or_else(|| env("LANG"));
+    let mut languages: Vec<String> = env("LANGUAGE")
+        .filter(|_| locale.is_some())
+        .unwrap_or_default()
+        .split(':')
+        .filter_map(from_posix)
+        .collect();
+    if let Some(language) = locale.as_deref().and_then(from_posix)
+        && !languages.contains(&language)
+    {
+        languages.push(language);
+    }
+    languages
+}
+
+/// Convert a POSIX locale to a language tag, e.g. `en-US` for `en_US.UTF-8@euro`.
+///
+/// The `C` and `POSIX` locales have no language.
+#[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+fn from_posix(locale: &str) -> Option<String> {
+    let language = locale.split(['.', '@']).next()?.trim();
+    if language.is_empty() || language == "C" || language == "POSIX" {
+        return None;
+    }
+    Some(language.replace('_', "-"))
+}
+
+/// Resolve the preferred languages against the available ones, the most preferred first.
+///
+/// A language matches the same language with either separator, then the first available
+/// language with the same primary subtag, e.g. `en-GB` for `en-AU`.
+pub fn resolve(preferred: &[String], available: &[String]) -> Vec<String> {
+    let normalize = |language: &str| language.replace('_', "-").to_ascii_lowercase();
+    let primary = |language: &str| {
+        normalize(language)
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let mut resolved: Vec<String> = Vec::new();
+    for language in preferred {
+        let found = available
+            .iter()
+            .find(|candidate| normalize(candidate) == normalize(language))
+            .or_else(|| {
+                available
+                    .iter()
+                    .find(|candidate| primary(candidate) == primary(language))
+            });
+        if let Some(found) = found
+            && !resolved.contains(found)
+        {
+            resolved.push(found.clone());
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_posix() {
+        assert_eq!(from_posix("en_US.UTF-8").as_deref(), Some("en-US"));
+        assert_eq!(from_posix("de_DE@euro").as_deref(), Some("de-DE"));
+        assert_eq!(from_posix("id").as_deref(), Some("id"));
+        assert_eq!(from_posix("C.UTF-8"), None);
+        assert_eq!(from_posix("POSIX"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let available = strings(&["en-GB", "en-US", "id-ID", "de_DE"]);
+        assert_eq!(
+            resolve(&strings(&["en-US", "id", "de-DE", "ja-JP"]), &available),
+            ["en-US", "id-ID", "de_DE"]
+        );
+        // The region falls back to the first one of the language, without duplicates
+        assert_eq!(
+            resolve(&strings(&["en-AU", "en-GB"]), &available),
+            ["en-GB"]
+        );
+        assert!(resolve(&strings(&["ja"]), &available).is_empty());
+    }
+}
@@ -0,0 +1,103 @@
+//! Newline-delimited JSON records for streaming the results of large scans.
+//!
+//! Each record is a single line without the trailing newline, so tooling can pipe
+//! the findings into other systems as soon as each file is checked.
+
+use crate::utils::Diagnostic;
+
+/// Get the record of an issue found in a file.
+///
+/// The shape is the same as the JavaScript `Diagnostic` object, with the `path` of the file.
+pub fn finding(path: &str, diagnostic: &Diagnostic) -> String {
+    let token = diagnostic.token();
+    let mut record = String::from("{\"path\":");
+    push_string(&mut record, path);
+    record.push_str(&format!(
+        ",\"start\":{},\"end\":{},\"word\":",
+        token.start(),
+        token.end()
+    ));
+    push_string(&mut record, token.word());
+    record.push_str(",\"suggestions\":");
+    push_array(&mut record, token.suggestions());
+    record.push_str(",\"category\":");
+    push_string(&mut record, diagnostic.category().as_str());
+    record.push_str(",\"severity\":");
+    push_string(&mut record, diagnostic.severity().as_str());
+    if let Some(language) = diagnostic.language() {
+        record.push_str(",\"language\":");
+        push_string(&mut record, language);
+    }
+    record.push_str(",\"rejectedBy\":");
+    push_array(&mut record, diagnostic.rejected_by());
+    record.push('}');
+    record
+}
+
+/// Get the record of a file which could not be checked.
+pub fn file_error(path: &str, error: &str) -> String {
+    let mut record = String::from("{\"path\":");
+    push_string(&mut record, path);
+    record.push_str(",\"error\":");
+    push_string(&mut record, error);
+    record.push('}');
+    record
+}
+
+fn push_array(output: &mut String, items: &[String]) {
+    output.push('[');
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            output.push(',');
+        }
+        push_string(output, item);
+    }
+    output.push(']');
+}
+
+/// Push a JSON string, escaping the quotes, backslashes, and control characters.
+fn push_string(output: &mut String, text: &str) {
+    output.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{Token, TokenWithSuggestions};
+
+    #[test]
+    fn test_finding() {
+        let token = TokenWithSuggestions::new(
+            Token::new(2, 5, "tset".into()),
+            vec!["test".into(), "te\"st".into()],
+        )
+        .with_language(Some("en-US".into()));
+        let record = finding("docs\\a.md", &Diagnostic::spelling(token));
+        assert_eq!(
+            record,
+            r#"{"path":"docs\\a.md","start":2,"end":5,"word":"tset","suggestions":["test","te\"st"],"category":"spelling","severity":"warning","language":"en-US","rejectedBy":["en-US"]}"#
+        );
+
+        let parsed: serde_json::Value = serde_json::from_str(&record).unwrap();
+        assert_eq!(parsed["suggestions"][1], "te\"st");
+    }
+
+    #[test]
+    fn test_file_error() {
+        let record = file_error("a.md", "Failed\nto read\u{1}");
+        assert_eq!(record, r#"{"path":"a.md","error":"Failed\nto read\u0001"}"#);
+        assert!(!record.contains('\n'));
+    }
+}
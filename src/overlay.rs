@@ -0,0 +1,109 @@
+//! Overlay dictionary, a list of custom words maintained by the crate itself.
+//!
+//! This is used when we cannot (or should not) store the words in the system dictionary.
+
+use std::{collections::HashSet, sync::RwLock};
+
+use crate::distance;
+
+#[derive(Debug, Default)]
+pub struct OverlayDictionary {
+    words: RwLock<HashSet<String>>,
+}
+
+impl OverlayDictionary {
+    /// Create a new empty overlay dictionary.
+    pub fn new() -> Self {
+        OverlayDictionary::default()
+    }
+
+    /// Add a word to the dictionary.
+    ///
+    /// Returns `true` if the word was not in the dictionary before.
+    pub fn add(&self, word: &str) -> bool {
+        self.words
+            .write()
+            .map(|mut words| words.insert(word.to_string()))
+            .unwrap_or(false)
+    }
+
+    /// Remove a word from the dictionary.
+    ///
+    /// Returns `true` if the word was in the dictionary before.
+    pub fn remove(&self, word: &str) -> bool {
+        self.words
+            .write()
+            .map(|mut words| words.remove(word))
+            .unwrap_or(false)
+    }
+
+    /// Check if the word is in the dictionary.
+    pub fn contains(&self, word: &str) -> bool {
+        self.words
+            .read()
+            .map(|words| words.contains(word))
+            .unwrap_or(false)
+    }
+
+    /// Remove all the words from the dictionary.
+    pub fn clear(&self) {
+        if let Ok(mut words) = self.words.write() {
+            words.clear();
+        }
+    }
+
+    /// Get the words within `max_distance` edits of the word, with their distance.
+    pub fn similar(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.words
+            .read()
+            .map(|words| {
+                words
+                    .iter()
+                    .filter_map(|entry| {
+                        distance::within(word, entry, max_distance)
+                            .map(|distance| (entry.clone(), distance))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get all the words in the dictionary.
+    pub fn words(&self) -> Vec<String> {
+        self.words
+            .read()
+            .map(|words| words.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_and_remove() {
+        let overlay = OverlayDictionary::new();
+        assert!(overlay.add("ejaan"));
+        assert!(!overlay.add("ejaan"), "Adding twice should be a no-op");
+        assert!(overlay.contains("ejaan"));
+
+        assert!(overlay.remove("ejaan"));
+        assert!(!overlay.remove("ejaan"), "Removing twice should be a no-op");
+        assert!(!overlay.contains("ejaan"));
+    }
+
+    #[test]
+    fn test_words() {
+        let overlay = OverlayDictionary::new();
+        overlay.add("ejaan");
+        overlay.add("napi");
+
+        let mut words = overlay.words();
+        words.sort();
+        assert_eq!(words, vec!["ejaan".to_string(), "napi".to_string()]);
+
+        overlay.clear();
+        assert!(overlay.words().is_empty());
+    }
+}
@@ -0,0 +1,441 @@
+//! A dedicated thread owning the native spell checker objects.
+//!
+//! The native objects are created, used, and released only on the owner thread,
+//! every call is sent to it through a channel and the caller waits for the reply.
+//! A single thread holds all the native spell checkers of the process, by handle.
+//! The thread is prepared before the objects are created (COM on Windows), and cleaned up
+//! once the last spell checker is released.
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, Weak,
+        mpsc::{self, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    SpellCheckerImpl, WORDS_CHUNK_SIZE,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, Error, TokenWithSuggestions},
+};
+
+type Job<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+/// A thread owning a value, the value never leaves the thread.
+pub struct OwnerThread<T: 'static> {
+    sender: Option<Sender<Job<T>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl<T: 'static> OwnerThread<T> {
+    /// Spawn a new owner thread, the value is created on the thread by `init`.
+    pub fn spawn<F>(name: &str, init: F) -> EjaanError<Self>
+    where
+        F: FnOnce() -> EjaanError<T> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Job<T>>();
+        let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let mut value = match init() {
+                    Ok(value) => {
+                        let _ = ready_tx.send(Ok(()));
+                        value
+                    }
+                    Err(err) => {
+                        let _ = ready_tx.send(Err(err));
+                        return;
+                    }
+                };
+
+                // Runs until all the senders are dropped
+                for job in receiver {
+                    job(&mut value);
+                }
+            })
+            .map_err(|e| Error::new(format!("Failed to spawn native owner thread: {}", e)))?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(OwnerThread {
+                sender: Some(sender),
+                handle: Some(handle),
+            }),
+            Ok(Err(err)) => {
+                let _ = handle.join();
+                Err(err)
+            }
+            Err(_) => {
+                let _ = handle.join();
+                Err(Self::stopped())
+            }
+        }
+    }
+
+    /// Run a job on the owner thread and wait for the result.
+    pub fn run<R, F>(&self, job: F) -> EjaanError<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut T) -> R + Send + 'static,
+    {
+        let sender = self.sender.as_ref().ok_or_else(Self::stopped)?;
+        let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+
+        sender
+            .send(Box::new(move |value: &mut T| {
+                let _ = reply_tx.send(job(value));
+            }))
+            .map_err(|_| Self::stopped())?;
+
+        // The reply is dropped without a value if the job panicked
+        reply_rx.recv().map_err(|_| Self::stopped())
+    }
+
+    fn stopped() -> Error {
+        Error::new("Native owner thread has stopped, the spell checker is unusable")
+    }
+}
+
+impl<T: 'static> Drop for OwnerThread<T> {
+    fn drop(&mut self) {
+        // Close the channel so the thread releases the value and exits
+        drop(self.sender.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Kept on an owner thread until its loop ends, e.g. to uninitialize COM.
+pub type ThreadGuard = Box<dyn Any>;
+
+/// The spell checkers of the owner thread by handle, with the guard of the thread.
+struct Registry {
+    checkers: HashMap<u64, Box<dyn SpellCheckerImpl>>,
+    next_handle: u64,
+    /// Dropped after the spell checkers, the fields are dropped in order.
+    _guard: ThreadGuard,
+}
+
+impl Registry {
+    fn insert(&mut self, checker: Box<dyn SpellCheckerImpl>) -> u64 {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.checkers.insert(handle, checker);
+        handle
+    }
+
+    fn get(&mut self, handle: u64) -> EjaanError<&mut Box<dyn SpellCheckerImpl>> {
+        self.checkers
+            .get_mut(&handle)
+            .ok_or_else(|| Error::new("Native spell checker has already been released"))
+    }
+}
+
+/// The owner thread shared by all the native spell checkers of the process.
+///
+/// The thread is spawned for the first spell checker, and stops once the last one is dropped.
+pub struct NativeOwner {
+    thread: Mutex<Weak<OwnerThread<Registry>>>,
+    /// Prepare the thread before any native object is used on it,
+    /// the guard is dropped once the last spell checker is released.
+    thread_init: fn() -> EjaanError<ThreadGuard>,
+}
+
+impl NativeOwner {
+    pub const fn new(thread_init: fn() -> EjaanError<ThreadGuard>) -> Self {
+        NativeOwner {
+            thread: Mutex::new(Weak::new()),
+            thread_init,
+        }
+    }
+
+    /// Create a spell checker on the owner thread with `create`, spawning the thread if needed.
+    pub fn spawn<F>(&self, create: F) -> EjaanError<OwnedSpellChecker>
+    where
+        F: FnOnce() -> EjaanError<Box<dyn SpellCheckerImpl>> + Send + 'static,
+    {
+        let owner = self.thread()?;
+        let handle = owner.run(move |registry| Ok::<_, Error>(registry.insert(create()?)))??;
+
+        Ok(OwnedSpellChecker { owner, handle })
+    }
+
+    fn thread(&self) -> EjaanError<Arc<OwnerThread<Registry>>> {
+        let mut thread = self
+            .thread
+            .lock()
+            .map_err(|_| Error::new("Native owner thread lock is poisoned"))?;
+        if let Some(owner) = thread.upgrade() {
+            return Ok(owner);
+        }
+
+        let thread_init = self.thread_init;
+        let owner = Arc::new(OwnerThread::spawn("ejaan-native", move || {
+            Ok(Registry {
+                checkers: HashMap::new(),
+                next_handle: 0,
+                _guard: thread_init()?,
+            })
+        })?);
+        *thread = Arc::downgrade(&owner);
+        Ok(owner)
+    }
+}
+
+/// A spell checker implementation living on the shared owner thread.
+pub struct OwnedSpellChecker {
+    owner: Arc<OwnerThread<Registry>>,
+    handle: u64,
+}
+
+impl OwnedSpellChecker {
+    /// Run a job with our spell checker on the owner thread and wait for the result.
+    fn run<R, F>(&self, job: F) -> EjaanError<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Box<dyn SpellCheckerImpl>) -> EjaanError<R> + Send + 'static,
+    {
+        let handle = self.handle;
+        self.owner.run(move |registry| job(registry.get(handle)?))?
+    }
+}
+
+impl Drop for OwnedSpellChecker {
+    fn drop(&mut self) {
+        // Release the native objects on the owner thread, the thread stops with the last one
+        let handle = self.handle;
+        let _ = self.owner.run(move |registry| {
+            registry.checkers.remove(&handle);
+        });
+    }
+}
+
+impl SpellCheckerImpl for OwnedSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        let word = word.to_string();
+        self.run(move |inner| inner.check_word(&word))
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let sentence = sentence.to_string();
+        self.run(move |inner| inner.check_sentences(&sentence))
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let sentence = sentence.to_string();
+        let options = options.clone();
+        self.run(move |inner| inner.check_sentences_with(&sentence, &options))
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let sentence = sentence.to_vec();
+        self.run(move |inner| inner.check_sentences_utf16(&sentence))
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        let word = word.to_string();
+        self.run(move |inner| inner.add_word(&word))
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        let word = word.to_string();
+        self.run(move |inner| inner.remove_word(&word))
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.run(move |inner| inner.add_words(words))
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        // Report the progress from the calling thread
+        for chunk in words.chunks(WORDS_CHUNK_SIZE) {
+            let chunk = chunk.to_vec();
+            let count = chunk.len();
+            self.run(move |inner| inner.add_words(chunk))?;
+            on_chunk(count);
+        }
+        Ok(())
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.run(move |inner| inner.remove_words(words))
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        // Report the progress from the calling thread
+        for chunk in words.chunks(WORDS_CHUNK_SIZE) {
+            let chunk = chunk.to_vec();
+            let count = chunk.len();
+            self.run(move |inner| inner.remove_words(chunk))?;
+            on_chunk(count);
+        }
+        Ok(())
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        // Raw pointers cannot cross threads, pass the address instead
+        self.run(|inner| Ok(inner.native_handle().map(|handle| handle as usize)))
+            .ok()
+            .flatten()
+            .map(|handle| handle as *mut std::ffi::c_void)
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        // The native spell checker is only reachable through its thread
+        self
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        // The forked spell checker stays on the owner thread with a handle of its own
+        let handle = self.handle;
+        let forked = self.owner.run(move |registry| {
+            let forked = registry.get(handle)?.fork()?;
+            Ok::<_, Error>(registry.insert(forked))
+        })??;
+
+        Ok(Box::new(OwnedSpellChecker {
+            owner: self.owner.clone(),
+            handle: forked,
+        }))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.run(|inner| inner.reset_session())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.run(|inner| inner.dictionary_info())
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.run(|inner| inner.capabilities())
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.run(|inner| inner.get_available_languages())
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.run(|inner| inner.get_language())
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        let language = language.to_string();
+        self.run(move |inner| inner.set_language(&language))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        rc::Rc,
+        sync::atomic::{AtomicBool, Ordering},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_value_stays_on_owner_thread() {
+        // `Rc` is not `Send`, so it must be created and used on the owner thread
+        let owner = OwnerThread::spawn("ejaan-test", || Ok(Rc::new(Cell::new(0)))).unwrap();
+        let caller = thread::current().id();
+
+        let (count, thread_id) = owner
+            .run(|value| {
+                value.set(value.get() + 1);
+                (value.get(), thread::current().id())
+            })
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_ne!(
+            thread_id, caller,
+            "Expected the job to run on the owner thread"
+        );
+
+        let count = owner.run(|value| value.get()).unwrap();
+        assert_eq!(count, 1, "Expected the value to be kept between jobs");
+    }
+
+    #[test]
+    fn test_init_error() {
+        let result = OwnerThread::<()>::spawn("ejaan-test", || Err(Error::new("init failed")));
+        assert_eq!(result.err().unwrap().message(), "init failed");
+    }
+
+    #[test]
+    fn test_panicked_job() {
+        let owner = OwnerThread::spawn("ejaan-test", || Ok(())).unwrap();
+
+        assert!(owner.run(|_| -> bool { panic!("job failed") }).is_err());
+        assert!(
+            owner.run(|_| ()).is_err(),
+            "Expected the owner thread to be unusable after a panic"
+        );
+    }
+
+    #[test]
+    fn test_thread_guard() {
+        static RELEASED: AtomicBool = AtomicBool::new(false);
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                RELEASED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        static OWNER: NativeOwner = NativeOwner::new(|| Ok(Box::new(Guard) as ThreadGuard));
+
+        let owned = OWNER
+            .spawn(|| Ok(Box::new(crate::stubs::StubSpellChecker::new())))
+            .unwrap();
+        drop(owned);
+        // The thread loop ends once the last spell checker is dropped
+        assert!(RELEASED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_shared_thread() {
+        static OWNER: NativeOwner = NativeOwner::new(|| Ok(Box::new(()) as ThreadGuard));
+        let thread_id = |owned: &OwnedSpellChecker| owned.owner.run(|_| thread::current().id());
+
+        let first = OWNER
+            .spawn(|| Ok(Box::new(crate::stubs::WordListSpellChecker::new())))
+            .unwrap();
+        let second = OWNER
+            .spawn(|| Ok(Box::new(crate::stubs::WordListSpellChecker::new())))
+            .unwrap();
+        let forked = first.fork().unwrap();
+        assert_eq!(thread_id(&first).unwrap(), thread_id(&second).unwrap());
+        assert_ne!(first.handle, second.handle);
+
+        // Each handle keeps its own session words
+        first.add_word("ejaan").unwrap();
+        assert!(first.check_word("ejaan").unwrap());
+        assert!(!second.check_word("ejaan").unwrap());
+        assert!(!forked.check_word("ejaan").unwrap());
+
+        // The other spell checkers are still usable once one is dropped
+        drop(first);
+        assert!(!second.check_word("ejaan").unwrap());
+        assert!(!forked.check_word("ejaan").unwrap());
+    }
+}
@@ -0,0 +1,321 @@
+//! Propose adding the words which keep being flagged in a session to the dictionary.
+//!
+//! A word typed many times is usually a name or a project-specific term, counting them here
+//! lets the applications prompt the user without keeping their own counts.
+//!
+//! Each occurrence is only counted once, an editor re-checking an unchanged paragraph
+//! on every keystroke does not inflate the counts.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    SpellCheckerImpl,
+    cache::{self, CacheStats},
+    dictionaries::CustomDictionary,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, TokenWithSuggestions},
+};
+
+/// How many times each misspelled word was flagged in the session.
+#[derive(Debug, Clone)]
+pub struct WordCounts {
+    /// The words flagged more than this many times are proposed.
+    threshold: usize,
+    counts: HashMap<String, usize>,
+    /// The occurrences already counted, see `record_at()`.
+    seen: HashSet<u64>,
+    /// The words the user declined to add, never proposed again in the session.
+    dismissed: HashSet<String>,
+}
+
+impl WordCounts {
+    /// Create the counts, proposing the words flagged more than `threshold` times.
+    pub fn new(threshold: usize) -> Self {
+        WordCounts {
+            threshold,
+            counts: HashMap::new(),
+            seen: HashSet::new(),
+            dismissed: HashSet::new(),
+        }
+    }
+
+    /// Count a flagged word.
+    pub fn record(&mut self, word: &str) {
+        if self.dismissed.contains(word) {
+            return;
+        }
+        match self.counts.get_mut(word) {
+            Some(count) => *count += 1,
+            None => {
+                self.counts.insert(word.to_string(), 1);
+            }
+        }
+    }
+
+    /// Count a flagged word, unless this occurrence of it was already counted.
+    pub fn record_at(&mut self, word: &str, occurrence: u64) {
+        if self.seen.insert(occurrence) {
+            self.record(word);
+        }
+    }
+
+    /// Forget the count of a word, e.g. once it is added to the dictionary.
+    pub fn forget(&mut self, word: &str) {
+        self.counts.remove(word);
+    }
+
+    /// Stop proposing the word for the rest of the session.
+    pub fn dismiss(&mut self, word: &str) {
+        self.counts.remove(word);
+        self.dismissed.insert(word.to_string());
+    }
+
+    /// Get the proposed words with their counts, the most frequent first.
+    pub fn proposals(&self) -> Vec<(String, usize)> {
+        let mut proposals: Vec<(String, usize)> = self
+            .counts
+            .iter()
+            .filter(|(_, count)| **count > self.threshold)
+            .map(|(word, count)| (word.clone(), *count))
+            .collect();
+        proposals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        proposals
+    }
+
+    /// Forget all the counts and the dismissed words.
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.seen.clear();
+        self.dismissed.clear();
+    }
+}
+
+/// A spell checker counting the flagged words of the session.
+///
+/// This is always the outermost wrapper, so it counts the words as seen by the caller.
+pub struct ProposalsSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    counts: RefCell<WordCounts>,
+}
+
+impl ProposalsSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>, threshold: usize) -> Self {
+        ProposalsSpellChecker {
+            inner,
+            counts: RefCell::new(WordCounts::new(threshold)),
+        }
+    }
+
+    /// Count the flagged words of the sentence.
+    ///
+    /// An occurrence is the paragraph and the offset inside of it, so the unchanged paragraphs
+    /// are not counted again when the whole text is re-checked.
+    fn record(
+        &self,
+        sentence: &str,
+        tokens: EjaanError<Vec<TokenWithSuggestions>>,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let tokens = tokens?;
+        let paragraphs = cache::paragraphs(sentence);
+        let mut counts = self.counts.borrow_mut();
+        for token in &tokens {
+            let index = paragraphs
+                .partition_point(|(start, _)| *start <= token.start())
+                .saturating_sub(1);
+            let (start, paragraph) = paragraphs.get(index).copied().unwrap_or((0, sentence));
+            let mut hasher = DefaultHasher::new();
+            (paragraph, token.start().saturating_sub(start)).hash(&mut hasher);
+            counts.record_at(token.word(), hasher.finish());
+        }
+        Ok(tokens)
+    }
+}
+
+impl SpellCheckerImpl for ProposalsSpellChecker {
+    /// A single word has no occurrence to tell the repeated checks apart, it is not counted.
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        self.inner.check_word(word)
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.record(sentence, self.inner.check_sentences(sentence))
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.record(sentence, self.inner.check_sentences_with(sentence, options))
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // The replacement of an unpaired surrogate keeps the UTF-16 offsets
+        let text = String::from_utf16_lossy(sentence);
+        self.record(&text, self.inner.check_sentences_utf16(sentence))
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)?;
+        self.counts.borrow_mut().forget(word);
+        Ok(())
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.add_words_chunked(&words, &mut |_| {})
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        let result = self.inner.add_words_chunked(words, on_chunk);
+        // Forget even on failure, some words may have been added already
+        let mut counts = self.counts.borrow_mut();
+        for word in words {
+            counts.forget(word);
+        }
+        result
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.remove_words_chunked(words, on_chunk)
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        // The fork has its own session, the counts start over
+        Ok(Box::new(Self::new(
+            self.inner.fork()?,
+            self.counts.borrow().threshold,
+        )))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.inner.reset_session()?;
+        self.counts.borrow_mut().clear();
+        Ok(())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.inner.custom_dictionaries()
+    }
+
+    fn fingerprint(&self, state: &mut dyn Hasher) {
+        self.inner.fingerprint(state)
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn set_dictionary_enabled(&mut self, id: &str, enabled: bool) -> bool {
+        self.inner.set_dictionary_enabled(id, enabled)
+    }
+
+    fn clear_cache(&self) {
+        self.inner.clear_cache()
+    }
+
+    fn cache_stats(&self) -> CacheStats {
+        self.inner.cache_stats()
+    }
+
+    fn proposed_additions(&self) -> Vec<(String, usize)> {
+        self.counts.borrow().proposals()
+    }
+
+    fn dismiss_proposal(&self, word: &str) {
+        self.counts.borrow_mut().dismiss(word);
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        self.inner.set_language(language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proposals() {
+        let mut counts = WordCounts::new(2);
+        for word in [
+            "napi", "napi", "tset", "napi", "ejaan", "ejaan", "ejaan", "tset",
+        ] {
+            counts.record(word);
+        }
+        assert_eq!(
+            counts.proposals(),
+            vec![("ejaan".to_string(), 3), ("napi".to_string(), 3)]
+        );
+
+        counts.forget("napi");
+        counts.dismiss("ejaan");
+        counts.record("ejaan");
+        assert!(counts.proposals().is_empty());
+
+        counts.clear();
+        for _ in 0..3 {
+            counts.record("ejaan");
+        }
+        assert_eq!(counts.proposals(), vec![("ejaan".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_record_at() {
+        let mut counts = WordCounts::new(1);
+        for occurrence in [1, 2, 1, 2, 1] {
+            counts.record_at("napi", occurrence);
+        }
+        assert_eq!(counts.proposals(), vec![("napi".to_string(), 2)]);
+
+        counts.clear();
+        counts.record_at("napi", 1);
+        assert!(counts.proposals().is_empty());
+    }
+}
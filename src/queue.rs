@@ -0,0 +1,165 @@
+//! A fair call queue to limit concurrent calls into the native spell checkers.
+//!
+//! Callers are served in the order they arrive, with at most `concurrency` calls running at once.
+
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+use crate::utils::{EjaanError, Error};
+
+#[derive(Debug)]
+struct QueueState {
+    /// The ticket given to the next caller.
+    next_ticket: u64,
+    /// The ticket allowed to start next.
+    next_start: u64,
+    /// The number of calls currently running.
+    active: usize,
+    /// The maximum number of calls running at once.
+    concurrency: usize,
+}
+
+#[derive(Debug)]
+pub struct CallQueue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+}
+
+/// A running call, the slot is released when this is dropped.
+pub struct QueuePermit<'a> {
+    queue: &'a CallQueue,
+}
+
+impl CallQueue {
+    /// Create a new call queue with the given concurrency.
+    pub const fn new(concurrency: usize) -> Self {
+        CallQueue {
+            state: Mutex::new(QueueState {
+                next_ticket: 0,
+                next_start: 0,
+                active: 0,
+                concurrency: if concurrency == 0 { 1 } else { concurrency },
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn state(&self) -> EjaanError<MutexGuard<'_, QueueState>> {
+        self.state
+            .lock()
+            .map_err(|_| Error::new("Call queue is unusable after a previous panic"))
+    }
+
+    /// Change the maximum number of calls running at once.
+    pub fn set_concurrency(&self, concurrency: usize) -> EjaanError<()> {
+        let mut state = self.state()?;
+        state.concurrency = concurrency.max(1);
+        self.changed.notify_all();
+        Ok(())
+    }
+
+    /// Wait for our turn in the queue.
+    pub fn acquire(&self) -> EjaanError<QueuePermit<'_>> {
+        let mut state = self.state()?;
+        let ticket = state.next_ticket;
+        state.next_ticket += 1;
+
+        while ticket != state.next_start || state.active >= state.concurrency {
+            state = self
+                .changed
+                .wait(state)
+                .map_err(|_| Error::new("Call queue is unusable after a previous panic"))?;
+        }
+
+        state.next_start += 1;
+        state.active += 1;
+        // Let the next caller check if it can start as well
+        self.changed.notify_all();
+
+        Ok(QueuePermit { queue: self })
+    }
+}
+
+impl Drop for QueuePermit<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.queue.state.lock() {
+            state.active -= 1;
+        }
+        self.queue.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_concurrency_limit() {
+        let queue = Arc::new(CallQueue::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let queue = queue.clone();
+                let running = running.clone();
+                let peak = peak.clone();
+                thread::spawn(move || {
+                    let _permit = queue.acquire().unwrap();
+                    let current = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(current, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert!(
+            peak.load(Ordering::SeqCst) <= 2,
+            "Expected at most 2 calls running at once"
+        );
+    }
+
+    #[test]
+    fn test_fifo_order() {
+        let queue = Arc::new(CallQueue::new(1));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // Hold the queue so all the other callers are waiting in line
+        let permit = queue.acquire().unwrap();
+        let handles: Vec<_> = (0..4)
+            .map(|index| {
+                let thread_queue = queue.clone();
+                let order = order.clone();
+                let handle = thread::spawn(move || {
+                    let _permit = thread_queue.acquire().unwrap();
+                    order.lock().unwrap().push(index);
+                });
+                // Make sure the callers take their tickets in order
+                while queue.state.lock().unwrap().next_ticket < index as u64 + 2 {
+                    thread::yield_now();
+                }
+                handle
+            })
+            .collect();
+        drop(permit);
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2, 3]);
+    }
+}
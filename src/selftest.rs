@@ -0,0 +1,179 @@
+//! A self-test of the native spell checker with known correct and misspelled words.
+//!
+//! The probes skip the layers of this library (word lists, caches, common words),
+//! so a failing probe points at the native spell checker or its dictionary.
+
+use std::time::{Duration, Instant};
+
+use crate::SpellCheckerImpl;
+
+/// The correct and misspelled probe words, keyed by the primary language subtag.
+///
+/// The correct words are not in the bundled common words, which would skip the native spell checker.
+const PROBES: &[(&str, [&str; 2], [&str; 2])] = &[
+    (
+        "de",
+        ["Wörterbuch", "Rechtschreibung"],
+        ["Wörterbuc", "Rechtschreibnug"],
+    ),
+    ("en", ["spelling", "dictionary"], ["speling", "dictionery"]),
+    (
+        "es",
+        ["diccionario", "ortografía"],
+        ["diccionaro", "ortografya"],
+    ),
+    (
+        "fr",
+        ["dictionnaire", "orthographe"],
+        ["dictionaire", "ortografe"],
+    ),
+    ("id", ["kamus", "ejaan"], ["kamuss", "ejaaan"]),
+    (
+        "it",
+        ["dizionario", "ortografia"],
+        ["dizionaryo", "ortograffia"],
+    ),
+    (
+        "nl",
+        ["woordenboek", "toetsenbord"],
+        ["woordenbok", "toetsenbrd"],
+    ),
+    (
+        "pt",
+        ["dicionário", "ortografia"],
+        ["dicionáro", "ortograffia"],
+    ),
+];
+
+/// A word which is misspelled in every language.
+const GIBBERISH: &str = "qzxwvkj";
+
+/// The result of a single probe.
+#[derive(Debug, Clone)]
+pub struct Probe {
+    /// The checked word or sentence.
+    pub input: String,
+    /// Is the input expected to be spelled correctly.
+    pub expected: bool,
+    pub passed: bool,
+    /// The error of the native spell checker, if the check failed.
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+/// The results of the self-test.
+#[derive(Debug, Clone)]
+pub struct Report {
+    /// The native spell checker backend, if it can be queried.
+    pub backend: Option<String>,
+    pub language: Option<String>,
+    pub probes: Vec<Probe>,
+    pub duration: Duration,
+}
+
+impl Report {
+    /// Did every probe pass.
+    pub fn passed(&self) -> bool {
+        !self.probes.is_empty() && self.probes.iter().all(|probe| probe.passed)
+    }
+}
+
+/// Get the probe words for the language, only the gibberish word for the other languages.
+fn probes(language: Option<&str>) -> (Vec<&'static str>, Vec<&'static str>) {
+    let primary = language
+        .and_then(|language| language.split(['-', '_']).next())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match PROBES.iter().find(|(language, _, _)| *language == primary) {
+        Some((_, correct, misspelled)) => {
+            let mut misspelled = misspelled.to_vec();
+            misspelled.push(GIBBERISH);
+            (correct.to_vec(), misspelled)
+        }
+        None => (Vec::new(), vec![GIBBERISH]),
+    }
+}
+
+/// Run the probes through the native spell checker.
+pub fn run(checker: &dyn SpellCheckerImpl) -> Report {
+    let started = Instant::now();
+    let native = checker.native();
+    let backend = native
+        .dictionary_info()
+        .ok()
+        .map(|info| info.backend().to_string());
+    let language = native.get_language().ok().flatten();
+    let (correct, misspelled) = probes(language.as_deref());
+
+    let mut results = Vec::new();
+    let words = correct
+        .iter()
+        .map(|word| (*word, true))
+        .chain(misspelled.iter().map(|word| (*word, false)));
+    for (word, expected) in words {
+        let start = Instant::now();
+        let result = native.check_word(word);
+        results.push(Probe {
+            input: word.to_string(),
+            expected,
+            passed: result.as_ref().is_ok_and(|correct| *correct == expected),
+            error: result.err().map(|e| e.message().to_string()),
+            duration: start.elapsed(),
+        });
+    }
+
+    // The word breaking of the native spell checker must flag only the misspelled word
+    let sentence = correct
+        .iter()
+        .copied()
+        .chain(std::iter::once(GIBBERISH))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let start = Instant::now();
+    let result = native.check_sentences(&sentence);
+    results.push(Probe {
+        input: sentence,
+        expected: false,
+        passed: result
+            .as_ref()
+            .is_ok_and(|tokens| tokens.len() == 1 && tokens[0].word() == GIBBERISH),
+        error: result.err().map(|e| e.message().to_string()),
+        duration: start.elapsed(),
+    });
+
+    Report {
+        backend,
+        language,
+        probes: results,
+        duration: started.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stubs::StubSpellChecker;
+
+    #[test]
+    fn test_probes() {
+        let (correct, misspelled) = probes(Some("en_US"));
+        assert_eq!(correct, ["spelling", "dictionary"]);
+        assert_eq!(misspelled, ["speling", "dictionery", GIBBERISH]);
+
+        let (correct, misspelled) = probes(Some("ja-JP"));
+        assert!(correct.is_empty());
+        assert_eq!(misspelled, [GIBBERISH]);
+        assert_eq!(probes(None).1, [GIBBERISH]);
+    }
+
+    #[test]
+    fn test_unavailable_backend() {
+        let report = run(&StubSpellChecker::new());
+        assert_eq!(report.backend, None);
+        assert_eq!(report.language, None);
+        // The gibberish word and the sentence
+        assert_eq!(report.probes.len(), 2);
+        assert!(!report.passed());
+        assert!(report.probes.iter().all(|probe| probe.error.is_some()));
+    }
+}
@@ -0,0 +1,55 @@
+//! Snowball stemmers, the fallback of `stem()` for the words missing from the dictionaries.
+
+use rust_stemmers::{Algorithm, Stemmer};
+
+use crate::casing::Casing;
+
+/// Get the Snowball algorithm of the language, by its primary subtag.
+fn algorithm(language: &str) -> Option<Algorithm> {
+    let primary = language
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let algorithm = match primary.as_str() {
+        "ar" => Algorithm::Arabic,
+        "da" => Algorithm::Danish,
+        "de" => Algorithm::German,
+        "el" => Algorithm::Greek,
+        "en" => Algorithm::English,
+        "es" => Algorithm::Spanish,
+        "fi" => Algorithm::Finnish,
+        "fr" => Algorithm::French,
+        "hu" => Algorithm::Hungarian,
+        "it" => Algorithm::Italian,
+        "nb" | "nn" | "no" => Algorithm::Norwegian,
+        "nl" => Algorithm::Dutch,
+        "pt" => Algorithm::Portuguese,
+        "ro" => Algorithm::Romanian,
+        "ru" => Algorithm::Russian,
+        "sv" => Algorithm::Swedish,
+        "ta" => Algorithm::Tamil,
+        "tr" => Algorithm::Turkish,
+        _ => return None,
+    };
+    Some(algorithm)
+}
+
+/// Stem the lowercase word with the Snowball stemmer of the language, if there is one.
+pub fn stem(word: &str, language: &str) -> Option<String> {
+    let stemmer = Stemmer::create(algorithm(language)?);
+    let word = Casing::for_language(Some(language)).lowercase(word);
+    Some(stemmer.stem(&word).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("Running", "en-US").as_deref(), Some("run"));
+        assert_eq!(stem("maisons", "fr_FR").as_deref(), Some("maison"));
+        assert_eq!(stem("ejaan", "id-ID"), None);
+    }
+}
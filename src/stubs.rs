@@ -1,6 +1,13 @@
-//! Fake spell checker implementation for unsupported platforms.
+//! Fake spell checker implementation for unsupported platforms, and the spell checker
+//! of the `dictionaryFallback` option checking with the loaded dictionaries only.
 
-use crate::SpellCheckerImpl;
+use std::{cell::RefCell, collections::HashSet};
+
+use crate::{
+    SpellCheckerImpl,
+    tokenizer::Tokenizer,
+    utils::{Capabilities, DictionaryInfo, DictionaryStorage, EjaanError, TokenWithSuggestions},
+};
 
 pub struct StubSpellChecker;
 
@@ -40,6 +47,30 @@ impl SpellCheckerImpl for StubSpellChecker {
         Err(crate::utils::Error::unimplemented())
     }
 
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        None
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self
+    }
+
+    fn fork(&self) -> crate::utils::EjaanError<Box<dyn SpellCheckerImpl>> {
+        Err(crate::utils::Error::unimplemented())
+    }
+
+    fn reset_session(&mut self) -> crate::utils::EjaanError<()> {
+        Err(crate::utils::Error::unimplemented())
+    }
+
+    fn dictionary_info(&self) -> crate::utils::EjaanError<crate::utils::DictionaryInfo> {
+        Err(crate::utils::Error::unimplemented())
+    }
+
+    fn capabilities(&self) -> crate::utils::EjaanError<crate::utils::Capabilities> {
+        Err(crate::utils::Error::unimplemented())
+    }
+
     fn get_available_languages(&self) -> crate::utils::EjaanError<Vec<String>> {
         Err(crate::utils::Error::unimplemented())
     }
@@ -52,3 +83,154 @@ impl SpellCheckerImpl for StubSpellChecker {
         Err(crate::utils::Error::unimplemented())
     }
 }
+
+/// A spell checker flagging every word, for the word lists to accept the words they know.
+///
+/// This replaces the native spell checker with the `dictionaryFallback` option when it is
+/// unavailable, so only the words of the loaded dictionaries and the added words are accepted,
+/// without any suggestions.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub struct WordListSpellChecker {
+    tokenizer: Tokenizer,
+    language: Option<String>,
+    /// The words added in this session.
+    words: RefCell<HashSet<String>>,
+}
+
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+impl WordListSpellChecker {
+    pub fn new() -> Self {
+        WordListSpellChecker {
+            tokenizer: Tokenizer::new(),
+            language: None,
+            words: RefCell::default(),
+        }
+    }
+}
+
+impl SpellCheckerImpl for WordListSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        Ok(self.words.borrow().contains(word))
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let words = self.words.borrow();
+        Ok(self
+            .tokenizer
+            .words(sentence)
+            .filter(|word| !words.contains(word.text))
+            .map(|word| {
+                TokenWithSuggestions::new(word.to_token(), Vec::new())
+                    .with_language(self.language.clone())
+            })
+            .collect())
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // Unpaired surrogates are replaced by a single code unit, the offsets are kept
+        self.check_sentences(&String::from_utf16_lossy(sentence))
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.words.borrow_mut().insert(word.to_string());
+        Ok(())
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.words.borrow_mut().extend(words);
+        Ok(())
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.words.borrow_mut().remove(word);
+        Ok(())
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        let mut added = self.words.borrow_mut();
+        for word in &words {
+            added.remove(word);
+        }
+        Ok(())
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        None
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        let mut fork = Self::new();
+        fork.language = self.language.clone();
+        Ok(Box::new(fork))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.words.borrow_mut().clear();
+        Ok(())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        Ok(DictionaryInfo::new(
+            "wordlist",
+            DictionaryStorage::Session,
+            None,
+            false,
+        ))
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        Ok(Capabilities::new(true, false))
+    }
+
+    /// There are no installed languages, the dictionaries cover their own languages.
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        Ok(self.language.clone())
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        self.language = Some(language.to_string());
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use super::*;
+    use crate::{
+        dictionaries::{DictionariesSpellChecker, WordList},
+        utils::CheckOptions,
+    };
+
+    #[test]
+    fn test_word_lists() {
+        let lists = Arc::new(RwLock::new(vec![WordList::parse("hello\nworld\n")]));
+        let checker = DictionariesSpellChecker::new(Box::new(WordListSpellChecker::new()), lists);
+        assert!(checker.check_word("hello").unwrap());
+        assert!(!checker.check_word("napi").unwrap());
+
+        let tokens = checker.check_sentences("Hello napi wrold").unwrap();
+        let words: Vec<&str> = tokens.iter().map(|token| token.word()).collect();
+        assert_eq!(words, ["napi", "wrold"]);
+
+        // The accepted words do not count towards the results
+        let options = CheckOptions::new().with_max_results(Some(1));
+        let tokens = checker
+            .check_sentences_with("hello world wrold", &options)
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].word(), "wrold");
+
+        checker.add_word("napi").unwrap();
+        assert!(checker.check_sentences("napi").unwrap().is_empty());
+    }
+}
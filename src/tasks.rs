@@ -0,0 +1,561 @@
+//! Background tasks running on the libuv thread pool.
+
+use std::{
+    fs,
+    hash::{DefaultHasher, Hasher},
+    path::PathBuf,
+    sync::{
+        Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use napi::{
+    Env, Task,
+    bindgen_prelude::Either,
+    threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
+};
+
+use crate::{
+    JsDiagnostic, JsFileResult, JsLintReport, JsSuggestion, JsTimedSuggestions, JsTimings,
+    SharedSpellChecker, SpellCheckerImpl, Timed, WORDS_CHUNK_SIZE,
+    dictionaries::{self, SharedWordLists},
+    diskcache::DiskCache,
+    download,
+    formats::{self, Format, Prepared},
+    lock_backend, ndjson,
+    timings::Phase,
+    utils::{CheckOptions, Diagnostic, EjaanError, Error, TokenWithSuggestions},
+};
+
+/// The batch operation to run on the words.
+pub enum WordsOperation {
+    Add,
+    Remove,
+}
+
+/// Add or remove a batch of words in the background.
+pub struct WordsTask {
+    inner: SharedSpellChecker,
+    words: Vec<String>,
+    operation: WordsOperation,
+    on_progress: Option<ThreadsafeFunction<(u32, u32), ErrorStrategy::Fatal>>,
+}
+
+impl WordsTask {
+    pub fn new(
+        inner: SharedSpellChecker,
+        words: Vec<String>,
+        operation: WordsOperation,
+        on_progress: Option<ThreadsafeFunction<(u32, u32), ErrorStrategy::Fatal>>,
+    ) -> Self {
+        WordsTask {
+            inner,
+            words,
+            operation,
+            on_progress,
+        }
+    }
+}
+
+impl Task for WordsTask {
+    type Output = ();
+    type JsValue = ();
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let total = self.words.len().try_into().unwrap_or(u32::MAX);
+        let mut done = 0u32;
+        let mut on_chunk = |count: usize| {
+            done = done.saturating_add(count.try_into().unwrap_or(u32::MAX));
+            if let Some(on_progress) = &self.on_progress {
+                on_progress.call((done, total), ThreadsafeFunctionCallMode::NonBlocking);
+            }
+        };
+
+        // Lock the spell checker for each chunk only, the other calls run between the chunks
+        for chunk in self.words.chunks(WORDS_CHUNK_SIZE) {
+            let backend = lock_backend(&self.inner)?;
+            match self.operation {
+                WordsOperation::Add => backend.add_words_chunked(chunk, &mut on_chunk)?,
+                WordsOperation::Remove => backend.remove_words_chunked(chunk, &mut on_chunk)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&mut self, _env: Env, _output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(())
+    }
+}
+
+/// Check the sentences in the background.
+pub struct CheckTask {
+    inner: SharedSpellChecker,
+    sentences: String,
+    options: CheckOptions,
+    /// The duration of the check in the background, without the marshalling.
+    elapsed: Duration,
+}
+
+impl CheckTask {
+    pub fn new(inner: SharedSpellChecker, sentences: String, options: CheckOptions) -> Self {
+        CheckTask {
+            inner,
+            sentences,
+            options,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Task for CheckTask {
+    type Output = Vec<TokenWithSuggestions>;
+    type JsValue = Either<Vec<JsSuggestion>, JsTimedSuggestions>;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let start = Instant::now();
+        let prepared = self.options.timings().time(Phase::Tokenization, || {
+            formats::prepare(&self.sentences, &self.options)
+        })?;
+        let mut backend = lock_backend(&self.inner)?;
+        let tokens = prepared.check(backend.as_mut(), &self.options)?;
+        self.elapsed = start.elapsed();
+        Ok(tokens)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        let start = Instant::now();
+        let suggestions = self.options.timings().time(Phase::Marshalling, || {
+            output.into_iter().map(JsSuggestion::from).collect()
+        });
+        let timings = self
+            .options
+            .timings()
+            .breakdown(self.elapsed + start.elapsed());
+        Ok(JsTimedSuggestions::wrap(suggestions, timings))
+    }
+}
+
+/// Which issues are counted towards the error threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailOn {
+    /// Every issue is counted.
+    Any,
+    /// Only the issues which were not reported in the previous run, from the persistent cache.
+    New,
+}
+
+impl FailOn {
+    /// Parse the mode from its name, `any` or `new`.
+    pub fn parse(name: &str) -> EjaanError<FailOn> {
+        match name.to_ascii_lowercase().as_str() {
+            "any" => Ok(FailOn::Any),
+            "new" => Ok(FailOn::New),
+            _ => Err(Error::new(format!("Unknown failOn mode: {}", name))),
+        }
+    }
+}
+
+/// Stop checking the files once more than `max_errors` issues are found.
+#[derive(Debug, Clone, Copy)]
+pub struct Threshold {
+    pub max_errors: usize,
+    pub fail_on: FailOn,
+}
+
+/// The misspelled words of a checked file.
+struct Checked {
+    tokens: Vec<TokenWithSuggestions>,
+    /// Are the results from the cache.
+    cached: bool,
+    /// The number of misspelled words not reported in the previous run.
+    new: usize,
+}
+
+/// The results of a checked file.
+pub struct FileResult {
+    path: String,
+    tokens: Vec<TokenWithSuggestions>,
+    /// The number of misspelled words, still known after the tokens are streamed.
+    count: usize,
+    /// The number of misspelled words not reported in the previous run.
+    new: usize,
+    /// Are the results from the cache.
+    cached: bool,
+    /// Why the file could not be checked, if any.
+    error: Option<String>,
+}
+
+impl FileResult {
+    fn new(path: &str, result: EjaanError<Checked>) -> Self {
+        match result {
+            Ok(checked) => FileResult {
+                path: path.to_string(),
+                count: checked.tokens.len(),
+                new: checked.new,
+                tokens: checked.tokens,
+                cached: checked.cached,
+                error: None,
+            },
+            Err(e) => FileResult {
+                path: path.to_string(),
+                tokens: Vec::new(),
+                count: 0,
+                new: 0,
+                cached: false,
+                error: Some(e.message().to_string()),
+            },
+        }
+    }
+
+    /// Send one NDJSON record per finding, dropping the tokens instead of keeping them for the report.
+    fn stream(mut self, on_record: &ThreadsafeFunction<String, ErrorStrategy::Fatal>) -> Self {
+        if let Some(error) = &self.error {
+            on_record.call(
+                ndjson::file_error(&self.path, error),
+                ThreadsafeFunctionCallMode::Blocking,
+            );
+        }
+        for token in std::mem::take(&mut self.tokens) {
+            on_record.call(
+                ndjson::finding(&self.path, &Diagnostic::spelling(token)),
+                ThreadsafeFunctionCallMode::Blocking,
+            );
+        }
+        self
+    }
+}
+
+/// Check the files in the background, like a linter.
+pub struct LintTask {
+    inner: SharedSpellChecker,
+    paths: Vec<String>,
+    options: CheckOptions,
+    /// Guess the format of each file from its extension.
+    guess_format: bool,
+    /// The file of the persistent cache, if enabled.
+    cache_location: Option<PathBuf>,
+    /// The number of files checked at once, each worker has its own forked spell checker.
+    concurrency: usize,
+    /// Called with an NDJSON record for each finding, as soon as each file is checked.
+    on_record: Option<ThreadsafeFunction<String, ErrorStrategy::Fatal>>,
+    threshold: Option<Threshold>,
+    /// The number of issues counted towards the threshold so far.
+    counted: AtomicUsize,
+    /// The duration of the checks in the background, without the marshalling.
+    elapsed: Duration,
+    /// The fingerprint of the spell checker, part of the key of each cached file.
+    fingerprint: u64,
+}
+
+impl LintTask {
+    pub fn new(
+        inner: SharedSpellChecker,
+        paths: Vec<String>,
+        options: CheckOptions,
+        guess_format: bool,
+        cache_location: Option<PathBuf>,
+        concurrency: usize,
+        on_record: Option<ThreadsafeFunction<String, ErrorStrategy::Fatal>>,
+    ) -> Self {
+        LintTask {
+            inner,
+            paths,
+            options,
+            guess_format,
+            cache_location,
+            concurrency,
+            on_record,
+            threshold: None,
+            counted: AtomicUsize::new(0),
+            elapsed: Duration::ZERO,
+            fingerprint: 0,
+        }
+    }
+
+    /// Stop checking the remaining files once the threshold is exceeded.
+    pub fn with_threshold(mut self, threshold: Option<Threshold>) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Is the error threshold exceeded by the files checked so far.
+    fn exceeded(&self) -> bool {
+        self.threshold
+            .is_some_and(|threshold| self.counted.load(Ordering::Relaxed) > threshold.max_errors)
+    }
+
+    /// Get the result of a checked file, streaming its findings if requested.
+    fn finish(&self, path: &str, result: EjaanError<Checked>) -> FileResult {
+        let result = FileResult::new(path, result);
+        if let Some(threshold) = &self.threshold {
+            let count = match threshold.fail_on {
+                FailOn::Any => result.count,
+                FailOn::New => result.new,
+            };
+            self.counted.fetch_add(count, Ordering::Relaxed);
+        }
+        match &self.on_record {
+            Some(on_record) => result.stream(on_record),
+            None => result,
+        }
+    }
+
+    /// Check a single file, returning the misspelled words and whether they are cached.
+    fn check_file(
+        &self,
+        path: &str,
+        language: &str,
+        cache: &Mutex<Option<DiskCache>>,
+        check: impl FnOnce(&Prepared, &CheckOptions) -> EjaanError<Vec<TokenWithSuggestions>>,
+    ) -> EjaanError<Checked> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| Error::new(format!("Failed to read {}: {}", path, e)))?;
+        let options = match self.guess_format {
+            true => self
+                .options
+                .clone()
+                .with_format(Format::from_path(path.as_ref())),
+            false => self.options.clone(),
+        };
+
+        let key = DiskCache::key(&text, language, &options, self.fingerprint);
+        if let Some(tokens) = lock_cache(cache)?
+            .as_ref()
+            .and_then(|cache| cache.get(path, key))
+        {
+            return Ok(Checked {
+                tokens: tokens.to_vec(),
+                cached: true,
+                new: 0,
+            });
+        }
+
+        let prepared = options
+            .timings()
+            .time(Phase::Tokenization, || formats::prepare(&text, &options))?;
+        let tokens = check(&prepared, &options)?;
+        let mut cache = lock_cache(cache)?;
+        let new = match cache.as_mut() {
+            Some(cache) => {
+                let new = cache.count_new(path, &tokens);
+                cache.insert(path, key, tokens.clone());
+                new
+            }
+            None => tokens.len(),
+        };
+        Ok(Checked {
+            tokens,
+            cached: false,
+            new,
+        })
+    }
+
+    /// Check the files one at a time with the shared spell checker.
+    fn check_serial(&self, language: &str, cache: &Mutex<Option<DiskCache>>) -> Vec<FileResult> {
+        let mut results = Vec::new();
+        for path in &self.paths {
+            if self.exceeded() {
+                break;
+            }
+            let result = self.check_file(path, language, cache, |prepared, options| {
+                // Only hold the backend for a single file, other calls can run in between
+                prepared.check(lock_backend(&self.inner)?.as_mut(), options)
+            });
+            results.push(self.finish(path, result));
+        }
+        results
+    }
+
+    /// Check the files with a pool of workers, each with its own forked spell checker.
+    fn check_parallel(
+        &self,
+        workers: Vec<Box<dyn SpellCheckerImpl>>,
+        language: &str,
+        cache: &Mutex<Option<DiskCache>>,
+    ) -> EjaanError<Vec<FileResult>> {
+        let next = AtomicUsize::new(0);
+        let mut results = thread::scope(|scope| {
+            let handles: Vec<_> = workers
+                .into_iter()
+                .map(|mut backend| {
+                    let next = &next;
+                    scope.spawn(move || {
+                        let mut results = Vec::new();
+                        while !self.exceeded() {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(path) = self.paths.get(index) else {
+                                break;
+                            };
+                            let result =
+                                self.check_file(path, language, cache, |prepared, options| {
+                                    // The forks bypass the call queue, the pool is already
+                                    // limited by the concurrency of the task, and each fork
+                                    // owns its native objects (Windows) or serializes the calls
+                                    // into the shared one (macOS)
+                                    prepared.check(backend.as_mut(), options)
+                                });
+                            results.push((index, self.finish(path, result)));
+                        }
+                        results
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .map_err(|_| Error::new("A worker panicked while checking the files"))
+                })
+                .collect::<EjaanError<Vec<_>>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        results.sort_by_key(|(index, _)| *index);
+        Ok(results.into_iter().map(|(_, result)| result).collect())
+    }
+}
+
+/// Lock the persistent cache shared by the workers.
+fn lock_cache(cache: &Mutex<Option<DiskCache>>) -> EjaanError<MutexGuard<'_, Option<DiskCache>>> {
+    cache
+        .lock()
+        .map_err(|_| Error::new("The cache is unusable after a previous panic"))
+}
+
+impl Task for LintTask {
+    type Output = Vec<FileResult>;
+    type JsValue = JsLintReport;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let start = Instant::now();
+        let cache = Mutex::new(self.cache_location.as_deref().map(DiskCache::load));
+        let backend = lock_backend(&self.inner)?;
+        let language = backend.get_language()?.unwrap_or_default();
+        let mut fingerprint = DefaultHasher::new();
+        backend.fingerprint(&mut fingerprint);
+        self.fingerprint = fingerprint.finish();
+
+        let workers = self.concurrency.min(self.paths.len());
+        let results = if workers > 1 {
+            let forks = (0..workers)
+                .map(|_| backend.fork())
+                .collect::<EjaanError<Vec<_>>>()?;
+            drop(backend);
+            self.check_parallel(forks, &language, &cache)?
+        } else {
+            drop(backend);
+            self.check_serial(&language, &cache)
+        };
+
+        if let (Some(cache), Some(location)) = (lock_cache(&cache)?.as_ref(), &self.cache_location)
+        {
+            cache.save(location)?;
+        }
+        self.elapsed = start.elapsed();
+        Ok(results)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        let start = Instant::now();
+        // The diagnostics are empty when they were streamed, count them separately
+        let error_count = output.iter().map(|result| result.count).sum::<usize>();
+        let files: Vec<JsFileResult> = self.options.timings().time(Phase::Marshalling, || {
+            output
+                .into_iter()
+                .map(|result| JsFileResult {
+                    path: result.path,
+                    diagnostics: result
+                        .tokens
+                        .into_iter()
+                        .map(|token| JsDiagnostic::from(Diagnostic::spelling(token)))
+                        .collect(),
+                    cached: result.cached,
+                    error: result.error,
+                })
+                .collect()
+        });
+        // The report carries its own timings, the other calls cannot overwrite them
+        let timings = self
+            .options
+            .timings()
+            .breakdown(self.elapsed + start.elapsed())
+            .map(JsTimings::from);
+
+        Ok(JsLintReport {
+            files,
+            error_count: error_count.try_into().unwrap_or(u32::MAX),
+            threshold_exceeded: self.exceeded(),
+            timings,
+        })
+    }
+}
+
+/// Download a dictionary in the background, and add its words to the spell checker.
+pub struct DownloadTask {
+    inner: SharedSpellChecker,
+    lists: SharedWordLists,
+    url: String,
+    sha256: String,
+    languages: Vec<String>,
+    directory: Option<PathBuf>,
+}
+
+impl DownloadTask {
+    pub fn new(
+        inner: SharedSpellChecker,
+        lists: SharedWordLists,
+        url: String,
+        sha256: String,
+        languages: Vec<String>,
+        directory: Option<PathBuf>,
+    ) -> Self {
+        DownloadTask {
+            inner,
+            lists,
+            url,
+            sha256,
+            languages,
+            directory,
+        }
+    }
+}
+
+impl Task for DownloadTask {
+    type Output = PathBuf;
+    type JsValue = String;
+
+    fn compute(&mut self) -> napi::Result<Self::Output> {
+        let directory = match self.directory.take() {
+            Some(directory) => directory,
+            None => download::default_directory()?,
+        };
+        let path = download::download(&self.url, &self.sha256, &directory)?;
+        let mut loaded = dictionaries::load(&path)?;
+        if !self.languages.is_empty() {
+            loaded = loaded
+                .into_iter()
+                .map(|list| list.with_languages(self.languages.clone()))
+                .collect();
+        }
+
+        self.lists
+            .write()
+            .map_err(|_| Error::new("Dictionaries are unusable after a previous panic"))?
+            .extend(loaded);
+        // The cached results were checked without the new words
+        lock_backend(&self.inner)?.clear_cache();
+        Ok(path)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+        Ok(output.to_string_lossy().into_owned())
+    }
+}
@@ -0,0 +1,141 @@
+//! Timing breakdown of the checks, recorded with the `debug` check option.
+//!
+//! The phases are recorded from every layer and the native spell checker, which may run
+//! on another thread (the owner thread on Windows), so the durations are kept in atomics.
+
+use std::{
+    hash::{Hash, Hasher},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// A phase of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Masking the format of the text and splitting the words with the `wordBreak` option.
+    Tokenization,
+    /// Getting the suggestions of the misspelled words from the native spell checker.
+    Suggestions,
+    /// Converting the results for JavaScript.
+    Marshalling,
+}
+
+impl Phase {
+    fn index(self) -> usize {
+        match self {
+            Phase::Tokenization => 0,
+            Phase::Suggestions => 1,
+            Phase::Marshalling => 2,
+        }
+    }
+}
+
+/// The time spent in each phase, in nanoseconds.
+#[derive(Debug, Default)]
+pub struct Timings {
+    nanos: [AtomicU64; 3],
+}
+
+impl Timings {
+    pub fn record(&self, phase: Phase, duration: Duration) {
+        let nanos = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        self.nanos[phase.index()].fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    pub fn get(&self, phase: Phase) -> Duration {
+        Duration::from_nanos(self.nanos[phase.index()].load(Ordering::Relaxed))
+    }
+}
+
+/// The optional timings of a check, shared by the layers and the native spell checker.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder(Option<Arc<Timings>>);
+
+impl Recorder {
+    /// A recorder keeping the timings, the default one only runs the phases.
+    pub fn enabled() -> Self {
+        Recorder(Some(Arc::default()))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Run a phase, recording its duration when enabled.
+    pub fn time<T>(&self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let Some(timings) = &self.0 else {
+            return f();
+        };
+        let start = Instant::now();
+        let result = f();
+        timings.record(phase, start.elapsed());
+        result
+    }
+
+    /// Get the breakdown of a check which took `total`, `None` when disabled.
+    pub fn breakdown(&self, total: Duration) -> Option<Breakdown> {
+        let timings = self.0.as_ref()?;
+        let tokenization = timings.get(Phase::Tokenization);
+        let suggestions = timings.get(Phase::Suggestions);
+        let marshalling = timings.get(Phase::Marshalling);
+        Some(Breakdown {
+            tokenization,
+            check: total.saturating_sub(tokenization + suggestions + marshalling),
+            suggestions,
+            marshalling,
+            total,
+        })
+    }
+}
+
+impl Hash for Recorder {
+    fn hash<H: Hasher>(&self, _state: &mut H) {
+        // The timings never change the results of a check
+    }
+}
+
+/// The time spent in each phase of a check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Breakdown {
+    pub tokenization: Duration,
+    /// Everything else, mostly the native spell checker and the layers of this library.
+    pub check: Duration,
+    pub suggestions: Duration,
+    pub marshalling: Duration,
+    pub total: Duration,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown() {
+        let recorder = Recorder::enabled();
+        let shared = recorder.clone();
+        assert_eq!(recorder.time(Phase::Suggestions, || 42), 42);
+        if let Some(timings) = &shared.0 {
+            timings.record(Phase::Tokenization, Duration::from_millis(2));
+            timings.record(Phase::Tokenization, Duration::from_millis(3));
+        }
+
+        let breakdown = recorder.breakdown(Duration::from_secs(1)).unwrap();
+        assert_eq!(breakdown.tokenization, Duration::from_millis(5));
+        assert_eq!(
+            breakdown.check,
+            Duration::from_secs(1) - breakdown.tokenization - breakdown.suggestions
+        );
+        assert_eq!(breakdown.total, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let recorder = Recorder::default();
+        assert!(!recorder.is_enabled());
+        assert_eq!(recorder.time(Phase::Marshalling, || "done"), "done");
+        assert_eq!(recorder.breakdown(Duration::from_secs(1)), None);
+    }
+}
@@ -0,0 +1,361 @@
+//! The word segmentation shared by all the checks done in Rust.
+//!
+//! Offsets are in UTF-16 code units, the same as the native spell checkers and JavaScript strings.
+
+use std::{
+    hash::{Hash, Hasher},
+    iter::Peekable,
+    str::CharIndices,
+    sync::Arc,
+};
+
+use crate::{
+    SpellCheckerImpl,
+    timings::Phase,
+    utils::{Capabilities, CheckOptions, DictionaryInfo, EjaanError, Token, TokenWithSuggestions},
+};
+
+/// A word found in the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Word<'a> {
+    /// The word itself.
+    pub text: &'a str,
+    /// The start index of the word, in UTF-16 code units.
+    pub start: usize,
+    /// The end index of the word (exclusive), in UTF-16 code units.
+    pub end: usize,
+}
+
+impl Word<'_> {
+    /// Convert into a token, with the inclusive end index.
+    pub fn to_token(self) -> Token {
+        Token::new(
+            self.start,
+            self.end.saturating_sub(1),
+            self.text.to_string(),
+        )
+    }
+}
+
+/// The rules to split a text into words.
+///
+/// By default, a word is a run of letters, and apostrophes are only kept inside a word.
+#[derive(Debug, Clone, Hash)]
+pub struct Tokenizer {
+    /// Extra characters which are always part of a word.
+    word_chars: Vec<char>,
+    /// Characters which are part of a word only between two word characters.
+    join_chars: Vec<char>,
+    /// Characters which always break a word, even letters.
+    break_chars: Vec<char>,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Tokenizer {
+            word_chars: Vec::new(),
+            join_chars: vec!['\'', '’'],
+            break_chars: Vec::new(),
+        }
+    }
+}
+
+impl Tokenizer {
+    pub fn new() -> Self {
+        Tokenizer::default()
+    }
+
+    /// Set the extra characters which are always part of a word, e.g. `’` as a letter.
+    pub fn with_word_chars(mut self, chars: &str) -> Self {
+        self.word_chars = chars.chars().collect();
+        self
+    }
+
+    /// Set the characters which are part of a word only inside of it, e.g. `_` or `.` in identifiers.
+    ///
+    /// This replaces the default apostrophes.
+    pub fn with_join_chars(mut self, chars: &str) -> Self {
+        self.join_chars = chars.chars().collect();
+        self
+    }
+
+    /// Set the characters which always break a word.
+    pub fn with_break_chars(mut self, chars: &str) -> Self {
+        self.break_chars = chars.chars().collect();
+        self
+    }
+
+    /// Check if the char is part of a word.
+    fn is_word_char(&self, c: char) -> bool {
+        if self.break_chars.contains(&c) {
+            return false;
+        }
+        c.is_alphabetic() || self.word_chars.contains(&c)
+    }
+
+    /// Check if the char joins two parts of a word, e.g. the apostrophe in "don't".
+    fn is_joiner(&self, c: char) -> bool {
+        !self.break_chars.contains(&c) && self.join_chars.contains(&c)
+    }
+
+    /// Split the text into words.
+    pub fn words<'a, 't>(&'t self, text: &'a str) -> Words<'a, 't> {
+        Words {
+            tokenizer: self,
+            text,
+            chars: text.char_indices().peekable(),
+            position: 0,
+        }
+    }
+}
+
+/// An iterator over the words of a text.
+pub struct Words<'a, 't> {
+    tokenizer: &'t Tokenizer,
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    /// The UTF-16 offset of the next char.
+    position: usize,
+}
+
+impl Words<'_, '_> {
+    fn advance(&mut self) -> Option<(usize, char)> {
+        let (index, c) = self.chars.next()?;
+        self.position += c.len_utf16();
+        Some((index, c))
+    }
+}
+
+impl<'a> Iterator for Words<'a, '_> {
+    type Item = Word<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip until the start of a word
+        let (start_index, start) = loop {
+            let position = self.position;
+            let (index, c) = self.advance()?;
+            if self.tokenizer.is_word_char(c) {
+                break (index, position);
+            }
+        };
+
+        let mut end_index = self.text.len();
+        while let Some(&(index, c)) = self.chars.peek() {
+            if self.tokenizer.is_word_char(c) {
+                self.advance();
+                continue;
+            }
+
+            if self.tokenizer.is_joiner(c) {
+                // Only keep the joiner if the word continues after it
+                let mut lookahead = self.chars.clone();
+                lookahead.next();
+                if lookahead
+                    .peek()
+                    .is_some_and(|&(_, next)| self.tokenizer.is_word_char(next))
+                {
+                    self.advance();
+                    continue;
+                }
+            }
+
+            end_index = index;
+            break;
+        }
+
+        Some(Word {
+            text: &self.text[start_index..end_index],
+            start,
+            end: self.position,
+        })
+    }
+}
+
+/// A spell checker splitting the text with our tokenizer, then checking each word on its own.
+pub struct TokenizedSpellChecker {
+    inner: Box<dyn SpellCheckerImpl>,
+    tokenizer: Arc<Tokenizer>,
+}
+
+impl TokenizedSpellChecker {
+    pub fn new(inner: Box<dyn SpellCheckerImpl>, tokenizer: Arc<Tokenizer>) -> Self {
+        TokenizedSpellChecker { inner, tokenizer }
+    }
+}
+
+impl SpellCheckerImpl for TokenizedSpellChecker {
+    fn check_word(&self, word: &str) -> EjaanError<bool> {
+        self.inner.check_word(word)
+    }
+
+    fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.check_sentences_with(sentence, &CheckOptions::new())
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let mut tokens = Vec::new();
+        let mut words = self.tokenizer.words(sentence);
+        while let Some(word) = options.timings().time(Phase::Tokenization, || words.next()) {
+            if options.is_full(tokens.len()) {
+                break;
+            }
+
+            // Only ask for the remaining results
+            let remaining = options
+                .max_results()
+                .map(|max_results| max_results - tokens.len());
+            let word_options = options.clone().with_max_results(remaining);
+            let results = self.inner.check_sentences_with(word.text, &word_options)?;
+            tokens.extend(results.into_iter().map(|token| token.offset(word.start)));
+        }
+        Ok(tokens)
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        // Unpaired surrogates are replaced by a single code unit, the offsets are kept
+        self.check_sentences(&String::from_utf16_lossy(sentence))
+    }
+
+    fn add_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.add_word(word)
+    }
+
+    fn remove_word(&self, word: &str) -> EjaanError<()> {
+        self.inner.remove_word(word)
+    }
+
+    fn add_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.add_words(words)
+    }
+
+    fn add_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.add_words_chunked(words, on_chunk)
+    }
+
+    fn remove_words(&self, words: Vec<String>) -> EjaanError<()> {
+        self.inner.remove_words(words)
+    }
+
+    fn remove_words_chunked(
+        &self,
+        words: &[String],
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> EjaanError<()> {
+        self.inner.remove_words_chunked(words, on_chunk)
+    }
+
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        self.inner.native_handle()
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self.inner.native()
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        Ok(Box::new(Self::new(
+            self.inner.fork()?,
+            self.tokenizer.clone(),
+        )))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        self.inner.reset_session()
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        self.inner.dictionary_info()
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        self.inner.capabilities()
+    }
+
+    fn similar_words(&self, word: &str, max_distance: usize) -> Vec<(String, usize)> {
+        self.inner.similar_words(word, max_distance)
+    }
+
+    fn dictionary_stems(&self, word: &str, language: &str) -> Vec<String> {
+        self.inner.dictionary_stems(word, language)
+    }
+
+    fn fingerprint(&self, mut state: &mut dyn Hasher) {
+        self.inner.fingerprint(state);
+        self.tokenizer.hash(&mut state);
+    }
+
+    fn get_available_languages(&self) -> EjaanError<Vec<String>> {
+        self.inner.get_available_languages()
+    }
+
+    fn get_language(&self) -> EjaanError<Option<String>> {
+        self.inner.get_language()
+    }
+
+    fn set_language(&mut self, language: &str) -> EjaanError<bool> {
+        self.inner.set_language(language)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(tokenizer: &Tokenizer, text: &'a str) -> Vec<&'a str> {
+        tokenizer.words(text).map(|word| word.text).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        let tokenizer = Tokenizer::new();
+        assert_eq!(
+            texts(&tokenizer, "This is a invalid snetences."),
+            vec!["This", "is", "a", "invalid", "snetences"]
+        );
+        assert_eq!(
+            texts(&tokenizer, "Don't 'quote' me’"),
+            vec!["Don't", "quote", "me"]
+        );
+        assert_eq!(
+            texts(&tokenizer, "well-known 123abc"),
+            vec!["well", "known", "abc"]
+        );
+        assert!(texts(&tokenizer, " ... ").is_empty());
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        let tokenizer = Tokenizer::new();
+        let found: Vec<_> = tokenizer.words("😀 snetences").collect();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].start, 3);
+        assert_eq!(found[0].end, 12);
+
+        let token = found[0].to_token();
+        assert_eq!(token.range(), 3..=11);
+        assert_eq!(token.word(), "snetences");
+    }
+
+    #[test]
+    fn test_custom_chars() {
+        let tokenizer = Tokenizer::new().with_join_chars("_.");
+        assert_eq!(
+            texts(&tokenizer, "call my_module.run_fast_ now"),
+            vec!["call", "my_module.run_fast", "now"]
+        );
+
+        let tokenizer = Tokenizer::new().with_word_chars("_");
+        assert_eq!(texts(&tokenizer, "_private"), vec!["_private"]);
+
+        let tokenizer = Tokenizer::new().with_break_chars("'");
+        assert_eq!(texts(&tokenizer, "don't"), vec!["don", "t"]);
+    }
+}
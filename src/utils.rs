@@ -1,8 +1,14 @@
 use std::ops::RangeInclusive;
 
+use crate::{
+    formats::{Format, placeholder::Placeholders},
+    timings::Recorder,
+};
+
 pub type EjaanError<T> = Result<T, Error>;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Token {
     start: usize,
     end: usize,
@@ -10,6 +16,7 @@ pub struct Token {
 }
 
 impl Token {
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
     pub(crate) fn new(start: usize, end: usize, word: String) -> Self {
         Token { start, end, word }
     }
@@ -29,17 +36,75 @@ impl Token {
     pub fn range(&self) -> RangeInclusive<usize> {
         self.start..=self.end
     }
+
+    /// Move the token by the given offset, e.g. from a word into the whole text.
+    pub(crate) fn offset(mut self, by: usize) -> Self {
+        self.start += by;
+        self.end += by;
+        self
+    }
+
+    /// Map the start and end index, e.g. back into a text with some parts removed.
+    pub(crate) fn map_offsets(mut self, map: impl Fn(usize) -> usize) -> Self {
+        self.start = map(self.start);
+        self.end = map(self.end);
+        self
+    }
 }
 
+/// A misspelled token with its suggestions.
+///
+/// When serialized, the shape is the same as the JavaScript `Suggestion` object.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TokenWithSuggestions {
+    #[cfg_attr(feature = "serde", serde(flatten))]
     token: Token,
     suggestions: Vec<String>,
+    /// The language which rejected the word, if known.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    language: Option<String>,
+    /// The other languages which also rejected the word, e.g. the fallback languages.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    also_rejected_by: Vec<String>,
 }
 
 impl TokenWithSuggestions {
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
     pub(crate) fn new(token: Token, suggestions: Vec<String>) -> Self {
-        TokenWithSuggestions { token, suggestions }
+        TokenWithSuggestions {
+            token,
+            suggestions,
+            language: None,
+            also_rejected_by: Vec::new(),
+        }
+    }
+
+    /// Move the token by the given offset, e.g. from a word into the whole text.
+    pub(crate) fn offset(mut self, by: usize) -> Self {
+        self.token = self.token.offset(by);
+        self
+    }
+
+    /// Map the start and end index, e.g. back into a text with some parts removed.
+    pub(crate) fn map_offsets(mut self, map: impl Fn(usize) -> usize) -> Self {
+        self.token = self.token.map_offsets(map);
+        self
+    }
+
+    /// Set the language which rejected the word.
+    #[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+    pub(crate) fn with_language(mut self, language: Option<String>) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Add another language which rejected the word, e.g. a fallback language.
+    pub(crate) fn also_rejected_by(mut self, language: String) -> Self {
+        if self.language.as_ref() != Some(&language) && !self.also_rejected_by.contains(&language) {
+            self.also_rejected_by.push(language);
+        }
+        self
     }
 
     pub fn token(&self) -> &Token {
@@ -49,6 +114,10 @@ impl TokenWithSuggestions {
     pub fn suggestions(&self) -> &[String] {
         &self.suggestions
     }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
 }
 
 impl std::ops::Deref for TokenWithSuggestions {
@@ -59,19 +128,375 @@ impl std::ops::Deref for TokenWithSuggestions {
     }
 }
 
+/// The kind of check which produced a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DiagnosticCategory {
+    /// A misspelled word.
+    Spelling,
+    /// A grammar issue.
+    Grammar,
+    /// A style issue.
+    Style,
+    /// A custom rule defined by the user.
+    CustomRule,
+}
+
+impl DiagnosticCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCategory::Spelling => "spelling",
+            DiagnosticCategory::Grammar => "grammar",
+            DiagnosticCategory::Style => "style",
+            DiagnosticCategory::CustomRule => "custom-rule",
+        }
+    }
+}
+
+/// How important a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        }
+    }
+}
+
+/// An issue found in the text, with its category and severity.
+///
+/// When serialized, the shape is the same as the JavaScript `Diagnostic` object.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Diagnostic {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    token: TokenWithSuggestions,
+    category: DiagnosticCategory,
+    severity: Severity,
+    /// The language which produced the diagnostic, if known.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    language: Option<String>,
+    /// All the languages which rejected the word.
+    #[cfg_attr(feature = "serde", serde(rename = "rejectedBy"))]
+    rejected_by: Vec<String>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(
+        token: TokenWithSuggestions,
+        category: DiagnosticCategory,
+        severity: Severity,
+    ) -> Self {
+        let language = token.language.clone();
+        Diagnostic {
+            rejected_by: language
+                .iter()
+                .chain(&token.also_rejected_by)
+                .cloned()
+                .collect(),
+            token,
+            category,
+            severity,
+            language,
+        }
+    }
+
+    /// Create a diagnostic for a misspelled word.
+    ///
+    /// The language which rejected the word is used as the language of the diagnostic.
+    pub(crate) fn spelling(token: TokenWithSuggestions) -> Self {
+        Diagnostic::new(token, DiagnosticCategory::Spelling, Severity::Warning)
+    }
+
+    pub fn token(&self) -> &TokenWithSuggestions {
+        &self.token
+    }
+
+    pub fn category(&self) -> DiagnosticCategory {
+        self.category
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn rejected_by(&self) -> &[String] {
+        &self.rejected_by
+    }
+
+    pub fn into_token(self) -> TokenWithSuggestions {
+        self.token
+    }
+}
+
+impl std::ops::Deref for Diagnostic {
+    type Target = TokenWithSuggestions;
+
+    fn deref(&self) -> &Self::Target {
+        &self.token
+    }
+}
+
+/// Options for checking the sentences.
+#[derive(Debug, Clone, Default, Hash)]
+pub struct CheckOptions {
+    max_results: Option<usize>,
+    format: Format,
+    check_keys: bool,
+    skip_emoji: bool,
+    placeholders: Placeholders,
+    cache: bool,
+    timings: Recorder,
+}
+
+impl CheckOptions {
+    pub fn new() -> Self {
+        CheckOptions::default()
+    }
+
+    /// Stop checking once this many misspelled words are found.
+    pub fn with_max_results(mut self, max_results: Option<usize>) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    pub fn max_results(&self) -> Option<usize> {
+        self.max_results
+    }
+
+    /// Set the format of the checked text, only the prose inside of it is checked.
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Also check the keys of the structured formats, e.g. JSON and YAML.
+    pub fn with_check_keys(mut self, check_keys: bool) -> Self {
+        self.check_keys = check_keys;
+        self
+    }
+
+    pub fn check_keys(&self) -> bool {
+        self.check_keys
+    }
+
+    /// Skip the emoji shortcodes (e.g. `:thumbsup:`) and the raw emoji sequences.
+    pub fn with_skip_emoji(mut self, skip_emoji: bool) -> Self {
+        self.skip_emoji = skip_emoji;
+        self
+    }
+
+    pub fn skip_emoji(&self) -> bool {
+        self.skip_emoji
+    }
+
+    /// Skip the template placeholders, e.g. `{name}` or `%s`.
+    pub fn with_placeholders(mut self, placeholders: Placeholders) -> Self {
+        self.placeholders = placeholders;
+        self
+    }
+
+    pub fn placeholders(&self) -> &Placeholders {
+        &self.placeholders
+    }
+
+    /// Reuse the results of the unchanged paragraphs from the previous checks.
+    pub fn with_cache(mut self, cache: bool) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn cache(&self) -> bool {
+        self.cache
+    }
+
+    /// Record the time spent in each phase of the check, see `timings::Phase`.
+    pub fn with_timings(mut self, timings: Recorder) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    pub fn timings(&self) -> &Recorder {
+        &self.timings
+    }
+
+    /// Check if enough misspelled words are found to stop checking.
+    pub fn is_full(&self, count: usize) -> bool {
+        self.max_results
+            .is_some_and(|max_results| count >= max_results)
+    }
+}
+
+/// Where the custom words added to the spell checker are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum DictionaryStorage {
+    /// The system-level user dictionary, shared with other applications.
+    System,
+    /// The session ignore list of the spell checker instance.
+    Session,
+    /// The overlay dictionary maintained by the crate.
+    Overlay,
+}
+
+impl DictionaryStorage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DictionaryStorage::System => "system",
+            DictionaryStorage::Session => "session",
+            DictionaryStorage::Overlay => "overlay",
+        }
+    }
+}
+
+/// Information about where the custom words are stored.
+///
+/// When serialized, the shape is the same as the JavaScript `DictionaryInfo` object.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DictionaryInfo {
+    backend: String,
+    storage: DictionaryStorage,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    location: Option<String>,
+    persistent: bool,
+}
+
+impl DictionaryInfo {
+    pub(crate) fn new<T: Into<String>>(
+        backend: T,
+        storage: DictionaryStorage,
+        location: Option<String>,
+        persistent: bool,
+    ) -> Self {
+        DictionaryInfo {
+            backend: backend.into(),
+            storage,
+            location,
+            persistent,
+        }
+    }
+
+    pub fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    pub fn storage(&self) -> DictionaryStorage {
+        self.storage
+    }
+
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    pub fn persistent(&self) -> bool {
+        self.persistent
+    }
+}
+
+/// The optional features supported by the spell checker backend.
+///
+/// When serialized, the shape is the same as the JavaScript `Capabilities` object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Capabilities {
+    remove_words: bool,
+    language_detection: bool,
+}
+
+impl Capabilities {
+    pub(crate) fn new(remove_words: bool, language_detection: bool) -> Self {
+        Capabilities {
+            remove_words,
+            language_detection,
+        }
+    }
+
+    /// Can the added words be removed again.
+    pub fn remove_words(&self) -> bool {
+        self.remove_words
+    }
+
+    /// Is the language of the text identified automatically when no language is set.
+    pub fn language_detection(&self) -> bool {
+        self.language_detection
+    }
+}
+
+/// The kind of error, exposed to JavaScript as the `code` of the thrown error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A generic failure coming from the spell checker.
+    Generic,
+    /// The native spell checking backend is not available on this system.
+    BackendUnavailable,
+}
+
+impl AsRef<str> for ErrorKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            // Keep the same code as the default napi status
+            ErrorKind::Generic => "GenericFailure",
+            ErrorKind::BackendUnavailable => "BackendUnavailable",
+        }
+    }
+}
+
 /// Error type for the spell checker
 #[derive(Debug, Clone)]
 pub struct Error {
+    kind: ErrorKind,
     message: String,
 }
 
 impl Error {
     pub fn new<T: Into<String>>(message: T) -> Self {
+        Error::with_kind(ErrorKind::Generic, message)
+    }
+
+    pub fn with_kind<T: Into<String>>(kind: ErrorKind, message: T) -> Self {
         Error {
+            kind,
             message: message.into(),
         }
     }
 
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
     pub fn message(&self) -> &str {
         &self.message
     }
@@ -97,6 +522,12 @@ impl From<Error> for napi::Error {
     }
 }
 
+impl From<Error> for napi::Error<ErrorKind> {
+    fn from(err: Error) -> Self {
+        napi::Error::new(err.kind, err.message)
+    }
+}
+
 #[cfg(target_os = "windows")]
 impl From<windows::core::Error> for Error {
     fn from(err: windows::core::Error) -> Self {
@@ -107,3 +538,58 @@ impl From<windows::core::Error> for Error {
         ))
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_token_with_suggestions() {
+        let token = TokenWithSuggestions::new(
+            Token::new(18, 26, "snetences".to_string()),
+            vec!["sentences".to_string()],
+        );
+
+        assert_eq!(
+            serde_json::to_string(&token).unwrap(),
+            r#"{"start":18,"end":26,"word":"snetences","suggestions":["sentences"]}"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_diagnostic() {
+        let diagnostic = Diagnostic::spelling(
+            TokenWithSuggestions::new(
+                Token::new(18, 26, "snetences".to_string()),
+                vec!["sentences".to_string()],
+            )
+            .with_language(Some("en-US".to_string()))
+            .also_rejected_by("id-ID".to_string()),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&diagnostic).unwrap(),
+            r#"{"start":18,"end":26,"word":"snetences","suggestions":["sentences"],"category":"spelling","severity":"warning","language":"en-US","rejectedBy":["en-US","id-ID"]}"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_capabilities() {
+        let capabilities = Capabilities::new(false, true);
+
+        assert_eq!(
+            serde_json::to_string(&capabilities).unwrap(),
+            r#"{"removeWords":false,"languageDetection":true}"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_dictionary_info() {
+        let info = DictionaryInfo::new("NSSpellChecker", DictionaryStorage::Overlay, None, false);
+
+        assert_eq!(
+            serde_json::to_string(&info).unwrap(),
+            r#"{"backend":"NSSpellChecker","storage":"overlay","persistent":false}"#
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Reusable UTF-16 buffers for the native spell checker APIs.
+//!
+//! The native APIs take null-terminated wide strings, encoding into a reused buffer
+//! avoids an allocation for every checked word in as-you-type scenarios.
+
+#[derive(Debug)]
+pub struct WideBuffer {
+    /// The encoded text, always followed by a null terminator.
+    buffer: Vec<u16>,
+}
+
+impl WideBuffer {
+    /// Create a new empty wide string buffer.
+    pub fn new() -> Self {
+        WideBuffer { buffer: vec![0] }
+    }
+
+    /// Replace the content with the UTF-16 encoding of the text.
+    pub fn set_str(&mut self, text: &str) {
+        self.buffer.clear();
+        self.buffer.extend(text.encode_utf16());
+        self.buffer.push(0);
+    }
+
+    /// Replace the content with the UTF-16 code units.
+    pub fn set_wide(&mut self, wide: &[u16]) {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(wide);
+        self.buffer.push(0);
+    }
+
+    /// Get the code units, without the null terminator.
+    pub fn as_wide(&self) -> &[u16] {
+        self.buffer.split_last().map_or(&[], |(_, wide)| wide)
+    }
+
+    /// Get the pointer to the null-terminated wide string.
+    ///
+    /// The pointer is invalidated when the content is replaced.
+    pub fn as_ptr(&self) -> *const u16 {
+        self.buffer.as_ptr()
+    }
+}
+
+impl Default for WideBuffer {
+    fn default() -> Self {
+        WideBuffer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_null_terminated() {
+        let mut buffer = WideBuffer::new();
+        assert!(buffer.as_wide().is_empty());
+
+        buffer.set_str("“Tset”");
+        assert_eq!(String::from_utf16(buffer.as_wide()).unwrap(), "“Tset”");
+        assert_eq!(unsafe { *buffer.as_ptr().add(buffer.as_wide().len()) }, 0);
+
+        let word = buffer.as_wide()[1..5].to_vec();
+        buffer.set_wide(&word);
+        assert_eq!(String::from_utf16(buffer.as_wide()).unwrap(), "Tset");
+        assert_eq!(unsafe { *buffer.as_ptr().add(4) }, 0);
+    }
+}
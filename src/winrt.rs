@@ -1,38 +1,89 @@
 //! Windows-specific implementation of the spell checker.
 
+use std::{cell::RefCell, marker::PhantomData};
+
 use windows::{
     Win32::{
-        Foundation::S_OK,
+        Foundation::{CLASS_E_CLASSNOTAVAILABLE, E_NOINTERFACE, REGDB_E_CLASSNOTREG, S_OK},
         Globalization::{
             CORRECTIVE_ACTION_DELETE, CORRECTIVE_ACTION_GET_SUGGESTIONS, CORRECTIVE_ACTION_NONE,
-            CORRECTIVE_ACTION_REPLACE, GetUserDefaultLocaleName, ISpellChecker2,
+            CORRECTIVE_ACTION_REPLACE, GetUserDefaultLocaleName, ISpellChecker, ISpellChecker2,
             ISpellCheckerFactory, LOCALE_NAME_SYSTEM_DEFAULT, SpellCheckerFactory,
         },
         System::Com::{
             CLSCTX_ALL, COINIT_MULTITHREADED, CoCreateInstance, CoInitializeEx, CoTaskMemFree,
+            CoUninitialize,
         },
     },
-    core::{HSTRING, Interface, PCWSTR, PWSTR},
+    core::{Interface, PCWSTR, PWSTR},
 };
 
 use crate::{
     SpellCheckerImpl,
-    utils::{EjaanError, Token, TokenWithSuggestions},
+    timings::Phase,
+    utils::{
+        Capabilities, CheckOptions, DictionaryInfo, DictionaryStorage, EjaanError, Error,
+        ErrorKind, Token, TokenWithSuggestions,
+    },
+    wide::WideBuffer,
 };
 
 pub struct WindowsSpellChecker {
     inner: ISpellCheckerFactory,
-    checker: ISpellChecker2,
+    checker: NativeChecker,
     locale: String,
+    /// Reused wide string buffers for the hot path.
+    buffers: RefCell<WideBuffers>,
+}
+
+/// The native spell checker, with the `ISpellChecker2` interface when available.
+///
+/// Older systems and some third-party providers only implement `ISpellChecker`,
+/// which cannot remove words.
+struct NativeChecker {
+    base: ISpellChecker,
+    removable: Option<ISpellChecker2>,
+}
+
+#[derive(Default)]
+struct WideBuffers {
+    /// The text being checked.
+    text: WideBuffer,
+    /// A single word, e.g. the misspelled word to get suggestions for.
+    word: WideBuffer,
+}
+
+// SAFETY: COM is initialized in the multithreaded apartment, so the objects can be moved
+// to another thread. The spell checker is kept on its owner thread, see `crate::owner`.
+unsafe impl Send for WindowsSpellChecker {}
+
+/// Keeps COM initialized on the current thread, uninitializing it when dropped.
+///
+/// Drop it after the COM objects of the thread are released, it cannot leave the thread.
+pub struct ComGuard(PhantomData<*const ()>);
+
+impl Drop for ComGuard {
+    fn drop(&mut self) {
+        unsafe { CoUninitialize() };
+    }
+}
+
+/// Initialize COM in the multithreaded apartment for the current thread.
+///
+/// Each initialization is balanced by a `CoUninitialize` once the guard is dropped.
+pub fn init_thread() -> EjaanError<ComGuard> {
+    unsafe { CoInitializeEx(None, COINIT_MULTITHREADED).ok()? };
+    Ok(ComGuard(PhantomData))
 }
 
 impl WindowsSpellChecker {
     /// Create a new instance of the Windows spell checker.
+    ///
+    /// COM must be initialized on the current thread, see `init_thread()`.
     pub fn new() -> EjaanError<Self> {
-        unsafe { CoInitializeEx(None, COINIT_MULTITHREADED).ok()? };
-
         let inner: ISpellCheckerFactory =
-            unsafe { CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_ALL)? };
+            unsafe { CoCreateInstance(&SpellCheckerFactory, None, CLSCTX_ALL) }
+                .map_err(Self::factory_error)?;
 
         let mut locale_name = [0u16; 256];
         unsafe { GetUserDefaultLocaleName(&mut locale_name) };
@@ -62,25 +113,119 @@ impl WindowsSpellChecker {
             inner,
             checker,
             locale,
+            buffers: RefCell::default(),
         })
     }
 
+    /// Map the error from creating the spell checker factory.
+    ///
+    /// The Spell Checking API is not shipped on Windows Server Core and some N editions,
+    /// in which case `CoCreateInstance` fails because the class is not registered.
+    fn factory_error(err: windows::core::Error) -> Error {
+        match err.code() {
+            REGDB_E_CLASSNOTREG | CLASS_E_CLASSNOTAVAILABLE | E_NOINTERFACE => Error::with_kind(
+                ErrorKind::BackendUnavailable,
+                format!(
+                    "Windows Spell Checking API is not available on this system (code: {}). \
+                     Windows Server Core and N editions may not ship it, \
+                     install the Desktop Experience or Media Feature Pack to enable it, \
+                     or use the dictionaryFallback option to check with the loaded dictionaries.",
+                    err.code().0
+                ),
+            ),
+            _ => err.into(),
+        }
+    }
+
+    /// Get the identifier and the localized name of the spell checking provider, e.g. `MsSpell`.
+    pub fn provider(&self) -> EjaanError<(String, String)> {
+        let read = |value: PWSTR| {
+            let string = unsafe { value.to_string() };
+            unsafe { CoTaskMemFree(Some(value.as_ptr() as *mut _)) };
+            string.map_err(|e| {
+                Error::new(format!("Failed to convert provider PWSTR to string: {}", e))
+            })
+        };
+        let id = read(unsafe { self.checker.base.Id()? })?;
+        let name = read(unsafe { self.checker.base.LocalizedName()? })?;
+        Ok((id, name))
+    }
+
     fn make_spell_checker(
         factory: &ISpellCheckerFactory,
         locale: PCWSTR,
-    ) -> EjaanError<ISpellChecker2> {
-        let checker = unsafe { factory.CreateSpellChecker(locale)? };
+    ) -> EjaanError<NativeChecker> {
+        let base = unsafe { factory.CreateSpellChecker(locale)? };
+        // Fall back to the base interface instead of refusing to work at all
+        let removable = base.cast::<ISpellChecker2>().ok();
 
-        Ok(checker.cast::<ISpellChecker2>()?)
+        Ok(NativeChecker { base, removable })
     }
 
-    fn common_spellcheck(&self, word: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
-        let mut tokens = Vec::new();
+    fn common_spellcheck(
+        &self,
+        word: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let mut buffers = self.buffers.borrow_mut();
+        buffers.text.set_str(word);
+        self.check_buffered(&mut buffers, options)
+    }
+
+    /// Get the suggestions for the misspelled word in the buffer.
+    fn suggest(&self, misspelled: &WideBuffer) -> EjaanError<Vec<String>> {
+        let suggestions = unsafe {
+            self.checker
+                .base
+                .Suggest(PCWSTR::from_raw(misspelled.as_ptr()))?
+        };
+
+        let mut tokenized_suggest = Vec::new();
+        loop {
+            let mut suggestion = [PWSTR::null()];
+            unsafe {
+                _ = suggestions.Next(&mut suggestion, None);
+            }
 
-        let wide_word = HSTRING::from(word);
+            if suggestion[0].is_null() {
+                unsafe { CoTaskMemFree(Some(suggestion[0].as_ptr() as *mut _)) };
+                break;
+            }
+
+            let suggest_str = unsafe {
+                suggestion[0].to_string().map_err(|e| {
+                    crate::utils::Error::new(format!(
+                        "Failed to convert suggestion PWSTR to string: {}",
+                        e
+                    ))
+                })?
+            };
+            tokenized_suggest.push(suggest_str);
+
+            unsafe { CoTaskMemFree(Some(suggestion[0].as_ptr() as *mut _)) };
+        }
+        Ok(tokenized_suggest)
+    }
+
+    /// Check the text in the buffers, the word buffer is reused for the suggestions.
+    fn check_buffered(
+        &self,
+        buffers: &mut WideBuffers,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let mut tokens = Vec::new();
+        let WideBuffers {
+            text,
+            word: misspelled,
+        } = buffers;
 
-        let errors = unsafe { self.checker.Check(&wide_word)? };
+        let errors = unsafe { self.checker.base.Check(PCWSTR::from_raw(text.as_ptr()))? };
         loop {
+            if options.is_full(tokens.len()) {
+                // Each remaining error would cost another call for the suggestions
+                break;
+            }
+
             let mut error = None;
             if unsafe { errors.Next(&mut error) } != S_OK {
                 break;
@@ -94,22 +239,23 @@ impl WindowsSpellChecker {
             let length = unsafe { err.Length()? };
 
             let range = start_index as usize..(start_index + length) as usize;
-            let substring = wide_word
-                .get(range.clone())
-                .ok_or(crate::utils::Error::new(format!(
-                    "Failed to get substring for range: {:?}",
-                    range
-                )))?;
+            let wide_substring =
+                text.as_wide()
+                    .get(range.clone())
+                    .ok_or(crate::utils::Error::new(format!(
+                        "Failed to get substring for range: {:?}",
+                        range
+                    )))?;
             let action = unsafe { err.CorrectiveAction()? };
 
-            let substring = String::from_utf16_lossy(substring)
+            let substring = String::from_utf16_lossy(wide_substring)
                 .trim_end_matches('\0')
                 .to_string();
 
             let token = Token::new(
                 start_index as usize,
                 (start_index + length) as usize - 1,
-                substring.to_string(),
+                substring,
             );
             match action {
                 CORRECTIVE_ACTION_DELETE | CORRECTIVE_ACTION_NONE => {
@@ -117,34 +263,16 @@ impl WindowsSpellChecker {
                     continue;
                 }
                 CORRECTIVE_ACTION_GET_SUGGESTIONS => {
-                    let suggestions = unsafe { self.checker.Suggest(&HSTRING::from(substring))? };
-
-                    let mut tokenized_suggest = Vec::new();
-                    loop {
-                        let mut suggestion = [PWSTR::null()];
-                        unsafe {
-                            _ = suggestions.Next(&mut suggestion, None);
-                        }
-
-                        if suggestion[0].is_null() {
-                            unsafe { CoTaskMemFree(Some(suggestion[0].as_ptr() as *mut _)) };
-                            break;
-                        }
-
-                        let suggest_str = unsafe {
-                            suggestion[0].to_string().map_err(|e| {
-                                crate::utils::Error::new(format!(
-                                    "Failed to convert suggestion PWSTR to string: {}",
-                                    e
-                                ))
-                            })?
-                        };
-                        tokenized_suggest.push(suggest_str);
-
-                        unsafe { CoTaskMemFree(Some(suggestion[0].as_ptr() as *mut _)) };
-                    }
-
-                    tokens.push(TokenWithSuggestions::new(token, tokenized_suggest));
+                    // Reuse the original code units instead of encoding the word again
+                    misspelled.set_wide(wide_substring);
+                    let tokenized_suggest = options
+                        .timings()
+                        .time(Phase::Suggestions, || self.suggest(misspelled))?;
+
+                    tokens.push(
+                        TokenWithSuggestions::new(token, tokenized_suggest)
+                            .with_language(Some(self.locale.clone())),
+                    );
                 }
                 CORRECTIVE_ACTION_REPLACE => {
                     let repl = unsafe { err.Replacement()? };
@@ -160,7 +288,10 @@ impl WindowsSpellChecker {
 
                     unsafe { CoTaskMemFree(Some(repl.as_ptr() as *mut _)) };
 
-                    tokens.push(TokenWithSuggestions::new(token, vec![repl_str]))
+                    tokens.push(
+                        TokenWithSuggestions::new(token, vec![repl_str])
+                            .with_language(Some(self.locale.clone())),
+                    )
                 }
                 _ => {}
             }
@@ -198,41 +329,121 @@ impl SpellCheckerImpl for WindowsSpellChecker {
     }
 
     fn check_word(&self, word: &str) -> EjaanError<bool> {
-        let tokens = self.common_spellcheck(word)?;
+        // A single misspelling is enough to know the word is wrong
+        let tokens =
+            self.common_spellcheck(word, &CheckOptions::new().with_max_results(Some(1)))?;
         Ok(tokens.is_empty())
     }
 
     fn check_sentences(&self, sentence: &str) -> EjaanError<Vec<TokenWithSuggestions>> {
-        self.common_spellcheck(sentence)
+        self.common_spellcheck(sentence, &CheckOptions::new())
+    }
+
+    fn check_sentences_with(
+        &self,
+        sentence: &str,
+        options: &CheckOptions,
+    ) -> EjaanError<Vec<TokenWithSuggestions>> {
+        self.common_spellcheck(sentence, options)
+    }
+
+    fn check_sentences_utf16(&self, sentence: &[u16]) -> EjaanError<Vec<TokenWithSuggestions>> {
+        let mut buffers = self.buffers.borrow_mut();
+        buffers.text.set_wide(sentence);
+        self.check_buffered(&mut buffers, &CheckOptions::new())
     }
 
     fn add_word(&self, word: &str) -> EjaanError<()> {
-        let wide_word = word.encode_utf16().collect::<Vec<u16>>();
-        let ptr = PCWSTR::from_raw(wide_word.as_ptr());
+        let mut buffers = self.buffers.borrow_mut();
+        buffers.word.set_str(word);
+        let ptr = PCWSTR::from_raw(buffers.word.as_ptr());
         // > Use Ignore instead of Add.
         // Since according to MSFT themselves, Ignore will only happens
         // only on the current checker instances itself rather than updating
         // globally.
-        unsafe { self.checker.Ignore(ptr) }?;
+        unsafe { self.checker.base.Ignore(ptr) }?;
 
         Ok(())
     }
 
     fn remove_word(&self, word: &str) -> EjaanError<()> {
-        let wide_word = word.encode_utf16().collect::<Vec<u16>>();
-        let ptr = PCWSTR::from_raw(wide_word.as_ptr());
+        let Some(checker) = &self.checker.removable else {
+            return Err(Error::new(
+                "Removing words is not supported by this spell checker provider, \
+                 ISpellChecker2 is not available",
+            ));
+        };
+
+        let mut buffers = self.buffers.borrow_mut();
+        buffers.word.set_str(word);
+        let ptr = PCWSTR::from_raw(buffers.word.as_ptr());
 
-        unsafe { self.checker.Remove(ptr)? };
+        unsafe { checker.Remove(ptr)? };
 
         Ok(())
     }
 
+    fn native_handle(&self) -> Option<*mut std::ffi::c_void> {
+        match &self.checker.removable {
+            Some(checker) => Some(checker.as_raw()),
+            None => Some(self.checker.base.as_raw()),
+        }
+    }
+
+    fn native(&self) -> &dyn SpellCheckerImpl {
+        self
+    }
+
+    fn fork(&self) -> EjaanError<Box<dyn SpellCheckerImpl>> {
+        // Share the factory, but create a new checker for our own ignored words
+        let mut locale = WideBuffer::new();
+        locale.set_str(&self.locale);
+        let checker = Self::make_spell_checker(&self.inner, PCWSTR::from_raw(locale.as_ptr()))?;
+
+        Ok(Box::new(Self {
+            inner: self.inner.clone(),
+            checker,
+            locale: self.locale.clone(),
+            buffers: RefCell::default(),
+        }))
+    }
+
+    fn reset_session(&mut self) -> EjaanError<()> {
+        // The ignored words live in the checker instance, so recreate it
+        let mut locale = WideBuffer::new();
+        locale.set_str(&self.locale);
+        self.checker = Self::make_spell_checker(&self.inner, PCWSTR::from_raw(locale.as_ptr()))?;
+
+        Ok(())
+    }
+
+    fn dictionary_info(&self) -> EjaanError<DictionaryInfo> {
+        // Added words are ignored per checker instance, see `add_word`
+        let backend = match self.checker.removable {
+            Some(_) => "ISpellChecker2",
+            None => "ISpellChecker",
+        };
+        Ok(DictionaryInfo::new(
+            backend,
+            DictionaryStorage::Session,
+            None,
+            false,
+        ))
+    }
+
+    fn capabilities(&self) -> EjaanError<Capabilities> {
+        Ok(Capabilities::new(self.checker.removable.is_some(), false))
+    }
+
     fn get_language(&self) -> EjaanError<Option<String>> {
         Ok(Some(self.locale.clone()))
     }
 
     fn set_language(&mut self, language: &str) -> EjaanError<bool> {
-        let locale = PCWSTR::from_raw(language.encode_utf16().collect::<Vec<u16>>().as_ptr());
+        // Keep the wide string alive until the checker is created
+        let mut wide_language = WideBuffer::new();
+        wide_language.set_str(language);
+        let locale = PCWSTR::from_raw(wide_language.as_ptr());
 
         let ret = unsafe { self.inner.IsSupported(locale)? };
         if ret.as_bool() {
@@ -253,6 +464,7 @@ mod tests {
 
     #[test]
     fn test_simple_spellcheck() {
+        let _com = init_thread().unwrap();
         let spell_checker = WindowsSpellChecker::new().unwrap();
         let word = "test";
         let is_correct = spell_checker
@@ -267,6 +479,7 @@ mod tests {
 
     #[test]
     fn test_simple_sentences() {
+        let _com = init_thread().unwrap();
         let spell_checker = WindowsSpellChecker::new().unwrap();
         let sentence = "This is a test sentence.";
         let tokens = spell_checker
@@ -282,6 +495,7 @@ mod tests {
 
     #[test]
     fn test_simple_sentences_with_typos() {
+        let _com = init_thread().unwrap();
         let spell_checker = WindowsSpellChecker::new().unwrap();
         let sentence = "This is a tset sentence.";
         let tokens = spell_checker
@@ -310,6 +524,7 @@ mod tests {
 
     #[test]
     fn test_utf_8_characters() {
+        let _com = init_thread().unwrap();
         let spell_checker = WindowsSpellChecker::new().unwrap();
         let word = "“Test...”";
 
@@ -333,4 +548,64 @@ mod tests {
             "Spell checking should return misspelled words for UTF-8 characters"
         );
     }
+
+    #[test]
+    fn test_fork() {
+        let _com = init_thread().unwrap();
+        let spell_checker = WindowsSpellChecker::new().unwrap();
+        let sentence = "This is a tset sentence.";
+
+        let forked = spell_checker.fork().expect("Failed to fork spell checker");
+        forked.add_word("tset").expect("Failed to add word");
+
+        let tokens = forked
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert!(tokens.is_empty(), "Expected the added word to be ignored");
+
+        let tokens = spell_checker
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert_eq!(
+            tokens.len(),
+            1,
+            "Expected the added word to only affect the forked instance"
+        );
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let _com = init_thread().unwrap();
+        let spell_checker = WindowsSpellChecker::new().unwrap();
+        let capabilities = spell_checker
+            .capabilities()
+            .expect("Failed to get capabilities");
+
+        assert_eq!(
+            capabilities.remove_words(),
+            spell_checker.remove_word("tset").is_ok(),
+            "Expected removing words to only fail without ISpellChecker2"
+        );
+    }
+
+    #[test]
+    fn test_reset_session() {
+        let _com = init_thread().unwrap();
+        let mut spell_checker = WindowsSpellChecker::new().unwrap();
+        let sentence = "This is a tset sentence.";
+
+        spell_checker.add_word("tset").expect("Failed to add word");
+        spell_checker
+            .reset_session()
+            .expect("Failed to reset session");
+
+        let tokens = spell_checker
+            .check_sentences(sentence)
+            .expect("Failed to check sentences");
+        assert_eq!(
+            tokens.len(),
+            1,
+            "Expected the added word to be cleared after reset"
+        );
+    }
 }
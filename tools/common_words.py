@@ -0,0 +1,77 @@
+import argparse
+import re
+import sys
+import urllib.request
+from pathlib import Path
+
+ROOT_DIR = Path(__file__).parent.parent.absolute()
+DATA_DIR = ROOT_DIR / "data" / "common"
+
+# The word frequencies of the OpenSubtitles 2018 corpus, CC-BY-SA 4.0
+FREQUENCY_URL = "https://raw.githubusercontent.com/hermitdave/FrequencyWords/master/content/2018/{language}/{language}_50k.txt"  # noqa: E501
+# The LibreOffice Hunspell dictionaries, to drop the words only valid as proper nouns
+DICTIONARY_URLS = {
+    "en": "https://raw.githubusercontent.com/LibreOffice/dictionaries/master/en/en_US.dic",
+    "id": "https://raw.githubusercontent.com/LibreOffice/dictionaries/master/id/id_ID.dic",
+}
+# A word of letters, with the apostrophes only inside of it like the default tokenizer
+WORD = re.compile(r"^[^\W\d_]+(?:['’][^\W\d_]+)*$")
+
+parser = argparse.ArgumentParser(description="Generate the bundled common word lists")
+parser.add_argument("languages", nargs="*", default=sorted(DICTIONARY_URLS), help="The languages to generate")
+parser.add_argument("--size", type=int, default=50_000, help="The maximum number of words of each list")
+parser.add_argument(
+    "--dry-run",
+    action="store_true",
+    help="Generate the lists but don't write them to the files",
+)
+args = parser.parse_args()
+
+
+def download(url: str) -> str:
+    print(f"Downloading {url}", file=sys.stderr)
+    with urllib.request.urlopen(url) as response:
+        return response.read().decode("utf-8", errors="replace")
+
+
+def dictionary_stems(content: str) -> set[str]:
+    # The first line is the number of entries, then `stem/FLAGS`
+    stems = set()
+    for line in content.splitlines()[1:]:
+        stem = line.split("/", 1)[0].strip()
+        if stem:
+            stems.add(stem)
+    return stems
+
+
+def common_words(language: str) -> list[str]:
+    stems = dictionary_stems(download(DICTIONARY_URLS[language]))
+    words = []
+    seen = set()
+    for line in download(FREQUENCY_URL.format(language=language)).splitlines():
+        word = line.split(" ", 1)[0].strip().lower()
+        if not WORD.match(word) or word in seen:
+            continue
+        seen.add(word)
+        # The corpus is lowercased, the dictionary tells the proper nouns apart, e.g. `Jakarta`
+        if word not in stems and word.capitalize() in stems:
+            # `I` is the only form of the pronoun, it is kept as is
+            if len(word) == 1:
+                words.append(word.upper())
+            continue
+        words.append(word)
+        if len(words) >= args.size:
+            break
+    return words
+
+
+for language in args.languages:
+    if language not in DICTIONARY_URLS:
+        print(f"Unknown language: {language}", file=sys.stderr)
+        sys.exit(1)
+
+    words = common_words(language)
+    print(f"{language}: {len(words)} words", file=sys.stderr)
+    if args.dry_run:
+        continue
+    (DATA_DIR / f"{language}.txt").write_text("\n".join(words) + "\n", encoding="utf-8")